@@ -1,21 +1,42 @@
+use crate::errors::{ManagerAccessError, QueryError};
 use crate::transactions::balance_manager::{self, BalanceManagerContract};
 use crate::transactions::deep_book::DeepBookContract;
 use crate::transactions::deep_book_admin::DeepBookAdminContract;
-use crate::transactions::flash_loans::FlashLoanContract;
+use crate::transactions::flash_loans::{FlashLoanContract, FlashLoanPlan};
 use crate::transactions::governance::GovernanceContract;
-use crate::types::{Account, BalanceManager, Coin, OrderDeepPrice, Pool, ScaledDeepPrice};
-use crate::utils::config::{DeepBookConfig, FLOAT_SCALAR};
+use crate::types::{
+    Account, BalanceManager, Balances, BookParams, BookParamsDiff, CapKind, ClientOrderId, Coin,
+    FlashLoanSimulation, GovernanceState, LadderParams, LegSimulation, ManagerBalanceReport,
+    ManagerCap, MoveCoin, OpenOrder, Order, OrderDeepPrice, OrderFill, OrderPlacement,
+    PlaceLimitOrderParams, Pool, PoolAccountBalances, Proposal, ScaledBalances, ScaledDeepPrice,
+    SwapDirection, TimeInForce, TradeParams, UnsignedTransaction,
+};
+use crate::utils::config::{DeepBookConfig, FLOAT_SCALAR, MAX_EVENT_PAGES};
+use crate::utils::offline::{describe_transaction, encode_transaction_data};
+use crate::utils::{
+    decode_order_id, get_clock_timestamp_ms, get_object_json, json_field, json_u64, json_u128,
+    move_abort, select_gas_coin,
+};
 use anyhow::{Context, Result, anyhow};
 use std::any;
 use std::collections::HashMap;
+use std::str::FromStr;
 use sui_sdk::SuiClient;
-use sui_sdk::rpc_types::DevInspectResults;
-use sui_sdk::types::base_types::SuiAddress;
+use sui_sdk::rpc_types::{
+    DevInspectArgs, DevInspectResults, EventFilter, ObjectChange, SuiExecutionStatus,
+    SuiObjectDataOptions, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+};
+use sui_sdk::types::TypeTag;
+use sui_sdk::types::base_types::{ObjectID, ObjectRef, SuiAddress};
 use sui_sdk::types::collection_types::VecSet;
+use sui_sdk::types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_sdk::types::event::EventID;
 use sui_sdk::types::id::ID;
+use sui_sdk::types::object::Owner;
+use sui_sdk::types::parse_sui_struct_tag;
 use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_sdk::types::storage::AccountOwnedObjectInfo;
-use sui_sdk::types::transaction::TransactionKind;
+use sui_sdk::types::transaction::{Argument, TransactionData, TransactionKind};
 
 #[derive(Clone)]
 pub struct DeepBookClient {
@@ -67,6 +88,10 @@ impl DeepBookClient {
         }
     }
 
+    pub fn config(&self) -> &DeepBookConfig {
+        &self.config
+    }
+
     pub async fn check_manager_balance(
         &self,
         manager_key: &str,
@@ -129,6 +154,159 @@ impl DeepBookClient {
         Ok((coin_type.to_string(), adjusted_balance))
     }
 
+    /// Balance of every configured coin held by the manager, read in a
+    /// single dev-inspect
+    pub async fn get_manager_balances(&self, manager_key: &str) -> Result<HashMap<String, f64>> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let coin_keys = self.config.coin_keys();
+
+        for coin_key in &coin_keys {
+            self.balance_manager
+                .check_manager_balance(&mut ptb, manager_key, coin_key)
+                .await
+                .context("Failed to create balance check transaction")?;
+        }
+
+        let resp = self
+            .client
+            .read_api()
+            .dev_inspect_transaction_block(
+                self.sender_address,
+                TransactionKind::programmable(ptb.finish()),
+                None,
+                None,
+                None,
+            )
+            .await
+            .context("Failed to execute dev inspect transaction block")?;
+
+        let DevInspectResults {
+            results, effects, ..
+        } = resp;
+
+        let results = results.ok_or_else(|| {
+            anyhow!(
+                "No results returned for balance check, effects: {:?}",
+                effects
+            )
+        })?;
+
+        coin_keys
+            .iter()
+            .zip(results.iter())
+            .map(|(coin_key, result)| {
+                let (value_bytes, _type_tag) = result
+                    .return_values
+                    .first()
+                    .ok_or_else(|| anyhow!("No return value found for {} balance", coin_key))?;
+                let balance: u64 = bcs::from_bytes(value_bytes)
+                    .context("Failed to decode balance from transaction response")?;
+                let scalar = self.config.get_coin(coin_key).scalar as f64;
+                Ok((coin_key.clone(), balance as f64 / scalar))
+            })
+            .collect()
+    }
+
+    /// Manager balances plus, for each pool the manager trades in, funds
+    /// locked by open orders and settled/owed amounts awaiting settlement
+    pub async fn get_manager_balance_report(
+        &self,
+        manager_key: &str,
+    ) -> Result<ManagerBalanceReport> {
+        let balances = self.get_manager_balances(manager_key).await?;
+
+        let mut pools = HashMap::new();
+        for pool_key in self.config.pool_keys() {
+            // `pool::account` aborts when the manager never traded in the pool
+            if !self.account_exists(&pool_key, manager_key).await? {
+                continue;
+            }
+            let account = self.get_account(&pool_key, manager_key).await?;
+            let locked = self.get_locked_balances(&pool_key, manager_key).await?;
+
+            pools.insert(
+                pool_key.clone(),
+                PoolAccountBalances {
+                    base_coin: self.config.get_pool(&pool_key).base_coin.to_string(),
+                    quote_coin: self.config.get_pool(&pool_key).quote_coin.to_string(),
+                    locked: self.scale_balances(&pool_key, &locked),
+                    settled: self.scale_balances(&pool_key, &account.settled_balances),
+                    owed: self.scale_balances(&pool_key, &account.owed_balances),
+                    unclaimed_rebates: self.scale_balances(&pool_key, &account.unclaimed_rebates),
+                },
+            );
+        }
+
+        Ok(ManagerBalanceReport { balances, pools })
+    }
+
+    /// Base, quote and DEEP locked in `pool_key`, in raw units
+    async fn get_locked_balances(&self, pool_key: &str, manager_key: &str) -> Result<Balances> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+
+        self.deep_book
+            .locked_balance(&mut ptb, pool_key, manager_key)
+            .await
+            .context("Failed to create locked balance retrieval transaction")?;
+
+        let resp = self
+            .client
+            .read_api()
+            .dev_inspect_transaction_block(
+                self.sender_address,
+                TransactionKind::programmable(ptb.finish()),
+                None,
+                None,
+                None,
+            )
+            .await
+            .context("Failed to execute dev inspect transaction block")?;
+
+        let results = resp
+            .results
+            .ok_or_else(|| anyhow!("No results returned for locked balance check"))?;
+        let return_values = &results
+            .first()
+            .ok_or_else(|| anyhow!("No return values found in transaction results"))?
+            .return_values;
+
+        let value = |index: usize| -> Result<u64> {
+            let (value_bytes, _type_tag) = return_values
+                .get(index)
+                .ok_or_else(|| anyhow!("Missing locked balance return value {}", index))?;
+            Ok(bcs::from_bytes(value_bytes)?)
+        };
+
+        Ok(Balances {
+            base: value(0)?,
+            quote: value(1)?,
+            deep: value(2)?,
+        })
+    }
+
+    /// Base, quote and DEEP locked in `pool_key`, in human units. Zero when
+    /// the manager has no account in the pool.
+    pub async fn get_scaled_locked_balances(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<ScaledBalances> {
+        if !self.account_exists(pool_key, manager_key).await? {
+            return Ok(ScaledBalances::default());
+        }
+        let locked = self.get_locked_balances(pool_key, manager_key).await?;
+        Ok(self.scale_balances(pool_key, &locked))
+    }
+
+    fn scale_balances(&self, pool_key: &str, balances: &Balances) -> ScaledBalances {
+        let pool = self.config.get_pool(pool_key);
+        ScaledBalances {
+            base: balances.base as f64 / self.config.get_coin(&pool.base_coin).scalar as f64,
+            quote: balances.quote as f64 / self.config.get_coin(&pool.quote_coin).scalar as f64,
+            deep: balances.deep as f64 / self.config.get_coin("DEEP").scalar as f64,
+        }
+    }
+
     pub async fn get_manager_owner(&self, manager_key: &str) -> Result<SuiAddress> {
         let mut ptb: ProgrammableTransactionBuilder = ProgrammableTransactionBuilder::new();
 
@@ -453,6 +631,84 @@ impl DeepBookClient {
         Ok((tick_size_scaled, lot_size_scaled, min_size_scaled))
     }
 
+    /// Dry-run the admin adjustments needed to move `pool_key` to `new`
+    /// and return the book params before and after. Requires the admin cap.
+    pub async fn preview_book_params(
+        &self,
+        pool_key: &str,
+        new: BookParams,
+    ) -> Result<BookParamsDiff> {
+        let (tick_size, lot_size, min_size) = self.get_pool_book_params(pool_key).await?;
+        let old = BookParams {
+            tick_size,
+            lot_size,
+            min_size,
+        };
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        if new.tick_size != old.tick_size {
+            self.deep_book_admin
+                .adjust_tick_size_admin(&mut ptb, pool_key, new.tick_size)
+                .await?;
+        }
+        if new.lot_size != old.lot_size || new.min_size != old.min_size {
+            self.deep_book_admin
+                .adjust_min_lot_size_admin(&mut ptb, pool_key, new.lot_size, new.min_size)
+                .await?;
+        }
+        self.deep_book
+            .pool_book_params(&mut ptb, pool_key)
+            .await
+            .context("Failed to create pool book params retrieval transaction")?;
+
+        let resp = self
+            .client
+            .read_api()
+            .dev_inspect_transaction_block(
+                self.sender_address,
+                TransactionKind::programmable(ptb.finish()),
+                None,
+                None,
+                None,
+            )
+            .await
+            .context("Failed to execute dev inspect transaction block")?;
+
+        if let Some(error) = resp.error {
+            return Err(anyhow!("Book params adjustment would fail: {}", error));
+        }
+
+        let results = resp
+            .results
+            .ok_or_else(|| anyhow!("No results returned for book params preview"))?;
+        let return_values = &results
+            .last()
+            .ok_or_else(|| anyhow!("No return values found in transaction results"))?
+            .return_values;
+        if return_values.len() != 3 {
+            return Err(anyhow!(
+                "Unexpected number of return values for pool book params: expected 3, got {}",
+                return_values.len()
+            ));
+        }
+
+        let pool = self.config.get_pool(pool_key);
+        let base_scalar = self.config.get_coin(&pool.base_coin).scalar as f64;
+        let quote_scalar = self.config.get_coin(&pool.quote_coin).scalar as f64;
+        let tick_size: u64 = bcs::from_bytes(&return_values[0].0)?;
+        let lot_size: u64 = bcs::from_bytes(&return_values[1].0)?;
+        let min_size: u64 = bcs::from_bytes(&return_values[2].0)?;
+
+        Ok(BookParamsDiff {
+            old,
+            new: BookParams {
+                tick_size: tick_size as f64 * base_scalar / quote_scalar / FLOAT_SCALAR,
+                lot_size: lot_size as f64 / base_scalar,
+                min_size: min_size as f64 / base_scalar,
+            },
+        })
+    }
+
     pub async fn get_pool_trade_params(&self, pool_key: &str) -> Result<(f64, f64, f64)> {
         let mut ptb = ProgrammableTransactionBuilder::new();
 
@@ -540,15 +796,16 @@ impl DeepBookClient {
             .context("Failed to execute dev inspect transaction block")?;
 
         let DevInspectResults {
-            results, effects, ..
+            results,
+            effects,
+            error,
+            ..
         } = resp;
 
-        println!("Results: {:?}", results);
-        let results = results.ok_or_else(|| {
-            anyhow!(
-                "No results returned for pool ID check, effects: {:?}",
-                effects
-            )
+        // `get_pool_id_by_asset` aborts when the pair is not registered
+        let results = results.ok_or_else(|| QueryError::Aborted {
+            query: "get_pool_id_by_asset",
+            error: error.unwrap_or_else(|| format!("{:?}", effects.status())),
         })?;
 
         let return_values = results
@@ -661,9 +918,6 @@ impl DeepBookClient {
             .return_values
             .as_slice(); // Get the entire array
 
-        // 🚨 Debugging Output: Check return values 🚨
-        println!("Return values count: {}", return_values.len());
-
         // Ensure we have exactly 4 vectors returned
         if return_values.len() != 4 {
             return Err(anyhow!(
@@ -685,6 +939,36 @@ impl DeepBookClient {
         Ok((bid_prices, bid_sizes, ask_prices, ask_sizes))
     }
 
+    /// `get_level2_ticks_from_mid` as (price, quantity) levels in human
+    /// units, best first
+    pub async fn get_scaled_level2_ticks_from_mid(
+        &self,
+        pool_key: &str,
+        tick_from_mid: u64,
+    ) -> Result<(Vec<(f64, f64)>, Vec<(f64, f64)>)> {
+        let (bid_prices, bid_sizes, ask_prices, ask_sizes) = self
+            .get_level2_ticks_from_mid(pool_key, tick_from_mid)
+            .await?;
+
+        let pool = self.config.get_pool(pool_key);
+        let base_scalar = self.config.get_coin(&pool.base_coin).scalar as f64;
+        let quote_scalar = self.config.get_coin(&pool.quote_coin).scalar as f64;
+        let levels = |prices: Vec<u64>, sizes: Vec<u64>| -> Vec<(f64, f64)> {
+            prices
+                .into_iter()
+                .zip(sizes)
+                .map(|(price, size)| {
+                    (
+                        price as f64 * base_scalar / (FLOAT_SCALAR * quote_scalar),
+                        size as f64 / base_scalar,
+                    )
+                })
+                .collect()
+        };
+
+        Ok((levels(bid_prices, bid_sizes), levels(ask_prices, ask_sizes)))
+    }
+
     pub async fn get_level2_range(
         &self,
         pool_key: &str,
@@ -803,6 +1087,147 @@ impl DeepBookClient {
         Ok(open_orders)
     }
 
+    /// Whether `manager_key` has an account in `pool_key`. Account queries
+    /// such as `get_account_open_orders` abort without one.
+    pub async fn account_exists(&self, pool_key: &str, manager_key: &str) -> Result<bool> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        self.deep_book
+            .account_exists(&mut ptb, pool_key, manager_key)
+            .await
+            .context("Failed to create account exists transaction")?;
+
+        let resp = self
+            .client
+            .read_api()
+            .dev_inspect_transaction_block(
+                self.sender_address,
+                TransactionKind::programmable(ptb.finish()),
+                None,
+                None,
+                None,
+            )
+            .await
+            .context("Failed to execute dev inspect transaction block")?;
+
+        let DevInspectResults {
+            results, effects, ..
+        } = resp;
+        let results = results.ok_or_else(|| {
+            anyhow!(
+                "No results returned for account exists, effects: {:?}",
+                effects
+            )
+        })?;
+        let (value_bytes, _type_tag) = results
+            .first()
+            .ok_or_else(|| anyhow!("No return values found in transaction results"))?
+            .return_values
+            .first()
+            .ok_or_else(|| anyhow!("No return value found for account exists"))?;
+
+        bcs::from_bytes(value_bytes).context("Failed to decode account exists from response")
+    }
+
+    /// Open orders of a manager in a pool, scaled to human units
+    pub async fn get_open_orders(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<Vec<OpenOrder>> {
+        let order_ids = self
+            .get_account_open_orders(pool_key, manager_key)
+            .await?
+            .contents;
+        if order_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        self.deep_book
+            .get_orders(&mut ptb, pool_key, order_ids)
+            .await
+            .context("Failed to create get orders transaction")?;
+
+        let resp = self
+            .client
+            .read_api()
+            .dev_inspect_transaction_block(
+                self.sender_address,
+                TransactionKind::programmable(ptb.finish()),
+                None,
+                None,
+                None,
+            )
+            .await
+            .context("Failed to execute dev inspect transaction block")?;
+
+        let DevInspectResults {
+            results, effects, ..
+        } = resp;
+        let results = results
+            .ok_or_else(|| anyhow!("No results returned for get orders, effects: {:?}", effects))?;
+        let (value_bytes, _) = results
+            .first()
+            .and_then(|result| result.return_values.first())
+            .ok_or_else(|| anyhow!("No return value found for get orders"))?;
+        let orders: Vec<Order> = bcs::from_bytes(value_bytes).context("Failed to decode orders")?;
+
+        let pool = self.config.get_pool(pool_key);
+        let base_scalar = self.config.get_coin(&pool.base_coin).scalar as f64;
+        let quote_scalar = self.config.get_coin(&pool.quote_coin).scalar as f64;
+
+        Ok(orders
+            .into_iter()
+            .map(|order| {
+                let (is_bid, price, _) = decode_order_id(order.order_id);
+                OpenOrder {
+                    order_id: order.order_id,
+                    client_order_id: ClientOrderId(order.client_order_id),
+                    is_bid,
+                    price: price as f64 * base_scalar / (FLOAT_SCALAR * quote_scalar),
+                    quantity: (order.quantity - order.filled_quantity) as f64 / base_scalar,
+                    filled_quantity: order.filled_quantity as f64 / base_scalar,
+                    expire_timestamp: order.expire_timestamp,
+                }
+            })
+            .collect())
+    }
+
+    /// Build a post-only ladder aligned to the pool's current tick and lot
+    /// sizes, all under one trade proof. Returns the orders added to `ptb`.
+    pub async fn place_ladder(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        params: &LadderParams,
+    ) -> Result<Vec<PlaceLimitOrderParams>> {
+        let (tick_size, lot_size, min_size) = self.get_pool_book_params(&params.pool_key).await?;
+        let book = BookParams {
+            tick_size,
+            lot_size,
+            min_size,
+        };
+        self.deep_book.place_ladder(ptb, params, &book).await
+    }
+
+    /// Chain time in ms, from the shared `Clock`
+    pub async fn get_chain_time_ms(&self) -> Result<u64> {
+        get_clock_timestamp_ms(&self.client).await
+    }
+
+    /// Build `place_limit_order` with its order type and expiration taken
+    /// from `time_in_force` at current chain time. Orders that would already
+    /// be expired are refused before reaching the chain.
+    pub async fn place_limit_order_with_time_in_force(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        params: &PlaceLimitOrderParams,
+        time_in_force: TimeInForce,
+    ) -> Result<()> {
+        let now_ms = self.get_chain_time_ms().await?;
+        let params = params.clone().with_time_in_force(time_in_force, now_ms)?;
+        self.deep_book.place_limit_order(ptb, &params).await
+    }
+
     pub async fn get_quantity_out(
         &self,
         pool_key: &str,
@@ -1061,14 +1486,16 @@ impl DeepBookClient {
             .context("Failed to execute mid price transaction")?;
 
         let DevInspectResults {
-            results, effects, ..
+            results,
+            effects,
+            error,
+            ..
         } = resp;
 
-        let results = results.ok_or_else(|| {
-            anyhow!(
-                "No results returned for mid price check, effects: {:?}",
-                effects
-            )
+        // `mid_price` aborts while either side of the book is empty
+        let results = results.ok_or_else(|| QueryError::Aborted {
+            query: "mid_price",
+            error: error.unwrap_or_else(|| format!("{:?}", effects.status())),
         })?;
 
         let return_values = results
@@ -1086,4 +1513,748 @@ impl DeepBookClient {
 
         Ok(mid_price)
     }
+
+    /// Mid price of `pool_key` in quote coin per base coin
+    pub async fn get_scaled_mid_price(&self, pool_key: &str) -> Result<f64> {
+        let pool = self.config.get_pool(pool_key);
+        let base_scalar = self.config.get_coin(&pool.base_coin).scalar as f64;
+        let quote_scalar = self.config.get_coin(&pool.quote_coin).scalar as f64;
+        let mid_price = self.get_mid_price(pool_key).await?;
+
+        Ok(mid_price as f64 * base_scalar / (FLOAT_SCALAR * quote_scalar))
+    }
+
+    /// Price of one `coin_key` in `numeraire`, through a pool trading the
+    /// pair directly or through one intermediate coin. `None` when no
+    /// configured pool with a two-sided book connects them.
+    pub async fn get_coin_price(&self, coin_key: &str, numeraire: &str) -> Result<Option<f64>> {
+        if coin_key == numeraire {
+            return Ok(Some(1.0));
+        }
+        if let Some(price) = self.get_direct_price(coin_key, numeraire).await? {
+            return Ok(Some(price));
+        }
+
+        for via in self.config.coin_keys() {
+            if via == coin_key || via == numeraire {
+                continue;
+            }
+            let Some(first) = self.get_direct_price(coin_key, &via).await? else {
+                continue;
+            };
+            if let Some(second) = self.get_direct_price(&via, numeraire).await? {
+                return Ok(Some(first * second));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn get_direct_price(&self, from: &str, to: &str) -> Result<Option<f64>> {
+        for pool_key in self.config.pool_keys() {
+            let pool = self.config.get_pool(&pool_key);
+            let inverted = if pool.base_coin == from && pool.quote_coin == to {
+                false
+            } else if pool.base_coin == to && pool.quote_coin == from {
+                true
+            } else {
+                continue;
+            };
+
+            // An empty side makes the mid price unavailable; try another pool
+            match self.get_scaled_mid_price(&pool_key).await {
+                Ok(mid) if mid > 0.0 => return Ok(Some(if inverted { 1.0 / mid } else { mid })),
+                Ok(_) => continue,
+                Err(error) if error.downcast_ref::<QueryError>().is_some() => continue,
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Fills involving `manager_key` in configured pools, after `cursor`,
+    /// reading at most `max_pages` pages of DeepBook fill events. Without a
+    /// cursor this only returns one at the newest fill, so history is never
+    /// paged from genesis. Maker fills come from other senders'
+    /// transactions, so every fill event is read and filtered by the
+    /// maker and taker managers. Returns the fills in chain order and the
+    /// cursor to resume from.
+    pub async fn get_order_fills(
+        &self,
+        manager_key: &str,
+        cursor: Option<EventID>,
+        max_pages: usize,
+    ) -> Result<(Vec<OrderFill>, Option<EventID>)> {
+        let manager_id =
+            ObjectID::from_hex_literal(&self.config.get_balance_manager(manager_key).address)?;
+        let event_type = parse_sui_struct_tag(&format!(
+            "{}::order_info::OrderFilled",
+            self.config.deepbook_package_id
+        ))?;
+        let filter = EventFilter::MoveEventType(event_type);
+
+        let Some(mut cursor) = cursor else {
+            let newest = self
+                .client
+                .event_api()
+                .query_events(filter, None, Some(1), true)
+                .await
+                .context("Failed to query the newest fill event")?;
+            return Ok((vec![], newest.data.first().map(|event| event.id)));
+        };
+
+        let mut fills = vec![];
+        for _ in 0..max_pages {
+            let page = self
+                .client
+                .event_api()
+                .query_events(filter.clone(), Some(cursor), None, false)
+                .await
+                .context("Failed to query fill events")?;
+
+            for event in &page.data {
+                if let Some(fill) = self.parse_order_fill(&event.parsed_json, manager_id)? {
+                    fills.push(fill);
+                }
+            }
+
+            if let Some(next) = page.next_cursor {
+                cursor = next;
+            }
+            if !page.has_next_page {
+                break;
+            }
+        }
+
+        Ok((fills, Some(cursor)))
+    }
+
+    fn parse_order_fill(
+        &self,
+        event: &serde_json::Value,
+        manager_id: ObjectID,
+    ) -> Result<Option<OrderFill>> {
+        let id_field = |name: &str| -> Result<ObjectID> {
+            let id = json_field(event, &[name])?
+                .as_str()
+                .ok_or_else(|| anyhow!("Invalid {} in fill event", name))?;
+            Ok(ObjectID::from_hex_literal(id)?)
+        };
+        let bool_field = |name: &str| -> Result<bool> {
+            json_field(event, &[name])?
+                .as_bool()
+                .ok_or_else(|| anyhow!("Invalid {} in fill event", name))
+        };
+
+        let is_maker = id_field("maker_balance_manager_id")? == manager_id;
+        let is_taker = id_field("taker_balance_manager_id")? == manager_id;
+        if !is_maker && !is_taker {
+            return Ok(None);
+        }
+        let Some(pool_key) = self.config.pool_key_by_address(&id_field("pool_id")?) else {
+            return Ok(None);
+        };
+
+        let pool = self.config.get_pool(&pool_key);
+        let base_scalar = self.config.get_coin(&pool.base_coin).scalar as f64;
+        let quote_scalar = self.config.get_coin(&pool.quote_coin).scalar as f64;
+        let deep_scalar = self.config.get_coin("DEEP").scalar as f64;
+
+        let taker_is_bid = bool_field("taker_is_bid")?;
+        let (fee, fee_is_deep) = if is_taker {
+            ("taker_fee", bool_field("taker_fee_is_deep")?)
+        } else {
+            ("maker_fee", bool_field("maker_fee_is_deep")?)
+        };
+        let deep_fee = if fee_is_deep {
+            json_u64(json_field(event, &[fee])?)? as f64 / deep_scalar
+        } else {
+            0.0
+        };
+
+        let (order_id, client_order_id) = if is_taker {
+            ("taker_order_id", "taker_client_order_id")
+        } else {
+            ("maker_order_id", "maker_client_order_id")
+        };
+
+        Ok(Some(OrderFill {
+            base_coin: pool.base_coin.to_string(),
+            quote_coin: pool.quote_coin.to_string(),
+            pool_key,
+            order_id: json_u128(json_field(event, &[order_id])?)?,
+            client_order_id: ClientOrderId(json_u64(json_field(event, &[client_order_id])?)?),
+            is_buy: if is_taker {
+                taker_is_bid
+            } else {
+                !taker_is_bid
+            },
+            base_quantity: json_u64(json_field(event, &["base_quantity"])?)? as f64 / base_scalar,
+            quote_quantity: json_u64(json_field(event, &["quote_quantity"])?)? as f64
+                / quote_scalar,
+            deep_fee,
+            timestamp_ms: json_u64(json_field(event, &["timestamp"])?)?,
+        }))
+    }
+
+    /// Orders of `manager_key` that rested on the book in an executed
+    /// transaction. The response must include events.
+    pub fn order_placements(
+        &self,
+        manager_key: &str,
+        response: &SuiTransactionBlockResponse,
+    ) -> Result<Vec<OrderPlacement>> {
+        let manager_id =
+            ObjectID::from_hex_literal(&self.config.get_balance_manager(manager_key).address)?;
+
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let mut placements = vec![];
+        for event in response.events.iter().flat_map(|events| &events.data) {
+            if ObjectID::from(event.type_.address) != package_id
+                || event.type_.module.as_str() != "order_info"
+                || event.type_.name.as_str() != "OrderPlaced"
+            {
+                continue;
+            }
+            if let Some(placement) = self.parse_order_placement(&event.parsed_json, manager_id)? {
+                placements.push(placement);
+            }
+        }
+        Ok(placements)
+    }
+
+    /// Fills of `manager_key`'s orders in an executed transaction. The
+    /// response must include events.
+    pub fn order_fills(
+        &self,
+        manager_key: &str,
+        response: &SuiTransactionBlockResponse,
+    ) -> Result<Vec<OrderFill>> {
+        let manager_id =
+            ObjectID::from_hex_literal(&self.config.get_balance_manager(manager_key).address)?;
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let mut fills = vec![];
+        for event in response.events.iter().flat_map(|events| &events.data) {
+            if ObjectID::from(event.type_.address) != package_id
+                || event.type_.module.as_str() != "order_info"
+                || event.type_.name.as_str() != "OrderFilled"
+            {
+                continue;
+            }
+            if let Some(fill) = self.parse_order_fill(&event.parsed_json, manager_id)? {
+                fills.push(fill);
+            }
+        }
+        Ok(fills)
+    }
+
+    fn parse_order_placement(
+        &self,
+        event: &serde_json::Value,
+        manager_id: ObjectID,
+    ) -> Result<Option<OrderPlacement>> {
+        let id_field = |name: &str| -> Result<ObjectID> {
+            let id = json_field(event, &[name])?
+                .as_str()
+                .ok_or_else(|| anyhow!("Invalid {} in placement event", name))?;
+            Ok(ObjectID::from_hex_literal(id)?)
+        };
+
+        if id_field("balance_manager_id")? != manager_id {
+            return Ok(None);
+        }
+        let Some(pool_key) = self.config.pool_key_by_address(&id_field("pool_id")?) else {
+            return Ok(None);
+        };
+
+        let pool = self.config.get_pool(&pool_key);
+        let base_scalar = self.config.get_coin(&pool.base_coin).scalar as f64;
+        let quote_scalar = self.config.get_coin(&pool.quote_coin).scalar as f64;
+
+        let order_id = json_u128(json_field(event, &["order_id"])?)?;
+        let is_bid = json_field(event, &["is_bid"])?
+            .as_bool()
+            .ok_or_else(|| anyhow!("Invalid is_bid in placement event"))?;
+
+        Ok(Some(OrderPlacement {
+            pool_key,
+            order_id,
+            client_order_id: ClientOrderId(json_u64(json_field(event, &["client_order_id"])?)?),
+            is_bid,
+            price: json_u64(json_field(event, &["price"])?)? as f64 * base_scalar
+                / (FLOAT_SCALAR * quote_scalar),
+            quantity: json_u64(json_field(event, &["placed_quantity"])?)? as f64 / base_scalar,
+            expire_timestamp: json_u64(json_field(event, &["expire_timestamp"])?)?,
+        }))
+    }
+
+    /// Simulate a flash loan plan through dev-inspect: whether it repays,
+    /// each leg's outputs and the net gain per coin
+    pub async fn simulate_flash_loan(&self, plan: &FlashLoanPlan) -> Result<FlashLoanSimulation> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let commands = plan
+            .build(
+                &mut ptb,
+                &self.flash_loans,
+                &self.deep_book,
+                self.sender_address,
+            )
+            .await
+            .context("Failed to build flash loan plan")?;
+
+        let resp = self
+            .client
+            .read_api()
+            .dev_inspect_transaction_block(
+                self.sender_address,
+                TransactionKind::programmable(ptb.finish()),
+                None,
+                None,
+                Some(DevInspectArgs {
+                    show_raw_txn_data_and_effects: Some(true),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .context("Failed to execute dev inspect transaction block")?;
+
+        let DevInspectResults {
+            results,
+            effects,
+            error,
+            raw_effects,
+            ..
+        } = resp;
+
+        let gas_used = effects.gas_cost_summary().net_gas_usage();
+        let error = error.or_else(|| match effects.status() {
+            SuiExecutionStatus::Success => None,
+            SuiExecutionStatus::Failure { error } => Some(error.clone()),
+        });
+        let raw_effects: TransactionEffects =
+            bcs::from_bytes(&raw_effects).context("Failed to decode dev inspect effects")?;
+        let abort = move_abort(raw_effects.status());
+        let repaid = raw_effects.status().is_ok() && error.is_none();
+
+        let mut leg_results = vec![];
+        let mut profit: HashMap<String, f64> = HashMap::new();
+
+        if let Some(results) = results.filter(|_| repaid) {
+            for (leg, command) in plan.legs.iter().zip(commands.swap_commands.iter()) {
+                let return_values = &results
+                    .get(*command as usize)
+                    .ok_or_else(|| anyhow!("No result for swap command {}", command))?
+                    .return_values;
+
+                if return_values.len() != 3 {
+                    return Err(anyhow!(
+                        "Unexpected number of return values for swap: expected 3, got {}",
+                        return_values.len()
+                    ));
+                }
+
+                let leg_pool = self.config.get_pool(&leg.pool_key);
+                let base_coin = self.config.get_coin(&leg_pool.base_coin);
+                let quote_coin = self.config.get_coin(&leg_pool.quote_coin);
+                let deep_coin = self.config.get_coin("DEEP");
+
+                let base_out: MoveCoin = bcs::from_bytes(&return_values[0].0)
+                    .context("Failed to decode base coin from swap result")?;
+                let quote_out: MoveCoin = bcs::from_bytes(&return_values[1].0)
+                    .context("Failed to decode quote coin from swap result")?;
+                let deep_out: MoveCoin = bcs::from_bytes(&return_values[2].0)
+                    .context("Failed to decode DEEP coin from swap result")?;
+
+                let base_out = base_out.value as f64 / base_coin.scalar as f64;
+                let quote_out = quote_out.value as f64 / quote_coin.scalar as f64;
+                let deep_out = deep_out.value as f64 / deep_coin.scalar as f64;
+
+                // Remainders of each leg go straight back to the sender
+                let unspent = match leg.direction {
+                    SwapDirection::BaseForQuote => (&leg_pool.base_coin, base_out),
+                    SwapDirection::QuoteForBase => (&leg_pool.quote_coin, quote_out),
+                };
+                *profit.entry(unspent.0.to_string()).or_default() += unspent.1;
+                *profit.entry("DEEP".to_string()).or_default() += deep_out - leg.deep_amount;
+
+                leg_results.push(LegSimulation {
+                    pool_key: leg.pool_key.clone(),
+                    direction: leg.direction,
+                    base_out,
+                    quote_out,
+                    deep_out,
+                });
+            }
+
+            // The final leg's output repays the loan; the excess is profit
+            if let (Some(last_leg), Some(last)) = (plan.legs.last(), leg_results.last()) {
+                let repaid_with = match last_leg.direction {
+                    SwapDirection::BaseForQuote => last.quote_out,
+                    SwapDirection::QuoteForBase => last.base_out,
+                };
+                *profit
+                    .entry(plan.borrowed_coin_key(&self.config))
+                    .or_default() += repaid_with - plan.amount;
+            }
+        }
+
+        Ok(FlashLoanSimulation {
+            repaid,
+            legs: leg_results,
+            profit,
+            gas_used,
+            error,
+            abort,
+        })
+    }
+
+    /// Get the governance state of a pool: epoch, quorum, voting power,
+    /// proposals and current/next trade params
+    pub async fn get_governance(&self, pool_key: &str) -> Result<GovernanceState> {
+        self.governance.get_governance(pool_key).await
+    }
+
+    /// List the proposals currently open in a pool
+    pub async fn get_proposals(&self, pool_key: &str) -> Result<Vec<Proposal>> {
+        Ok(self.get_governance(pool_key).await?.proposals)
+    }
+
+    /// Trade params that take effect at the next epoch
+    pub async fn get_next_trade_params(&self, pool_key: &str) -> Result<TradeParams> {
+        Ok(self.get_governance(pool_key).await?.next_trade_params)
+    }
+
+    /// Make a balance manager available to every builder under `key`
+    pub fn register_balance_manager(&self, key: &str, manager: BalanceManager) {
+        self.config.register_balance_manager(key, manager);
+    }
+
+    /// Register the balance manager created by an executed transaction
+    /// (e.g. `create_and_share_balance_manager`) under `key`.
+    /// The response must include object changes.
+    pub fn register_created_balance_manager(
+        &self,
+        key: &str,
+        response: &SuiTransactionBlockResponse,
+    ) -> Result<ObjectID> {
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+        let manager_id = created_balance_managers(response, package_id)
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                anyhow!(
+                    "No BalanceManager created in transaction {}",
+                    response.digest
+                )
+            })?;
+
+        self.register_balance_manager(key, BalanceManager::new(&manager_id.to_string()));
+
+        Ok(manager_id)
+    }
+
+    /// Whether a pool already trades `base_coin_key`/`quote_coin_key` in
+    /// either direction. The registry allows only one pool per pair.
+    pub async fn pool_exists(&self, base_coin_key: &str, quote_coin_key: &str) -> Result<bool> {
+        let base_type = self.config.get_coin(base_coin_key).coin_type;
+        let quote_type = self.config.get_coin(quote_coin_key).coin_type;
+
+        for (base_type, quote_type) in [(base_type, quote_type), (quote_type, base_type)] {
+            match self.get_pool_id_by_assets(base_type, quote_type).await {
+                Ok(_) => return Ok(true),
+                // Not registered in this direction
+                Err(e) if e.downcast_ref::<QueryError>().is_some() => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(false)
+    }
+
+    /// Build `create_permissionless_pool` after checking the pair has no pool yet
+    pub async fn create_permissionless_pool(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        base_coin_key: &str,
+        quote_coin_key: &str,
+        tick_size: f64,
+        lot_size: f64,
+        min_size: f64,
+    ) -> Result<Argument> {
+        if self.pool_exists(base_coin_key, quote_coin_key).await? {
+            return Err(anyhow!(
+                "A pool for {}/{} already exists",
+                base_coin_key,
+                quote_coin_key
+            ));
+        }
+
+        self.deep_book
+            .create_permissionless_pool(
+                ptb,
+                base_coin_key,
+                quote_coin_key,
+                tick_size,
+                lot_size,
+                min_size,
+            )
+            .await
+    }
+
+    /// Register the pool created by an executed `create_permissionless_pool`
+    /// transaction as `{BASE}_{QUOTE}`. Returns the pool key.
+    /// The response must include object changes.
+    pub fn register_created_pool(
+        &self,
+        base_coin_key: &str,
+        quote_coin_key: &str,
+        response: &SuiTransactionBlockResponse,
+    ) -> Result<String> {
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+        let base_type = TypeTag::from_str(self.config.get_coin(base_coin_key).coin_type)?;
+        let quote_type = TypeTag::from_str(self.config.get_coin(quote_coin_key).coin_type)?;
+
+        let pool_id = response
+            .object_changes
+            .iter()
+            .flatten()
+            .find_map(|change| match change {
+                ObjectChange::Created {
+                    object_type,
+                    object_id,
+                    ..
+                } if ObjectID::from(object_type.address) == package_id
+                    && object_type.module.as_str() == "pool"
+                    && object_type.name.as_str() == "Pool"
+                    && object_type.type_params == [base_type.clone(), quote_type.clone()] =>
+                {
+                    Some(*object_id)
+                }
+                _ => None,
+            })
+            .ok_or_else(|| {
+                anyhow!(
+                    "No {}/{} Pool created in transaction {}",
+                    base_coin_key,
+                    quote_coin_key,
+                    response.digest
+                )
+            })?;
+
+        let pool_key = format!("{}_{}", base_coin_key, quote_coin_key);
+        self.config.register_pool(
+            &pool_key,
+            Pool {
+                address: pool_id.to_string(),
+                base_coin: base_coin_key.to_string(),
+                quote_coin: quote_coin_key.to_string(),
+            },
+        );
+
+        Ok(pool_key)
+    }
+
+    /// Finish `ptb` into unsigned transaction data for an external wallet.
+    /// Without `gas`, a SUI coin of the sender covering `gas_budget` pays.
+    pub async fn build_transaction_bytes(
+        &self,
+        sender: SuiAddress,
+        gas: Option<ObjectRef>,
+        gas_budget: u64,
+        ptb: ProgrammableTransactionBuilder,
+    ) -> Result<UnsignedTransaction> {
+        let gas = match gas {
+            Some(gas) => gas,
+            None => select_gas_coin(&self.client, sender, gas_budget).await?,
+        };
+        let gas_price = self.client.read_api().get_reference_gas_price().await?;
+
+        let tx_data = TransactionData::new_programmable(
+            sender,
+            vec![gas],
+            ptb.finish(),
+            gas_budget,
+            gas_price,
+        );
+
+        Ok(UnsignedTransaction {
+            tx_bytes: encode_transaction_data(&tx_data)?,
+            description: describe_transaction(&self.config, &tx_data)?,
+        })
+    }
+
+    /// Find balance managers owned by `owner` from their creation events,
+    /// including those another address created for it with
+    /// `new_with_custom_owner`. Only the newest [`MAX_EVENT_PAGES`] pages of
+    /// creation events across all owners are read, so older managers can be
+    /// missed on a busy network. Newest first.
+    pub async fn discover_balance_managers(&self, owner: SuiAddress) -> Result<Vec<ObjectID>> {
+        let event_type = parse_sui_struct_tag(&format!(
+            "{}::balance_manager::BalanceManagerEvent",
+            self.config.deepbook_package_id
+        ))?;
+        let filter = EventFilter::MoveEventType(event_type);
+
+        let mut managers = vec![];
+        let mut cursor = None;
+        for _ in 0..MAX_EVENT_PAGES {
+            let page = self
+                .client
+                .event_api()
+                .query_events(filter.clone(), cursor, None, true)
+                .await
+                .context("Failed to query balance manager events")?;
+
+            for event in &page.data {
+                let field = |name: &str| -> Result<String> {
+                    json_field(&event.parsed_json, &[name])?
+                        .as_str()
+                        .map(str::to_string)
+                        .ok_or_else(|| anyhow!("Invalid {} in balance manager event", name))
+                };
+                if SuiAddress::from_str(&field("owner")?)? == owner {
+                    managers.push(ObjectID::from_hex_literal(&field("balance_manager_id")?)?);
+                }
+            }
+
+            if !page.has_next_page {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+
+        Ok(managers)
+    }
+
+    /// Switch `manager_key` to delegated mode: orders, cancels and governance
+    /// go through `trade_cap_id` and owner-only builders are refused.
+    /// The cap must be a live TradeCap for this manager held by the sender.
+    pub async fn use_trade_cap(&self, manager_key: &str, trade_cap_id: ObjectID) -> Result<()> {
+        let manager = self.config.get_balance_manager(manager_key);
+        let cap = trade_cap_id.to_string();
+
+        let data = self
+            .client
+            .read_api()
+            .get_object_with_options(
+                trade_cap_id,
+                SuiObjectDataOptions::new().with_type().with_owner(),
+            )
+            .await?
+            .data
+            .ok_or_else(|| ManagerAccessError::NotATradeCap(cap.clone()))?;
+
+        let is_trade_cap = data.type_.as_ref().is_some_and(|type_| {
+            type_.to_string().ends_with(&format!(
+                "::balance_manager::{}",
+                CapKind::Trade.struct_name()
+            ))
+        });
+        if !is_trade_cap {
+            return Err(ManagerAccessError::NotATradeCap(cap).into());
+        }
+
+        if data.owner != Some(Owner::AddressOwner(self.sender_address)) {
+            return Err(ManagerAccessError::TradeCapNotHeld {
+                cap,
+                sender: self.sender_address.to_string(),
+            }
+            .into());
+        }
+
+        if !self
+            .get_allow_listed_caps(manager_key)
+            .await?
+            .contains(&trade_cap_id)
+        {
+            return Err(ManagerAccessError::TradeCapRevoked {
+                cap,
+                manager: manager_key.to_string(),
+            }
+            .into());
+        }
+
+        self.register_balance_manager(
+            manager_key,
+            BalanceManager::delegated(&manager.address, &cap),
+        );
+
+        Ok(())
+    }
+
+    /// Cap ids currently allow-listed on a balance manager
+    pub async fn get_allow_listed_caps(&self, manager_key: &str) -> Result<Vec<ObjectID>> {
+        let manager = self.config.get_balance_manager(manager_key);
+        let manager_id = ObjectID::from_hex_literal(&manager.address)?;
+        let manager_json = get_object_json(&self.client, manager_id)
+            .await
+            .context("Failed to read balance manager")?;
+
+        json_field(&manager_json, &["allow_listed", "contents"])?
+            .as_array()
+            .ok_or_else(|| anyhow!("Invalid allow list for balance manager {}", manager_key))?
+            .iter()
+            .map(|id| {
+                let id = id
+                    .as_str()
+                    .ok_or_else(|| anyhow!("Invalid cap id in allow list"))?;
+                Ok(ObjectID::from_hex_literal(id)?)
+            })
+            .collect()
+    }
+
+    /// Allow-listed caps with their kind and current holder
+    pub async fn list_caps(&self, manager_key: &str) -> Result<Vec<ManagerCap>> {
+        let mut caps = vec![];
+        for id in self.get_allow_listed_caps(manager_key).await? {
+            let data = self
+                .client
+                .read_api()
+                .get_object_with_options(id, SuiObjectDataOptions::new().with_type().with_owner())
+                .await?
+                .data;
+
+            let kind = data.as_ref().and_then(|data| {
+                let type_name = data.type_.as_ref()?.to_string();
+                [CapKind::Trade, CapKind::Deposit, CapKind::Withdraw]
+                    .into_iter()
+                    .find(|kind| {
+                        type_name.ends_with(&format!("::balance_manager::{}", kind.struct_name()))
+                    })
+            });
+            let owner = data.and_then(|data| match data.owner {
+                Some(Owner::AddressOwner(address)) => Some(address),
+                _ => None,
+            });
+
+            caps.push(ManagerCap { id, kind, owner });
+        }
+
+        Ok(caps)
+    }
+}
+
+fn created_balance_managers(
+    response: &SuiTransactionBlockResponse,
+    package_id: ObjectID,
+) -> Vec<ObjectID> {
+    response
+        .object_changes
+        .iter()
+        .flatten()
+        .filter_map(|change| match change {
+            ObjectChange::Created {
+                object_type,
+                object_id,
+                ..
+            } if ObjectID::from(object_type.address) == package_id
+                && object_type.module.as_str() == "balance_manager"
+                && object_type.name.as_str() == "BalanceManager" =>
+            {
+                Some(*object_id)
+            }
+            _ => None,
+        })
+        .collect()
 }
@@ -0,0 +1,125 @@
+use thiserror::Error;
+
+/// Reasons a governance proposal is rejected before it reaches the chain
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ProposalError {
+    #[error("pool {0} is whitelisted and does not accept proposals")]
+    WhitelistedPool(String),
+    #[error("taker fee {fee} is outside [{min}, {max}] for {kind} pools")]
+    TakerFeeOutOfRange {
+        fee: f64,
+        min: f64,
+        max: f64,
+        kind: &'static str,
+    },
+    #[error("maker fee {fee} is outside [{min}, {max}] for {kind} pools")]
+    MakerFeeOutOfRange {
+        fee: f64,
+        min: f64,
+        max: f64,
+        kind: &'static str,
+    },
+    #[error("maker fee {maker_fee} exceeds taker fee {taker_fee}")]
+    MakerAboveTaker { maker_fee: f64, taker_fee: f64 },
+    #[error("stake required must be non-negative, got {0}")]
+    InvalidStakeRequired(f64),
+}
+
+/// Reasons a balance manager operation is refused for the current sender
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ManagerAccessError {
+    #[error("{operation} on balance manager {manager} is reserved to its owner")]
+    OwnerOnly {
+        manager: String,
+        operation: &'static str,
+    },
+    #[error("object {0} is not a TradeCap")]
+    NotATradeCap(String),
+    #[error("trade cap {cap} is not held by sender {sender}")]
+    TradeCapNotHeld { cap: String, sender: String },
+    #[error("trade cap {cap} is not allow-listed on balance manager {manager}")]
+    TradeCapRevoked { cap: String, manager: String },
+}
+
+/// A read-only query that reached the chain and aborted there, e.g. the mid
+/// price of an empty book, as opposed to failing to reach it
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum QueryError {
+    #[error("{query} aborted: {error}")]
+    Aborted { query: &'static str, error: String },
+}
+
+/// Book parameters the pool would reject, in raw on-chain units
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum BookParamsError {
+    #[error("tick size {0} must be a positive power of ten")]
+    InvalidTickSize(u64),
+    #[error("lot size {0} must be a positive power of ten")]
+    InvalidLotSize(u64),
+    #[error(
+        "min size {min_size} must be a positive power of ten and a multiple of lot size {lot_size}"
+    )]
+    InvalidMinSize { min_size: u64, lot_size: u64 },
+}
+
+/// Ladder settings that cannot produce valid orders for the pool
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum LadderError {
+    #[error("a ladder needs at least one level")]
+    NoLevels,
+    #[error("ladder prices must be positive, got {start} to {end}")]
+    InvalidPrice { start: f64, end: f64 },
+    #[error("{levels} levels between {start} and {end} overlap at tick size {tick_size}")]
+    LevelsOverlap {
+        levels: usize,
+        start: f64,
+        end: f64,
+        tick_size: f64,
+    },
+    #[error("{size} per level is below the pool's min size {min_size}")]
+    SizeTooSmall { size: f64, min_size: f64 },
+    #[error("{levels} client order ids from {first} overflow u64")]
+    ClientOrderIdOverflow { first: u64, levels: usize },
+}
+
+/// Order lifetimes the pool would reject
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TimeInForceError {
+    #[error("order expires at {expire_timestamp} ms, not after chain time {now_ms} ms")]
+    Expired { expire_timestamp: u64, now_ms: u64 },
+}
+
+/// Orders refused by the pre-trade risk checks
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RiskError {
+    #[error("kill switch is engaged")]
+    KillSwitchEngaged,
+    #[error("{pool_key} has no mid price to check orders against")]
+    NoMidPrice { pool_key: String },
+    #[error("order notional {notional} exceeds the limit of {max}")]
+    NotionalTooLarge { notional: f64, max: f64 },
+    #[error("price {price} is outside the {max_deviation} collar around mid {mid}")]
+    OutsideCollar {
+        price: f64,
+        mid: f64,
+        max_deviation: f64,
+    },
+    #[error("{coin} position would reach {position}, above the limit of {max}")]
+    PositionTooLarge {
+        coin: String,
+        position: f64,
+        max: f64,
+    },
+    #[error("orders need {required} {coin} but the manager holds {available}")]
+    InsufficientBalance {
+        coin: String,
+        required: f64,
+        available: f64,
+    },
+    #[error("{pool_key} would have {open} open orders, above the limit of {max}")]
+    TooManyOpenOrders {
+        pool_key: String,
+        open: usize,
+        max: usize,
+    },
+}
@@ -1,4 +1,10 @@
 pub mod client;
+pub mod errors;
+pub mod orders;
+pub mod portfolio;
+pub mod risk;
+pub mod strategy;
 pub mod transactions;
+pub mod triggers;
 pub mod types;
 pub mod utils;
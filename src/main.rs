@@ -1,243 +1,924 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! `deepbook` command-line tool built on [`DeepBookClient`].
+//!
+//! Settings are taken from flags first, then from the `--config` JSON file,
+//! then from the Sui CLI's own config (`~/.sui/sui_config`).
+
 use std::collections::HashMap;
-use std::{any, str::FromStr};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
 
-use anyhow::{Error, Result};
-use anyhow::{Ok, anyhow};
+use anyhow::{Context, Result, anyhow};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use deepbookv3::client::DeepBookClient;
-use deepbookv3::transactions::balance_manager;
+use deepbookv3::orders::ClientOrderIdGenerator;
 use deepbookv3::types::{
-    BalanceManager, OrderType, PlaceLimitOrderParams, PlaceMarketOrderParams, SelfMatchingOptions,
-    SwapParams,
+    BalanceManager, ClientOrderId, OrderType, PlaceLimitOrderParams, PlaceMarketOrderParams,
+    SwapParams, TimeInForce,
+};
+use deepbookv3::utils::config::{FLOAT_SCALAR, GAS_BUDGET};
+use deepbookv3::utils::explain::{explain_transaction_data, render_explanation};
+use deepbookv3::utils::offline::{
+    decode_transaction_data, describe_transaction, encode_transaction_data,
 };
+use deepbookv3::utils::{select_gas_coin, transfer_objects};
+use serde::Deserialize;
+use serde_json::{Value, json};
 use shared_crypto::intent::Intent;
-use sui_config::{SUI_KEYSTORE_FILENAME, sui_config_dir};
+use sui_config::{PersistedConfig, SUI_CLIENT_CONFIG, SUI_KEYSTORE_FILENAME, sui_config_dir};
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
-use sui_sdk::{
-    SuiClient, SuiClientBuilder,
-    rpc_types::{
-        DevInspectResults, SuiObjectData, SuiObjectDataOptions, SuiObjectResponse,
-        SuiTransactionBlockResponseOptions,
-    },
-    types::{
-        Identifier, TypeTag,
-        base_types::{ObjectID, ObjectRef, SuiAddress},
-        crypto::SuiKeyPair,
-        programmable_transaction_builder::ProgrammableTransactionBuilder,
-        quorum_driver_types::ExecuteTransactionRequestType,
-        transaction::{
-            Argument, CallArg, Command, ObjectArg, ProgrammableMoveCall, Transaction,
-            TransactionData, TransactionKind,
-        },
-        type_input::TypeInput,
-    },
+use sui_sdk::rpc_types::{
+    SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions,
 };
-use sui_types::collection_types::VecSet;
-use sui_types::transaction::ProgrammableTransaction;
-
-pub async fn setup_client() -> Result<(SuiClient, SuiAddress, DeepBookClient)> {
-    let client = SuiClientBuilder::default().build_testnet().await?;
-    let sender =
-        SuiAddress::from_str("0x38a27d258039c629219b3dbaaeb502381d26f9b93f985e2fec7d248db00d3cf1")?;
-
-    let balance_managers = HashMap::from([(
-        "MANAGER_2".to_string(),
-        BalanceManager {
-            address: "0x08933685e0246a2ddae2f5e5628fdeba09de831cadf5ad949db308807f18bee5", // balance_manager for testnet
-            // address: "0x73e7bc2f1007a4f1ffcc42af9305e4e7ce16274297e2e513b2503b9c85c287d4", // balance_manager for devnet
-            trade_cap: None,
-            deposit_cap: None,
-            withdraw_cap: None,
-        },
-    )]);
-
-    let deep_book_client = DeepBookClient::new(
-        client.clone(),
-        sender,
-        "testnet",
-        Some(balance_managers),
-        None,
-        None,
-        None,
-    );
-
-    Ok((client, sender, deep_book_client))
-}
-
-/// Retrieve a gas coin from the sender's account.
-pub async fn get_gas_coin(client: &SuiClient, sender: SuiAddress) -> Result<ObjectRef> {
-    let coins = client
-        .coin_read_api()
-        .get_coins(sender, None, None, None)
-        .await?;
-    let gas_coin = coins.data.into_iter().next().unwrap();
-    Ok(gas_coin.object_ref())
-}
-
-/// Sign and execute a transaction.
-pub async fn sign_and_execute(
-    client: &SuiClient,
+use sui_sdk::sui_client_config::SuiClientConfig;
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_sdk::types::transaction::{Transaction, TransactionData};
+use sui_sdk::{SuiClient, SuiClientBuilder};
+
+#[derive(Parser)]
+#[command(name = "deepbook", version, about = "DeepBook V3 command-line client")]
+struct Cli {
+    /// Network to connect to [default: testnet]
+    #[arg(long, global = true, value_enum)]
+    network: Option<Network>,
+
+    /// Full node RPC URL, overriding the network default
+    #[arg(long, global = true)]
+    rpc_url: Option<String>,
+
+    /// JSON file with network, sender, keystore, admin cap and balance managers
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Sender address [default: the Sui CLI's active address]
+    #[arg(long, global = true)]
+    sender: Option<SuiAddress>,
+
+    /// Keystore used for signing [default: ~/.sui/sui_config/sui.keystore]
+    #[arg(long, global = true)]
+    keystore: Option<PathBuf>,
+
+    /// DeepBook admin capability, for `admin` commands
+    #[arg(long, global = true)]
+    admin_cap: Option<String>,
+
+    /// Balance manager as KEY=ADDRESS, may be repeated
+    #[arg(long = "manager", global = true, value_name = "KEY=ADDRESS")]
+    managers: Vec<String>,
+
+    /// Gas budget in MIST
+    #[arg(long, global = true, default_value_t = GAS_BUDGET as u64)]
+    gas_budget: u64,
+
+    /// Simulate transactions instead of executing them
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Print unsigned transaction bytes for an external wallet instead of executing
+    #[arg(long, global = true, conflicts_with = "dry_run")]
+    unsigned: bool,
+
+    /// Print machine-readable JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Network {
+    Mainnet,
+    Testnet,
+    Devnet,
+}
+
+impl Network {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+            Network::Devnet => "devnet",
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OrderKind {
+    Limit,
+    PostOnly,
+    Ioc,
+    Fok,
+}
+
+impl From<OrderKind> for OrderType {
+    fn from(kind: OrderKind) -> Self {
+        match kind {
+            OrderKind::Limit => OrderType::NoRestriction,
+            OrderKind::PostOnly => OrderType::PostOnly,
+            OrderKind::Ioc => OrderType::ImmediateOrCancel,
+            OrderKind::Fok => OrderType::FillOrKill,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SwapInput {
+    Base,
+    Quote,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List configured pools
+    Pools,
+    /// Show the order book around the mid price
+    Book {
+        pool: String,
+        /// Price levels on each side
+        #[arg(long, default_value_t = 10)]
+        ticks: u64,
+    },
+    /// Show a pool's mid price
+    Mid { pool: String },
+    /// Show a balance manager's balances
+    Balance {
+        manager: String,
+        /// Only this coin
+        #[arg(long)]
+        coin: Option<String>,
+    },
+    /// Deposit coins from the sender into a balance manager
+    Deposit {
+        manager: String,
+        coin: String,
+        amount: f64,
+    },
+    /// Withdraw coins from a balance manager to the sender
+    Withdraw {
+        manager: String,
+        coin: String,
+        #[arg(required_unless_present = "all")]
+        amount: Option<f64>,
+        /// Withdraw the whole balance
+        #[arg(long, conflicts_with = "amount")]
+        all: bool,
+    },
+    /// Place an order; without --price it is a market order
+    Place(PlaceArgs),
+    /// Cancel an order, or every order with --all
+    Cancel {
+        pool: String,
+        manager: String,
+        #[arg(required_unless_present = "all")]
+        order_id: Option<u128>,
+        #[arg(long, conflicts_with = "order_id")]
+        all: bool,
+    },
+    /// Swap coins from the sender's wallet, without a balance manager
+    Swap {
+        pool: String,
+        /// Which side of the pool is paid in
+        #[arg(long, value_enum)]
+        sell: SwapInput,
+        amount: f64,
+        /// DEEP paid for fees
+        #[arg(long, default_value_t = 0.0)]
+        deep_amount: f64,
+        /// Minimum received, or the transaction aborts
+        #[arg(long, default_value_t = 0.0)]
+        min_out: f64,
+    },
+    /// Stake DEEP in a pool
+    Stake {
+        pool: String,
+        manager: String,
+        amount: f64,
+    },
+    /// Vote for a governance proposal
+    Vote {
+        pool: String,
+        manager: String,
+        proposal_id: ObjectID,
+    },
+    /// Describe base64 transaction bytes, e.g. from --unsigned, before signing
+    Decode { tx_bytes: String },
+    /// Commands requiring the DeepBook admin cap, unless noted
+    #[command(subcommand)]
+    Admin(AdminCommand),
+}
+
+#[derive(Args)]
+struct PlaceArgs {
+    pool: String,
+    manager: String,
+    #[arg(long, value_enum)]
+    side: Side,
+    /// Quantity in the base coin
+    #[arg(long)]
+    quantity: f64,
+    /// Limit price in the quote coin
+    #[arg(long)]
+    price: Option<f64>,
+    #[arg(long, value_enum, default_value = "limit", requires = "price")]
+    order_type: OrderKind,
+    /// Expiration timestamp in ms, limit orders only
+    #[arg(long, requires = "price")]
+    expiration: Option<u64>,
+    /// Expire this many seconds after current chain time, limit orders only
+    #[arg(long, requires = "price", conflicts_with = "expiration")]
+    expires_in: Option<u64>,
+    /// [default: generated from the current time]
+    #[arg(long)]
+    client_order_id: Option<ClientOrderId>,
+    /// Pay fees in the input coin instead of DEEP
+    #[arg(long)]
+    no_deep: bool,
+}
+
+#[derive(Subcommand)]
+enum AdminCommand {
+    /// Set a pool's tick size, in the quote coin
+    AdjustTickSize {
+        pool: String,
+        tick_size: f64,
+    },
+    /// Set a pool's lot and min size, in the base coin
+    AdjustLotSize {
+        pool: String,
+        lot_size: f64,
+        min_size: f64,
+    },
+    /// Add a DEEP price point to a pool from a reference pool (permissionless)
+    AddDeepPricePoint {
+        pool: String,
+        reference_pool: String,
+    },
+    /// Sync a pool's allowed versions with the registry (permissionless)
+    UpdatePoolVersions {
+        pool: String,
+    },
+    EnableVersion {
+        version: u64,
+    },
+    DisableVersion {
+        version: u64,
+    },
+    SetTreasury {
+        address: SuiAddress,
+    },
+    UnregisterPool {
+        pool: String,
+    },
+    AddStablecoin {
+        coin: String,
+    },
+    RemoveStablecoin {
+        coin: String,
+    },
+    /// Authorize an app by its witness type
+    AuthorizeApp {
+        app_type: String,
+    },
+    DeauthorizeApp {
+        app_type: String,
+    },
+}
+
+/// Contents of the `--config` file
+#[derive(Default, Deserialize)]
+struct FileConfig {
+    network: Option<Network>,
+    rpc_url: Option<String>,
+    sender: Option<SuiAddress>,
+    keystore: Option<PathBuf>,
+    admin_cap: Option<String>,
+    #[serde(default)]
+    balance_managers: HashMap<String, ManagerEntry>,
+}
+
+#[derive(Deserialize)]
+struct ManagerEntry {
+    address: String,
+    trade_cap: Option<String>,
+    deposit_cap: Option<String>,
+    withdraw_cap: Option<String>,
+}
+
+struct Session {
+    client: SuiClient,
+    deep_book: DeepBookClient,
     sender: SuiAddress,
-    tx_data: TransactionData,
-) -> Result<()> {
-    let keystore = FileBasedKeystore::new(&sui_config_dir()?.join(SUI_KEYSTORE_FILENAME))?;
-    let signature = keystore.sign_secure(&sender, &tx_data, Intent::sui_transaction())?;
-
-    let transaction_response = client
-        .quorum_driver_api()
-        .execute_transaction_block(
-            Transaction::from_data(tx_data, vec![signature]),
-            SuiTransactionBlockResponseOptions::full_content(),
-            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
-        )
-        .await?;
-
-    assert!(
-        transaction_response
-            .confirmed_local_execution
-            .unwrap_or(false),
-        "Transaction execution failed"
-    );
-
-    println!("Transaction Successful: {:?}", transaction_response);
-    Ok(())
-}
-
-async fn test_deposit_to_manager() -> Result<()> {
-    let (client, sender, deep_book_client) = setup_client().await?;
-    let mut ptb: ProgrammableTransactionBuilder = ProgrammableTransactionBuilder::new();
-    // Step 1: Set up transaction for withdrawal
-    let recipient = sender; // Self-withdrawal test
-    // deep_book_client
-    //     .balance_manager
-    //     .withdraw_all_from_manager(&mut ptb, "MANAGER_2", "SUI", recipient)
-    //     .await?;
-
-    deep_book_client
-        .balance_manager
-        .deposit_into_manager(&mut ptb, "MANAGER_2", "DEEP", 1.1)
-        .await?;
-    let gas_coins = client
-        .coin_read_api()
-        .get_coins(sender, Some("0x2::sui::SUI".to_string()), None, None)
-        .await?
-        .data;
-    let gas_object_refs: Vec<ObjectRef> = gas_coins
-        .iter()
-        .map(|coin| (coin.coin_object_id, coin.version, coin.digest))
-        .collect();
-
-    let gas_budget = 5_000_000;
-    let gas_price = client.read_api().get_reference_gas_price().await?;
-    let tx_data: TransactionData = TransactionData::new_programmable(
-        sender,
-        gas_object_refs,
-        ptb.finish(),
-        gas_budget,
-        gas_price,
-    );
-
-    println!("Signing and executing transaction...");
-    let transaction_response = sign_and_execute(&client, sender, tx_data).await?;
-
-    println!("transaction_response: {:?} ", transaction_response);
-    Ok(())
-}
-async fn test_place_limit_order() -> Result<()> {
-    println!("Placing limit order...");
-    let (client, sender, deep_book_client) = setup_client().await?;
-
-    // Step 1: Set up transaction for place_limit_order
-    let params = PlaceLimitOrderParams {
-        pool_key: "DBUSDT_SUI".to_string(),
-        balance_manager_key: "MANAGER_2".to_string(),
-        client_order_id: "123123".to_string(),
-        price: 2.27,
-        quantity: 10.0,
-        is_bid: false,
-        expiration: None,
-        order_type: Some(OrderType::NoRestriction),
-        self_matching_option: Some(SelfMatchingOptions::SelfMatchingAllowed),
-        pay_with_deep: Some(true),
-    };
-    let mut ptb: ProgrammableTransactionBuilder = ProgrammableTransactionBuilder::new();
-    deep_book_client
-        .deep_book
-        .place_limit_order(&mut ptb, &params)
-        .await?;
-
-    let pt = ptb.finish();
-    // Step 2: Fetch a suitable gas coin
-    let gas_coin = get_gas_coin(&client, sender).await?;
-
-    // Step 3: Set up gas and create transaction data
-    let gas_budget = 50_000_000;
-    let gas_price = client.read_api().get_reference_gas_price().await?;
-    let tx_data =
-        TransactionData::new_programmable(sender, vec![gas_coin], pt, gas_budget, gas_price);
-
-    // Step 4: Sign and execute the transaction
-    let transaction_response = sign_and_execute(&client, sender, tx_data).await?;
-
-    println!("Transaction response: {:?}", transaction_response);
-
-    // assert!(
-    //     transaction_response.digest.is_some(),
-    //     "Transaction digest should not be empty"
-    // );
-
-    Ok(())
+    keystore: PathBuf,
+    gas_budget: u64,
+    dry_run: bool,
+    unsigned: bool,
+    json: bool,
 }
 
-#[tokio::main]
-async fn main() -> Result<(), anyhow::Error> {
-    let (client, sender, deep_book_client) = setup_client().await?;
-    let mut ptb = ProgrammableTransactionBuilder::new();
+impl Session {
+    async fn connect(cli: &Cli) -> Result<Self> {
+        let file = match &cli.config {
+            Some(path) => serde_json::from_str(
+                &fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read config {}", path.display()))?,
+            )
+            .with_context(|| format!("Invalid config {}", path.display()))?,
+            None => FileConfig::default(),
+        };
+
+        let network = cli.network.or(file.network).unwrap_or(Network::Testnet);
+        let rpc_url = cli.rpc_url.clone().or(file.rpc_url);
+        let client = match (rpc_url, network) {
+            (Some(url), _) => SuiClientBuilder::default().build(url).await?,
+            (None, Network::Mainnet) => SuiClientBuilder::default().build_mainnet().await?,
+            (None, Network::Testnet) => SuiClientBuilder::default().build_testnet().await?,
+            (None, Network::Devnet) => SuiClientBuilder::default().build_devnet().await?,
+        };
+
+        let keystore = match cli.keystore.clone().or(file.keystore) {
+            Some(path) => path,
+            None => sui_config_dir()?.join(SUI_KEYSTORE_FILENAME),
+        };
+        let sender = cli
+            .sender
+            .or(file.sender)
+            .or_else(active_address)
+            .ok_or_else(|| anyhow!("No sender: pass --sender or set one in the config file"))?;
+
+        let mut balance_managers: HashMap<String, BalanceManager> = file
+            .balance_managers
+            .into_iter()
+            .map(|(key, entry)| {
+                (
+                    key,
+                    BalanceManager {
+                        address: entry.address,
+                        trade_cap: entry.trade_cap,
+                        deposit_cap: entry.deposit_cap,
+                        withdraw_cap: entry.withdraw_cap,
+                    },
+                )
+            })
+            .collect();
+        for manager in &cli.managers {
+            let (key, address) = manager
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Expected KEY=ADDRESS, got {}", manager))?;
+            balance_managers.insert(key.to_string(), BalanceManager::new(address));
+        }
+
+        let deep_book = DeepBookClient::new(
+            client.clone(),
+            sender,
+            network.as_str(),
+            Some(balance_managers),
+            None,
+            None,
+            cli.admin_cap.clone().or(file.admin_cap),
+        );
+
+        Ok(Self {
+            client,
+            deep_book,
+            sender,
+            keystore,
+            gas_budget: cli.gas_budget,
+            dry_run: cli.dry_run,
+            unsigned: cli.unsigned,
+            json: cli.json,
+        })
+    }
 
-    let (base_coin_result, quote_coin_result, deep_coin_result) = deep_book_client
-        .deep_book
-        .swap_exact_quote_for_base(
-            &mut ptb,
-            &SwapParams {
-                pool_key: "DEEP_SUI".to_string(),
-                amount: 1.0,      // Quote amount (e.g., DBUSDT)
-                deep_amount: 0.0, // DEEP tokens burned
-                min_out: 0.0,     // Expected min base out (e.g., SUI)
-            },
-        )
-        .await?;
-
-    ptb.transfer_args(
-        sender,
-        vec![base_coin_result, quote_coin_result, deep_coin_result],
-    );
-
-    let gas_coins = client
-        .coin_read_api()
-        .get_coins(sender, Some("0x2::sui::SUI".to_string()), None, None)
-        .await?
-        .data;
-
-    let gas_object_refs: Vec<ObjectRef> = gas_coins
-        .iter()
-        .map(|coin| (coin.coin_object_id, coin.version, coin.digest))
-        .collect();
-
-    let gas_budget = 50_000_000;
-    let gas_price = client.read_api().get_reference_gas_price().await?;
-    let pt = ptb.finish();
-
-    println!("📜 Commands for swap_exact_quote_for_base:");
-    for (i, cmd) in pt.commands.iter().enumerate() {
-        println!("  [{}] {:?}", i, cmd);
+    fn ensure_pool(&self, pool_key: &str) -> Result<()> {
+        self.deep_book
+            .config()
+            .try_get_pool(pool_key)
+            .map(|_| ())
+            .ok_or_else(|| anyhow!("Unknown pool {}, see `deepbook pools`", pool_key))
     }
 
-    let tx_data =
-        TransactionData::new_programmable(sender, gas_object_refs, pt, gas_budget, gas_price);
+    fn ensure_coin(&self, coin_key: &str) -> Result<()> {
+        if self
+            .deep_book
+            .config()
+            .coin_keys()
+            .iter()
+            .any(|key| key == coin_key)
+        {
+            Ok(())
+        } else {
+            Err(anyhow!("Unknown coin {}", coin_key))
+        }
+    }
 
-    println!("🚀 Signing and executing quote-for-base swap transaction...");
-    let transaction_response = sign_and_execute(&client, sender, tx_data).await?;
+    fn ensure_manager(&self, manager_key: &str) -> Result<()> {
+        self.deep_book
+            .config()
+            .try_get_balance_manager(manager_key)
+            .map(|_| ())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown balance manager {}, pass --manager {}=ADDRESS",
+                    manager_key,
+                    manager_key
+                )
+            })
+    }
 
-    println!("✅ Transaction response: {:?}", transaction_response);
+    fn print(&self, value: Value, text: String) -> Result<()> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        } else {
+            println!("{}", text);
+        }
+        Ok(())
+    }
 
-    Ok(())
+    /// Sign and execute the transaction, dry-run it with `--dry-run` or
+    /// print it unsigned with `--unsigned`
+    async fn execute(&self, ptb: ProgrammableTransactionBuilder) -> Result<()> {
+        let gas_coin = select_gas_coin(&self.client, self.sender, self.gas_budget).await?;
+        let gas_price = self.client.read_api().get_reference_gas_price().await?;
+        let tx_data = TransactionData::new_programmable(
+            self.sender,
+            vec![gas_coin],
+            ptb.finish(),
+            self.gas_budget,
+            gas_price,
+        );
+
+        if self.unsigned {
+            let tx_bytes = encode_transaction_data(&tx_data)?;
+            let description = describe_transaction(self.deep_book.config(), &tx_data)?;
+            let explanation = explain_transaction_data(self.deep_book.config(), &tx_data)?;
+            return self.print(
+                json!({
+                    "tx_bytes": tx_bytes,
+                    "description": description,
+                    "explanation": explanation,
+                }),
+                format!("{}\n\n{}", tx_bytes, render_explanation(&explanation)),
+            );
+        }
+
+        if self.dry_run {
+            let response = self
+                .client
+                .read_api()
+                .dry_run_transaction_block(tx_data)
+                .await
+                .context("Dry run failed")?;
+            let status = response.effects.status().clone();
+            let gas = response.effects.gas_cost_summary().net_gas_usage();
+            let changes = response
+                .balance_changes
+                .iter()
+                .map(|change| format!("\n  {} {}", change.amount, change.coin_type))
+                .collect::<String>();
+            self.print(
+                serde_json::to_value(&response)?,
+                format!(
+                    "Dry run {}, gas {} MIST{}",
+                    describe_status(&status),
+                    gas,
+                    changes
+                ),
+            )?;
+            return ensure_success(&status);
+        }
+
+        let keystore = FileBasedKeystore::new(&self.keystore)
+            .with_context(|| format!("Failed to open keystore {}", self.keystore.display()))?;
+        let signature = keystore.sign_secure(&self.sender, &tx_data, Intent::sui_transaction())?;
+        let response = self
+            .client
+            .quorum_driver_api()
+            .execute_transaction_block(
+                Transaction::from_data(tx_data, vec![signature]),
+                SuiTransactionBlockResponseOptions::full_content(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await
+            .context("Failed to execute transaction")?;
+
+        let status = response
+            .effects
+            .as_ref()
+            .map(|effects| effects.status().clone())
+            .ok_or_else(|| anyhow!("No effects returned for {}", response.digest))?;
+        self.print(
+            serde_json::to_value(&response)?,
+            format!("{} {}", response.digest, describe_status(&status)),
+        )?;
+        ensure_success(&status)
+    }
+}
+
+/// The Sui CLI's active address, if it is configured
+fn active_address() -> Option<SuiAddress> {
+    let path = sui_config_dir().ok()?.join(SUI_CLIENT_CONFIG);
+    PersistedConfig::<SuiClientConfig>::read(&path)
+        .ok()?
+        .active_address
+}
+
+fn describe_status(status: &SuiExecutionStatus) -> String {
+    match status {
+        SuiExecutionStatus::Success => "succeeded".to_string(),
+        SuiExecutionStatus::Failure { error } => format!("failed: {}", error),
+    }
+}
+
+fn ensure_success(status: &SuiExecutionStatus) -> Result<()> {
+    match status {
+        SuiExecutionStatus::Success => Ok(()),
+        SuiExecutionStatus::Failure { error } => Err(anyhow!("Transaction failed: {}", error)),
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let ctx = Session::connect(&cli).await?;
+    let config = ctx.deep_book.config().clone();
+    let mut ptb = ProgrammableTransactionBuilder::new();
+
+    match cli.command {
+        Command::Pools => {
+            let pools: Vec<Value> = config
+                .pool_keys()
+                .iter()
+                .map(|key| {
+                    let pool = config.get_pool(key);
+                    json!({
+                        "key": key,
+                        "base_coin": pool.base_coin,
+                        "quote_coin": pool.quote_coin,
+                        "address": pool.address,
+                    })
+                })
+                .collect();
+            let text = pools
+                .iter()
+                .map(|pool| {
+                    format!(
+                        "{:<16} {}/{}  {}",
+                        pool["key"].as_str().unwrap_or_default(),
+                        pool["base_coin"].as_str().unwrap_or_default(),
+                        pool["quote_coin"].as_str().unwrap_or_default(),
+                        pool["address"].as_str().unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            ctx.print(Value::Array(pools), text)
+        }
+        Command::Book { pool, ticks } => {
+            ctx.ensure_pool(&pool)?;
+            let pool_info = config.get_pool(&pool);
+            let base_scalar = config.get_coin(&pool_info.base_coin).scalar as f64;
+            let quote_scalar = config.get_coin(&pool_info.quote_coin).scalar as f64;
+            let (bid_prices, bid_sizes, ask_prices, ask_sizes) = ctx
+                .deep_book
+                .get_level2_ticks_from_mid(&pool, ticks)
+                .await?;
+
+            let levels = |prices: Vec<u64>, sizes: Vec<u64>| -> Vec<(f64, f64)> {
+                prices
+                    .into_iter()
+                    .zip(sizes)
+                    .map(|(price, size)| {
+                        (
+                            price as f64 * base_scalar / (FLOAT_SCALAR * quote_scalar),
+                            size as f64 / base_scalar,
+                        )
+                    })
+                    .collect()
+            };
+            let bids = levels(bid_prices, bid_sizes);
+            let asks = levels(ask_prices, ask_sizes);
+
+            let mut text = format!("{:>16} {:>16}\n", "price", "quantity");
+            for (price, size) in asks.iter().rev() {
+                text.push_str(&format!("{:>16} {:>16}  ask\n", price, size));
+            }
+            for (price, size) in &bids {
+                text.push_str(&format!("{:>16} {:>16}  bid\n", price, size));
+            }
+            let side = |levels: &[(f64, f64)]| -> Vec<Value> {
+                levels
+                    .iter()
+                    .map(|(price, size)| json!({ "price": price, "quantity": size }))
+                    .collect()
+            };
+            ctx.print(
+                json!({ "pool": pool, "bids": side(&bids), "asks": side(&asks) }),
+                text.trim_end().to_string(),
+            )
+        }
+        Command::Mid { pool } => {
+            ctx.ensure_pool(&pool)?;
+            let mid_price = ctx.deep_book.get_scaled_mid_price(&pool).await?;
+            ctx.print(
+                json!({ "pool": pool, "mid_price": mid_price }),
+                mid_price.to_string(),
+            )
+        }
+        Command::Balance { manager, coin } => {
+            ctx.ensure_manager(&manager)?;
+            let balances = match coin {
+                Some(coin) => {
+                    ctx.ensure_coin(&coin)?;
+                    let (_, balance) = ctx.deep_book.check_manager_balance(&manager, &coin).await?;
+                    HashMap::from([(coin, balance)])
+                }
+                None => ctx.deep_book.get_manager_balances(&manager).await?,
+            };
+            let mut coins: Vec<_> = balances
+                .iter()
+                .filter(|(_, balance)| **balance != 0.0)
+                .collect();
+            coins.sort_by(|a, b| a.0.cmp(b.0));
+            let text = coins
+                .iter()
+                .map(|(coin, balance)| format!("{:<10} {}", coin, balance))
+                .collect::<Vec<_>>()
+                .join("\n");
+            ctx.print(json!(balances), text)
+        }
+        Command::Deposit {
+            manager,
+            coin,
+            amount,
+        } => {
+            ctx.ensure_manager(&manager)?;
+            ctx.ensure_coin(&coin)?;
+            ctx.deep_book
+                .balance_manager
+                .deposit_into_manager(&mut ptb, &manager, &coin, amount)
+                .await?;
+            ctx.execute(ptb).await
+        }
+        Command::Withdraw {
+            manager,
+            coin,
+            amount,
+            all,
+        } => {
+            ctx.ensure_manager(&manager)?;
+            ctx.ensure_coin(&coin)?;
+            match amount {
+                Some(amount) if !all => {
+                    ctx.deep_book
+                        .balance_manager
+                        .withdraw_from_manager_to(&mut ptb, &manager, &coin, amount, ctx.sender)
+                        .await?
+                }
+                _ => {
+                    ctx.deep_book
+                        .balance_manager
+                        .withdraw_all_from_manager_to(&mut ptb, &manager, &coin, ctx.sender)
+                        .await?
+                }
+            }
+            ctx.execute(ptb).await
+        }
+        Command::Place(args) => {
+            ctx.ensure_pool(&args.pool)?;
+            ctx.ensure_manager(&args.manager)?;
+            let client_order_id = args
+                .client_order_id
+                .unwrap_or_else(|| ClientOrderIdGenerator::new().next_id());
+            let is_bid = matches!(args.side, Side::Buy);
+
+            // Expirations are checked against chain time, not the local clock
+            let time_in_force = match (args.expires_in, args.expiration) {
+                (Some(seconds), _) => Some(TimeInForce::Gtt(Duration::from_secs(seconds))),
+                (None, Some(timestamp)) => Some(TimeInForce::Gtd(timestamp)),
+                (None, None) => None,
+            };
+            let expiration = match time_in_force {
+                Some(time_in_force) => {
+                    let now_ms = ctx.deep_book.get_chain_time_ms().await?;
+                    Some(time_in_force.resolve(now_ms)?.1)
+                }
+                None => None,
+            };
+
+            match args.price {
+                Some(price) => {
+                    let params = PlaceLimitOrderParams {
+                        pool_key: args.pool,
+                        balance_manager_key: args.manager,
+                        client_order_id,
+                        price,
+                        quantity: args.quantity,
+                        is_bid,
+                        expiration,
+                        order_type: Some(args.order_type.into()),
+                        self_matching_option: None,
+                        pay_with_deep: Some(!args.no_deep),
+                    };
+                    ctx.deep_book
+                        .deep_book
+                        .place_limit_order(&mut ptb, &params)
+                        .await?;
+                }
+                None => {
+                    let params = PlaceMarketOrderParams {
+                        pool_key: args.pool,
+                        balance_manager_key: args.manager,
+                        client_order_id,
+                        quantity: args.quantity,
+                        is_bid,
+                        self_matching_option: None,
+                        pay_with_deep: Some(!args.no_deep),
+                    };
+                    ctx.deep_book
+                        .deep_book
+                        .place_market_order(&mut ptb, &params)
+                        .await?;
+                }
+            }
+            ctx.execute(ptb).await
+        }
+        Command::Cancel {
+            pool,
+            manager,
+            order_id,
+            all,
+        } => {
+            ctx.ensure_pool(&pool)?;
+            ctx.ensure_manager(&manager)?;
+            match order_id {
+                Some(order_id) if !all => {
+                    ctx.deep_book
+                        .deep_book
+                        .cancel_order(&mut ptb, &pool, &manager, order_id)
+                        .await?
+                }
+                _ => {
+                    ctx.deep_book
+                        .deep_book
+                        .cancel_all_orders(&mut ptb, &pool, &manager)
+                        .await?
+                }
+            }
+            ctx.execute(ptb).await
+        }
+        Command::Swap {
+            pool,
+            sell,
+            amount,
+            deep_amount,
+            min_out,
+        } => {
+            ctx.ensure_pool(&pool)?;
+            let params = SwapParams {
+                pool_key: pool,
+                amount,
+                deep_amount,
+                min_out,
+            };
+            let (base, quote, deep) = match sell {
+                SwapInput::Base => {
+                    ctx.deep_book
+                        .deep_book
+                        .swap_exact_base_for_quote(&mut ptb, &params)
+                        .await?
+                }
+                SwapInput::Quote => {
+                    ctx.deep_book
+                        .deep_book
+                        .swap_exact_quote_for_base(&mut ptb, &params)
+                        .await?
+                }
+            };
+            transfer_objects(&mut ptb, vec![base, quote, deep], ctx.sender)?;
+            ctx.execute(ptb).await
+        }
+        Command::Stake {
+            pool,
+            manager,
+            amount,
+        } => {
+            ctx.ensure_pool(&pool)?;
+            ctx.ensure_manager(&manager)?;
+            ctx.deep_book
+                .governance
+                .stake(&mut ptb, &pool, &manager, amount)
+                .await?;
+            ctx.execute(ptb).await
+        }
+        Command::Vote {
+            pool,
+            manager,
+            proposal_id,
+        } => {
+            ctx.ensure_pool(&pool)?;
+            ctx.ensure_manager(&manager)?;
+            ctx.deep_book
+                .governance
+                .vote(&mut ptb, &pool, &manager, proposal_id)
+                .await?;
+            ctx.execute(ptb).await
+        }
+        Command::Decode { tx_bytes } => {
+            let tx_data = decode_transaction_data(&tx_bytes)?;
+            let description = describe_transaction(&config, &tx_data)?;
+            let explanation = explain_transaction_data(&config, &tx_data)?;
+            ctx.print(
+                json!({ "description": description, "explanation": explanation }),
+                format!(
+                    "sender {}\ngas budget {} at price {}\n{}",
+                    description.sender,
+                    description.gas_budget,
+                    description.gas_price,
+                    render_explanation(&explanation)
+                ),
+            )
+        }
+        Command::Admin(command) => {
+            let admin = &ctx.deep_book.deep_book_admin;
+            match command {
+                AdminCommand::AdjustTickSize { pool, tick_size } => {
+                    ctx.ensure_pool(&pool)?;
+                    admin
+                        .adjust_tick_size_admin(&mut ptb, &pool, tick_size)
+                        .await?
+                }
+                AdminCommand::AdjustLotSize {
+                    pool,
+                    lot_size,
+                    min_size,
+                } => {
+                    ctx.ensure_pool(&pool)?;
+                    admin
+                        .adjust_min_lot_size_admin(&mut ptb, &pool, lot_size, min_size)
+                        .await?
+                }
+                AdminCommand::AddDeepPricePoint {
+                    pool,
+                    reference_pool,
+                } => {
+                    ctx.ensure_pool(&pool)?;
+                    ctx.ensure_pool(&reference_pool)?;
+                    admin
+                        .add_deep_price_point(&mut ptb, &pool, &reference_pool)
+                        .await?
+                }
+                AdminCommand::UpdatePoolVersions { pool } => {
+                    ctx.ensure_pool(&pool)?;
+                    admin.update_pool_allowed_versions(&mut ptb, &pool).await?
+                }
+                AdminCommand::EnableVersion { version } => {
+                    admin.enable_version(&mut ptb, version).await?
+                }
+                AdminCommand::DisableVersion { version } => {
+                    admin.disable_version(&mut ptb, version).await?
+                }
+                AdminCommand::SetTreasury { address } => {
+                    admin.set_treasury_address(&mut ptb, address).await?
+                }
+                AdminCommand::UnregisterPool { pool } => {
+                    ctx.ensure_pool(&pool)?;
+                    admin.unregister_pool_admin(&mut ptb, &pool).await?
+                }
+                AdminCommand::AddStablecoin { coin } => {
+                    ctx.ensure_coin(&coin)?;
+                    admin.add_stablecoin(&mut ptb, &coin).await?
+                }
+                AdminCommand::RemoveStablecoin { coin } => {
+                    ctx.ensure_coin(&coin)?;
+                    admin.remove_stablecoin(&mut ptb, &coin).await?
+                }
+                AdminCommand::AuthorizeApp { app_type } => {
+                    admin.authorize_app(&mut ptb, &app_type).await?
+                }
+                AdminCommand::DeauthorizeApp { app_type } => {
+                    admin.deauthorize_app(&mut ptb, &app_type).await?
+                }
+            }
+            ctx.execute(ptb).await
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(err) = run(Cli::parse()).await {
+        eprintln!("Error: {:#}", err);
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,206 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+
+use crate::client::DeepBookClient;
+use crate::types::{ClientOrderId, OpenOrder, OrderPlacement};
+use crate::utils::decode_order_id;
+
+/// Last id handed out by any generator in the process
+static LAST_ISSUED: AtomicU64 = AtomicU64::new(0);
+
+/// Hands out increasing client order ids seeded from the clock, so ids stay
+/// unique across restarts while fewer than 1000 are issued per millisecond.
+/// Generators in one process share a sequence, so an engine and an executor
+/// on the same manager never issue the same id.
+#[derive(Debug, Clone, Default)]
+pub struct ClientOrderIdGenerator {
+    last: u64,
+}
+
+impl ClientOrderIdGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Never return `last` or anything below it, e.g. the highest id
+    /// found on chain
+    pub fn starting_after(last: ClientOrderId) -> Self {
+        Self { last: last.0 }
+    }
+
+    pub fn next_id(&mut self) -> ClientOrderId {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64 * 1_000)
+            .unwrap_or_default();
+        let floor = now.max(self.last.saturating_add(1));
+        // Always `Ok`, as the update never declines
+        let previous = LAST_ISSUED
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+                Some(floor.max(last.saturating_add(1)))
+            })
+            .unwrap_or_else(|last| last);
+        self.last = floor.max(previous.saturating_add(1));
+        ClientOrderId(self.last)
+    }
+}
+
+/// A resting order known to the registry
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedOrder {
+    pub pool_key: String,
+    pub order_id: u128,
+    pub client_order_id: ClientOrderId,
+    pub is_bid: bool,
+    pub price: f64,
+    /// Quantity left on the book as of the last placement or sync
+    pub quantity: f64,
+}
+
+/// Changes made by [`OrderRegistry::reconcile`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Reconciliation {
+    /// Open on chain but unknown locally, now tracked
+    pub recovered: Vec<ClientOrderId>,
+    /// Tracked locally but no longer open (filled, cancelled or expired),
+    /// now dropped
+    pub closed: Vec<ClientOrderId>,
+    /// Ids shared by several open orders; only the oldest is tracked
+    pub duplicates: Vec<ClientOrderId>,
+}
+
+/// Maps client order ids to on-chain order ids for one balance manager
+#[derive(Debug, Clone, Default)]
+pub struct OrderRegistry {
+    orders: BTreeMap<ClientOrderId, TrackedOrder>,
+    by_order_id: HashMap<u128, ClientOrderId>,
+}
+
+impl OrderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, client_order_id: ClientOrderId) -> Option<&TrackedOrder> {
+        self.orders.get(&client_order_id)
+    }
+
+    pub fn order_id(&self, client_order_id: ClientOrderId) -> Option<u128> {
+        self.get(client_order_id).map(|order| order.order_id)
+    }
+
+    pub fn client_order_id(&self, order_id: u128) -> Option<ClientOrderId> {
+        self.by_order_id.get(&order_id).copied()
+    }
+
+    /// Tracked orders, by client order id
+    pub fn orders(&self) -> impl Iterator<Item = &TrackedOrder> {
+        self.orders.values()
+    }
+
+    /// Highest id tracked, to resume a [`ClientOrderIdGenerator`] from
+    pub fn last_client_order_id(&self) -> Option<ClientOrderId> {
+        self.orders.keys().next_back().copied()
+    }
+
+    /// Track orders from [`DeepBookClient::order_placements`]. A reused
+    /// client order id replaces the older order.
+    pub fn record_placements(&mut self, placements: &[OrderPlacement]) {
+        for placement in placements {
+            self.insert(TrackedOrder {
+                pool_key: placement.pool_key.clone(),
+                order_id: placement.order_id,
+                client_order_id: placement.client_order_id,
+                is_bid: placement.is_bid,
+                price: placement.price,
+                quantity: placement.quantity,
+            });
+        }
+    }
+
+    /// Stop tracking an order, e.g. after cancelling it
+    pub fn remove(&mut self, client_order_id: ClientOrderId) -> Option<TrackedOrder> {
+        let order = self.orders.remove(&client_order_id)?;
+        self.by_order_id.remove(&order.order_id);
+        Some(order)
+    }
+
+    /// Bring the orders tracked in `pool_key` in line with `open`, the
+    /// manager's open orders on chain
+    pub fn reconcile(&mut self, pool_key: &str, open: &[OpenOrder]) -> Reconciliation {
+        let mut report = Reconciliation::default();
+
+        // Oldest first on each side, bids before asks, so the earliest order
+        // keeps a shared client id. Bid sequence numbers count down from
+        // `u64::MAX` and ask sequence numbers count up.
+        let mut open: Vec<&OpenOrder> = open.iter().collect();
+        open.sort_by_key(|order| {
+            let (is_bid, _, sequence) = decode_order_id(order.order_id);
+            if is_bid {
+                (false, u64::MAX - sequence)
+            } else {
+                (true, sequence)
+            }
+        });
+
+        let mut seen = BTreeSet::new();
+        let mut open_ids = BTreeSet::new();
+        for order in open {
+            if !seen.insert(order.client_order_id) {
+                if !report.duplicates.contains(&order.client_order_id) {
+                    report.duplicates.push(order.client_order_id);
+                }
+                continue;
+            }
+            open_ids.insert(order.order_id);
+
+            let tracked = self.get(order.client_order_id);
+            if tracked.is_none_or(|tracked| tracked.order_id != order.order_id) {
+                report.recovered.push(order.client_order_id);
+            }
+            self.insert(TrackedOrder {
+                pool_key: pool_key.to_string(),
+                order_id: order.order_id,
+                client_order_id: order.client_order_id,
+                is_bid: order.is_bid,
+                price: order.price,
+                quantity: order.quantity,
+            });
+        }
+
+        let closed: Vec<ClientOrderId> = self
+            .orders
+            .values()
+            .filter(|order| order.pool_key == pool_key && !open_ids.contains(&order.order_id))
+            .map(|order| order.client_order_id)
+            .collect();
+        for client_order_id in &closed {
+            self.remove(*client_order_id);
+        }
+        report.closed = closed;
+        report
+    }
+
+    /// Reconcile `pool_key` against the chain, e.g. after a restart
+    pub async fn sync(
+        &mut self,
+        client: &DeepBookClient,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<Reconciliation> {
+        let open = client.get_open_orders(pool_key, manager_key).await?;
+        Ok(self.reconcile(pool_key, &open))
+    }
+
+    fn insert(&mut self, order: TrackedOrder) {
+        if let Some(previous) = self.orders.get(&order.client_order_id) {
+            self.by_order_id.remove(&previous.order_id);
+        }
+        self.by_order_id
+            .insert(order.order_id, order.client_order_id);
+        self.orders.insert(order.client_order_id, order);
+    }
+}
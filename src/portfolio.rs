@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use sui_sdk::types::event::EventID;
+
+use crate::client::DeepBookClient;
+use crate::errors::QueryError;
+use crate::types::OrderFill;
+use crate::utils::config::MAX_EVENT_PAGES;
+
+/// Net base position in one pool, with an average-cost basis in the quote coin.
+/// Fees paid in DEEP are tracked separately and not deducted from PnL.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub base_coin: String,
+    pub quote_coin: String,
+    /// Signed: negative when short
+    pub base: f64,
+    /// Quote paid for the open position, signed like `base`
+    pub cost: f64,
+    /// Realized PnL in quote
+    pub realized: f64,
+    pub deep_fees: f64,
+}
+
+impl Position {
+    pub fn new(base_coin: &str, quote_coin: &str) -> Self {
+        Self {
+            base_coin: base_coin.to_string(),
+            quote_coin: quote_coin.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn apply_fill(&mut self, fill: &OrderFill) {
+        self.deep_fees += fill.deep_fee;
+        if fill.base_quantity <= 0.0 {
+            return;
+        }
+
+        let price = fill.quote_quantity / fill.base_quantity;
+        let mut quantity = if fill.is_buy {
+            fill.base_quantity
+        } else {
+            -fill.base_quantity
+        };
+
+        // Close against the open position first, realizing the difference
+        // between the fill price and the average cost
+        if self.base != 0.0 && self.base.signum() != quantity.signum() {
+            let average_cost = self.cost / self.base;
+            let closing = quantity.abs().min(self.base.abs()) * quantity.signum();
+
+            self.realized += (price - average_cost) * -closing;
+            self.base += closing;
+            self.cost += average_cost * closing;
+            quantity -= closing;
+
+            if self.base.abs() < f64::EPSILON {
+                self.base = 0.0;
+                self.cost = 0.0;
+            }
+        }
+
+        self.base += quantity;
+        self.cost += price * quantity;
+    }
+
+    pub fn average_cost(&self) -> Option<f64> {
+        (self.base != 0.0).then(|| self.cost / self.base)
+    }
+
+    /// Unrealized PnL in quote at `mid_price`
+    pub fn unrealized(&self, mid_price: f64) -> f64 {
+        self.base * mid_price - self.cost
+    }
+}
+
+/// A manager's holdings valued in a numeraire coin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Valuation {
+    pub numeraire: String,
+    /// Coin key -> amount, including funds held by pools
+    pub holdings: HashMap<String, f64>,
+    /// Coin key -> value in the numeraire
+    pub values: HashMap<String, f64>,
+    /// Coins held but without a pool path to the numeraire
+    pub unpriced: Vec<String>,
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    pub timestamp_ms: u64,
+    pub total_value: f64,
+    /// In the numeraire
+    pub realized_pnl: f64,
+    /// In the numeraire
+    pub unrealized_pnl: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManagerHistory {
+    /// Last fill event processed
+    pub cursor: Option<EventID>,
+    /// Whether positions were seeded from holdings on the first sync
+    #[serde(default)]
+    pub seeded: bool,
+    /// Pool key -> position built from fills
+    pub positions: HashMap<String, Position>,
+    pub snapshots: Vec<PortfolioSnapshot>,
+}
+
+/// Value a balance manager in `numeraire`: manager balances, funds locked by
+/// open orders or settled in pools, and unclaimed rebates, net of what is owed
+pub async fn value_manager(
+    client: &DeepBookClient,
+    manager_key: &str,
+    numeraire: &str,
+) -> Result<Valuation> {
+    let holdings = manager_holdings(client, manager_key).await?;
+
+    let mut values = HashMap::new();
+    let mut unpriced = vec![];
+    for (coin_key, amount) in &holdings {
+        if *amount == 0.0 {
+            continue;
+        }
+        match client.get_coin_price(coin_key, numeraire).await? {
+            Some(price) => {
+                values.insert(coin_key.clone(), amount * price);
+            }
+            None => unpriced.push(coin_key.clone()),
+        }
+    }
+    unpriced.sort();
+
+    Ok(Valuation {
+        numeraire: numeraire.to_string(),
+        total: values.values().sum(),
+        holdings,
+        values,
+        unpriced,
+    })
+}
+
+/// Coin key -> amount a manager holds, including funds held by pools
+async fn manager_holdings(
+    client: &DeepBookClient,
+    manager_key: &str,
+) -> Result<HashMap<String, f64>> {
+    let report = client.get_manager_balance_report(manager_key).await?;
+
+    let mut holdings = report.balances;
+    for pool in report.pools.values() {
+        // `locked` already includes what is settled
+        let base = pool.locked.base + pool.unclaimed_rebates.base - pool.owed.base;
+        let quote = pool.locked.quote + pool.unclaimed_rebates.quote - pool.owed.quote;
+        let deep = pool.locked.deep + pool.unclaimed_rebates.deep - pool.owed.deep;
+
+        *holdings.entry(pool.base_coin.clone()).or_default() += base;
+        *holdings.entry(pool.quote_coin.clone()).or_default() += quote;
+        *holdings.entry("DEEP".to_string()).or_default() += deep;
+    }
+    Ok(holdings)
+}
+
+/// Positions and valuation history per balance manager, persisted as JSON
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PortfolioTracker {
+    pub numeraire: String,
+    managers: HashMap<String, ManagerHistory>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl PortfolioTracker {
+    /// Load the tracker stored at `path`, or start an empty one
+    pub fn open(path: impl AsRef<Path>, numeraire: &str) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if !path.exists() {
+            return Ok(Self {
+                numeraire: numeraire.to_string(),
+                managers: HashMap::new(),
+                path,
+            });
+        }
+
+        let data = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read portfolio file {}", path.display()))?;
+        let mut tracker: Self = serde_json::from_str(&data)
+            .with_context(|| format!("Invalid portfolio file {}", path.display()))?;
+        anyhow::ensure!(
+            tracker.numeraire == numeraire,
+            "Portfolio file {} is valued in {}, not {}",
+            path.display(),
+            tracker.numeraire,
+            numeraire
+        );
+        tracker.path = path;
+        Ok(tracker)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        // Write then rename so a crash never leaves a truncated file
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+
+    pub fn history(&self, manager_key: &str) -> Option<&ManagerHistory> {
+        self.managers.get(manager_key)
+    }
+
+    /// Apply fills since the last sync to the manager's positions.
+    /// Returns the number of new fills. The first sync starts at the newest
+    /// fill rather than replaying the manager's whole history, and seeds
+    /// positions from what the manager holds instead: each base coin goes
+    /// to the first pool, by key, that trades it and has a mid, at a cost
+    /// of that mid. PnL therefore counts from the first sync.
+    pub async fn sync_fills(
+        &mut self,
+        client: &DeepBookClient,
+        manager_key: &str,
+    ) -> Result<usize> {
+        let history = self.managers.entry(manager_key.to_string()).or_default();
+        if !history.seeded && history.cursor.is_none() {
+            history.positions = seed_positions(client, manager_key).await?;
+            history.seeded = true;
+        }
+        let (fills, cursor) = client
+            .get_order_fills(manager_key, history.cursor.clone(), MAX_EVENT_PAGES)
+            .await?;
+
+        for fill in &fills {
+            history
+                .positions
+                .entry(fill.pool_key.clone())
+                .or_insert_with(|| Position::new(&fill.base_coin, &fill.quote_coin))
+                .apply_fill(fill);
+        }
+        history.cursor = cursor;
+
+        Ok(fills.len())
+    }
+
+    /// Sync fills, value the manager and record a snapshot, then save
+    pub async fn snapshot(
+        &mut self,
+        client: &DeepBookClient,
+        manager_key: &str,
+    ) -> Result<PortfolioSnapshot> {
+        self.sync_fills(client, manager_key).await?;
+        let valuation = value_manager(client, manager_key, &self.numeraire).await?;
+
+        let mut realized_pnl = 0.0;
+        let mut unrealized_pnl = 0.0;
+        let history = self.managers.entry(manager_key.to_string()).or_default();
+        for (pool_key, position) in &history.positions {
+            let quote_price = client
+                .get_coin_price(&position.quote_coin, &self.numeraire)
+                .await?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No pool path to price {} in {}",
+                        position.quote_coin,
+                        self.numeraire
+                    )
+                })?;
+            realized_pnl += position.realized * quote_price;
+            if position.base != 0.0 {
+                let mid_price = client.get_scaled_mid_price(pool_key).await?;
+                unrealized_pnl += position.unrealized(mid_price) * quote_price;
+            }
+        }
+
+        let snapshot = PortfolioSnapshot {
+            timestamp_ms: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64,
+            total_value: valuation.total,
+            realized_pnl,
+            unrealized_pnl,
+        };
+        history.snapshots.push(snapshot.clone());
+        self.save()?;
+
+        Ok(snapshot)
+    }
+}
+
+/// Open positions at current mid for the base coins a manager holds
+async fn seed_positions(
+    client: &DeepBookClient,
+    manager_key: &str,
+) -> Result<HashMap<String, Position>> {
+    let mut holdings = manager_holdings(client, manager_key).await?;
+
+    let mut positions = HashMap::new();
+    for pool_key in client.config().pool_keys() {
+        let pool = client.config().get_pool(&pool_key);
+        let Some(amount) = holdings.get(pool.base_coin.as_str()).copied() else {
+            continue;
+        };
+        if amount == 0.0 {
+            continue;
+        }
+        let mid = match client.get_scaled_mid_price(&pool_key).await {
+            Ok(mid) => mid,
+            // An empty book can't price the holding; try the next pool
+            Err(e) if e.downcast_ref::<QueryError>().is_some() => continue,
+            Err(e) => return Err(e),
+        };
+
+        let mut position = Position::new(&pool.base_coin, &pool.quote_coin);
+        position.base = amount;
+        position.cost = amount * mid;
+        positions.insert(pool_key, position);
+        holdings.remove(pool.base_coin.as_str());
+    }
+    Ok(positions)
+}
@@ -0,0 +1,357 @@
+//! Pre-trade risk checks in front of the `DeepBookContract` order builders,
+//! and a kill switch that cancels every order of a manager.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+use crate::client::DeepBookClient;
+use crate::errors::{QueryError, RiskError};
+use crate::transactions::deep_book::ladder_orders;
+use crate::types::{BookParams, LadderParams, PlaceLimitOrderParams, PlaceMarketOrderParams};
+
+// Rounding slack when comparing human-unit amounts
+const EPSILON: f64 = 1e-9;
+
+/// Limits checked before orders are built. Unset limits are not enforced.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskLimits {
+    /// Largest quote value of one order
+    pub max_order_notional: Option<f64>,
+    /// Largest balance of each coin, by coin key, the manager may hold once
+    /// its resting and new orders fill
+    pub max_position: HashMap<String, f64>,
+    /// Largest distance of a limit price from mid, as a fraction of mid
+    pub price_collar: Option<f64>,
+    /// Largest number of open orders per pool, counting new ones
+    pub max_open_orders: Option<usize>,
+    /// Refuse orders the manager's settled balance can't pay for, before fees
+    pub require_balance: bool,
+}
+
+/// An order as the risk checks see it, in human units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderIntent {
+    pub is_bid: bool,
+    pub quantity: f64,
+    /// `None` for market orders, which are valued at mid and never rest
+    pub price: Option<f64>,
+}
+
+impl From<&PlaceLimitOrderParams> for OrderIntent {
+    fn from(params: &PlaceLimitOrderParams) -> Self {
+        Self {
+            is_bid: params.is_bid,
+            quantity: params.quantity,
+            price: Some(params.price),
+        }
+    }
+}
+
+impl From<&PlaceMarketOrderParams> for OrderIntent {
+    fn from(params: &PlaceMarketOrderParams) -> Self {
+        Self {
+            is_bid: params.is_bid,
+            quantity: params.quantity,
+            price: None,
+        }
+    }
+}
+
+/// State of one pool and manager that orders are checked against
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskSnapshot {
+    pub pool_key: String,
+    pub base_coin: String,
+    pub quote_coin: String,
+    /// `None` when the book has no mid price
+    pub mid: Option<f64>,
+    /// Settled manager balances, by coin key
+    pub balances: HashMap<String, f64>,
+    pub open_orders: usize,
+    /// What the manager's resting orders in the pool add to each coin once
+    /// filled, by coin key
+    pub open_exposure: HashMap<String, f64>,
+}
+
+impl RiskLimits {
+    /// Check `orders`, all in the snapshot's pool, as if placed together
+    pub fn check(&self, orders: &[OrderIntent], snapshot: &RiskSnapshot) -> Result<(), RiskError> {
+        let no_mid = || RiskError::NoMidPrice {
+            pool_key: snapshot.pool_key.clone(),
+        };
+
+        let (mut base_in, mut base_out, mut quote_in, mut quote_out) = (0.0, 0.0, 0.0, 0.0);
+        for order in orders {
+            let price = match order.price {
+                Some(price) => price,
+                None => snapshot.mid.ok_or_else(no_mid)?,
+            };
+            let notional = order.quantity * price;
+
+            let too_large = |max: &f64| notional > max + EPSILON;
+            if let Some(max) = self.max_order_notional.filter(too_large) {
+                return Err(RiskError::NotionalTooLarge { notional, max });
+            }
+            if let (Some(max_deviation), Some(price)) = (self.price_collar, order.price) {
+                let mid = snapshot.mid.ok_or_else(no_mid)?;
+                if (price - mid).abs() > max_deviation * mid + EPSILON {
+                    return Err(RiskError::OutsideCollar {
+                        price,
+                        mid,
+                        max_deviation,
+                    });
+                }
+            }
+
+            if order.is_bid {
+                base_in += order.quantity;
+                quote_out += notional;
+            } else {
+                base_out += order.quantity;
+                quote_in += notional;
+            }
+        }
+
+        if let Some(max) = self.max_open_orders {
+            let resting = orders.iter().filter(|order| order.price.is_some()).count();
+            let open = snapshot.open_orders + resting;
+            if resting > 0 && open > max {
+                return Err(RiskError::TooManyOpenOrders {
+                    pool_key: snapshot.pool_key.clone(),
+                    open,
+                    max,
+                });
+            }
+        }
+
+        let balance = |coin: &str| snapshot.balances.get(coin).copied().unwrap_or_default();
+        if self.require_balance {
+            for (coin, required) in [
+                (&snapshot.quote_coin, quote_out),
+                (&snapshot.base_coin, base_out),
+            ] {
+                let available = balance(coin);
+                if required > available + EPSILON {
+                    return Err(RiskError::InsufficientBalance {
+                        coin: coin.clone(),
+                        required,
+                        available,
+                    });
+                }
+            }
+        }
+        for (coin, added) in [
+            (&snapshot.base_coin, base_in),
+            (&snapshot.quote_coin, quote_in),
+        ] {
+            let Some(&max) = self.max_position.get(coin) else {
+                continue;
+            };
+            let resting = snapshot
+                .open_exposure
+                .get(coin)
+                .copied()
+                .unwrap_or_default();
+            let position = balance(coin) + resting + added;
+            if added > 0.0 && position > max + EPSILON {
+                return Err(RiskError::PositionTooLarge {
+                    coin: coin.clone(),
+                    position,
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Pools handled by [`RiskManager::kill_switch`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KillSwitchReport {
+    /// Pools whose orders the PTB cancels
+    pub cancelled: Vec<String>,
+    /// Pools that could not be checked, with the error. Orders there may
+    /// still be resting.
+    pub failed: Vec<(String, String)>,
+}
+
+/// Builds orders only when they pass [`RiskLimits`], and refuses all of
+/// them once the kill switch is engaged
+pub struct RiskManager {
+    client: DeepBookClient,
+    pub limits: RiskLimits,
+    killed: bool,
+}
+
+impl RiskManager {
+    pub fn new(client: DeepBookClient, limits: RiskLimits) -> Self {
+        Self {
+            client,
+            limits,
+            killed: false,
+        }
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.killed
+    }
+
+    /// Accept orders again after [`RiskManager::kill_switch`]
+    pub fn reset_kill_switch(&mut self) {
+        self.killed = false;
+    }
+
+    /// Read what the checks need for `manager_key` in `pool_key`
+    pub async fn snapshot(&self, pool_key: &str, manager_key: &str) -> Result<RiskSnapshot> {
+        let pool = self.client.config().get_pool(pool_key);
+        // An empty book has no mid; only checks that need it refuse orders
+        let mid = match self.client.get_scaled_mid_price(pool_key).await {
+            Ok(mid) => Some(mid),
+            Err(e) if e.downcast_ref::<QueryError>().is_some() => None,
+            Err(e) => return Err(e),
+        };
+        let balances = self.client.get_manager_balances(manager_key).await?;
+
+        let needs_orders =
+            self.limits.max_open_orders.is_some() || !self.limits.max_position.is_empty();
+        let orders = if needs_orders && self.client.account_exists(pool_key, manager_key).await? {
+            self.client.get_open_orders(pool_key, manager_key).await?
+        } else {
+            vec![]
+        };
+        let mut open_exposure = HashMap::new();
+        for order in &orders {
+            let (coin, amount) = if order.is_bid {
+                (pool.base_coin.to_string(), order.quantity)
+            } else {
+                (pool.quote_coin.to_string(), order.quantity * order.price)
+            };
+            *open_exposure.entry(coin).or_insert(0.0) += amount;
+        }
+
+        Ok(RiskSnapshot {
+            pool_key: pool_key.to_string(),
+            base_coin: pool.base_coin.to_string(),
+            quote_coin: pool.quote_coin.to_string(),
+            mid,
+            balances,
+            open_orders: orders.len(),
+            open_exposure,
+        })
+    }
+
+    /// Refuse `orders` unless they pass the limits against current state
+    pub async fn check(
+        &self,
+        pool_key: &str,
+        manager_key: &str,
+        orders: &[OrderIntent],
+    ) -> Result<()> {
+        if self.killed {
+            return Err(RiskError::KillSwitchEngaged.into());
+        }
+        let snapshot = self.snapshot(pool_key, manager_key).await?;
+        self.limits.check(orders, &snapshot)?;
+        Ok(())
+    }
+
+    pub async fn place_limit_order(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        params: &PlaceLimitOrderParams,
+    ) -> Result<()> {
+        self.check(
+            &params.pool_key,
+            &params.balance_manager_key,
+            &[params.into()],
+        )
+        .await?;
+        self.client.deep_book.place_limit_order(ptb, params).await
+    }
+
+    pub async fn place_market_order(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        params: &PlaceMarketOrderParams,
+    ) -> Result<()> {
+        self.check(
+            &params.pool_key,
+            &params.balance_manager_key,
+            &[params.into()],
+        )
+        .await?;
+        self.client.deep_book.place_market_order(ptb, params).await
+    }
+
+    /// Check every level of the ladder together, then build it
+    pub async fn place_ladder(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        params: &LadderParams,
+    ) -> Result<Vec<PlaceLimitOrderParams>> {
+        if self.killed {
+            return Err(RiskError::KillSwitchEngaged.into());
+        }
+        let (tick_size, lot_size, min_size) =
+            self.client.get_pool_book_params(&params.pool_key).await?;
+        let book = BookParams {
+            tick_size,
+            lot_size,
+            min_size,
+        };
+        let orders: Vec<OrderIntent> = ladder_orders(params, &book)?
+            .iter()
+            .map(OrderIntent::from)
+            .collect();
+        self.check(&params.pool_key, &params.balance_manager_key, &orders)
+            .await?;
+        self.client.deep_book.place_ladder(ptb, params, &book).await
+    }
+
+    /// Refuse further orders and add `cancel_all_orders` for every
+    /// configured pool where `manager_key` has an account. A pool that
+    /// can't be checked or cancelled is reported and the rest still go
+    /// ahead.
+    pub async fn kill_switch(
+        &mut self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+    ) -> Result<KillSwitchReport> {
+        self.killed = true;
+
+        let mut pool_keys = self.client.config().pool_keys();
+        pool_keys.sort();
+        let mut report = KillSwitchReport::default();
+        for pool_key in pool_keys {
+            match self.cancel_all(ptb, &pool_key, manager_key).await {
+                Ok(true) => report.cancelled.push(pool_key),
+                Ok(false) => {}
+                Err(error) => report.failed.push((pool_key, format!("{:#}", error))),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Add `cancel_all_orders` for `pool_key` if the manager has an account
+    /// there
+    async fn cancel_all(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<bool> {
+        // cancel_all_orders aborts the transaction without an account
+        if !self.client.account_exists(pool_key, manager_key).await? {
+            return Ok(false);
+        }
+        self.client
+            .deep_book
+            .cancel_all_orders(ptb, pool_key, manager_key)
+            .await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use sui_sdk::SuiClient;
+use sui_sdk::types::base_types::SuiAddress;
+use sui_sdk::types::event::EventID;
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+use super::{Action, BookSnapshot, Exchange, Inventory, QuotingParams};
+use crate::client::DeepBookClient;
+use crate::types::{
+    OpenOrder, OrderFill, OrderType, PlaceLimitOrderParams, PlaceMarketOrderParams,
+};
+use crate::utils::config::{GAS_BUDGET, MAX_EVENT_PAGES};
+use crate::utils::offline::{TransactionSigner, decode_transaction_data, sign_and_execute};
+
+/// Quotes one pool from one balance manager on DeepBook
+pub struct DeepBookExchange<T> {
+    client: SuiClient,
+    deep_book: DeepBookClient,
+    signer: T,
+    sender: SuiAddress,
+    pool_key: String,
+    manager_key: String,
+    /// Ticks each side of mid read into the book snapshot
+    depth: u64,
+    fill_cursor: Option<EventID>,
+    /// Filled quantity per live order, as modify takes the new total
+    filled: HashMap<u128, f64>,
+}
+
+impl<T: TransactionSigner> DeepBookExchange<T> {
+    pub fn new(
+        client: SuiClient,
+        deep_book: DeepBookClient,
+        signer: T,
+        sender: SuiAddress,
+        pool_key: &str,
+        manager_key: &str,
+        depth: u64,
+    ) -> Self {
+        Self {
+            client,
+            deep_book,
+            signer,
+            sender,
+            pool_key: pool_key.to_string(),
+            manager_key: manager_key.to_string(),
+            depth,
+            fill_cursor: None,
+            filled: HashMap::new(),
+        }
+    }
+}
+
+impl<T: TransactionSigner> Exchange for DeepBookExchange<T> {
+    async fn book(&mut self) -> Result<BookSnapshot> {
+        let (bids, asks) = self
+            .deep_book
+            .get_scaled_level2_ticks_from_mid(&self.pool_key, self.depth)
+            .await?;
+        Ok(BookSnapshot {
+            bids,
+            asks,
+            timestamp_ms: SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64,
+        })
+    }
+
+    async fn open_orders(&mut self) -> Result<Vec<OpenOrder>> {
+        let orders = self
+            .deep_book
+            .get_open_orders(&self.pool_key, &self.manager_key)
+            .await?;
+        self.filled = orders
+            .iter()
+            .map(|order| (order.order_id, order.filled_quantity))
+            .collect();
+        Ok(orders)
+    }
+
+    /// Fills in this pool since the previous call. The first call only
+    /// finds where existing history ends.
+    async fn fills(&mut self) -> Result<Vec<OrderFill>> {
+        let (fills, cursor) = self
+            .deep_book
+            .get_order_fills(&self.manager_key, self.fill_cursor, MAX_EVENT_PAGES)
+            .await?;
+        self.fill_cursor = cursor;
+        Ok(fills
+            .into_iter()
+            .filter(|fill| fill.pool_key == self.pool_key)
+            .collect())
+    }
+
+    /// Free balances in the manager, and what this pool holds for it
+    async fn inventory(&mut self) -> Result<Inventory> {
+        let balances = self
+            .deep_book
+            .get_manager_balances(&self.manager_key)
+            .await?;
+        let locked = self
+            .deep_book
+            .get_scaled_locked_balances(&self.pool_key, &self.manager_key)
+            .await?;
+        let pool = self.deep_book.config().get_pool(&self.pool_key);
+        let balance = |coin: &str| balances.get(coin).copied().unwrap_or_default();
+        Ok(Inventory {
+            base: balance(&pool.base_coin),
+            quote: balance(&pool.quote_coin),
+            locked_base: locked.base,
+            locked_quote: locked.quote,
+        })
+    }
+
+    async fn execute(&mut self, actions: &[Action], params: &QuotingParams) -> Result<()> {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        let trade_proof = self
+            .deep_book
+            .balance_manager
+            .generate_proof(&mut ptb, &self.manager_key)
+            .await?;
+
+        let contract = &self.deep_book.deep_book;
+        for action in actions {
+            match action {
+                Action::Cancel { order_id } => {
+                    contract
+                        .cancel_order_with_proof(
+                            &mut ptb,
+                            &self.pool_key,
+                            &self.manager_key,
+                            *order_id,
+                            trade_proof,
+                        )
+                        .await?
+                }
+                Action::Modify { order_id, quantity } => {
+                    let filled = self.filled.get(order_id).copied().unwrap_or_default();
+                    contract
+                        .modify_order_with_proof(
+                            &mut ptb,
+                            &self.pool_key,
+                            &self.manager_key,
+                            *order_id,
+                            filled + quantity,
+                            trade_proof,
+                        )
+                        .await?
+                }
+                Action::Place {
+                    quote,
+                    client_order_id,
+                } => {
+                    let order_type = if params.post_only {
+                        OrderType::PostOnly
+                    } else {
+                        OrderType::NoRestriction
+                    };
+                    let order = PlaceLimitOrderParams {
+                        pool_key: self.pool_key.clone(),
+                        balance_manager_key: self.manager_key.clone(),
+                        client_order_id: *client_order_id,
+                        price: quote.price,
+                        quantity: quote.quantity,
+                        is_bid: quote.is_bid,
+                        expiration: None,
+                        order_type: Some(order_type),
+                        self_matching_option: None,
+                        pay_with_deep: Some(params.pay_with_deep),
+                    };
+                    contract
+                        .place_limit_order_with_proof(&mut ptb, &order, trade_proof)
+                        .await?
+                }
+                Action::Take {
+                    is_bid,
+                    quantity,
+                    limit_price: Some(price),
+                    client_order_id,
+                } => {
+                    let order = PlaceLimitOrderParams {
+                        pool_key: self.pool_key.clone(),
+                        balance_manager_key: self.manager_key.clone(),
+                        client_order_id: *client_order_id,
+                        price: *price,
+                        quantity: *quantity,
+                        is_bid: *is_bid,
+                        expiration: None,
+                        order_type: Some(OrderType::ImmediateOrCancel),
+                        self_matching_option: None,
+                        pay_with_deep: Some(params.pay_with_deep),
+                    };
+                    contract
+                        .place_limit_order_with_proof(&mut ptb, &order, trade_proof)
+                        .await?
+                }
+                Action::Take {
+                    is_bid,
+                    quantity,
+                    limit_price: None,
+                    client_order_id,
+                } => {
+                    let order = PlaceMarketOrderParams {
+                        pool_key: self.pool_key.clone(),
+                        balance_manager_key: self.manager_key.clone(),
+                        client_order_id: *client_order_id,
+                        quantity: *quantity,
+                        is_bid: *is_bid,
+                        self_matching_option: None,
+                        pay_with_deep: Some(params.pay_with_deep),
+                    };
+                    contract
+                        .place_market_order_with_proof(&mut ptb, &order, trade_proof)
+                        .await?
+                }
+            }
+        }
+
+        let unsigned = self
+            .deep_book
+            .build_transaction_bytes(self.sender, None, GAS_BUDGET as u64, ptb)
+            .await?;
+        let tx_data = decode_transaction_data(&unsigned.tx_bytes)?;
+        sign_and_execute(&self.client, &self.signer, tx_data).await?;
+        Ok(())
+    }
+}
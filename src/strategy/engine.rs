@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+
+use super::{Action, Exchange, QuotingParams, Strategy, plan_actions};
+use crate::orders::ClientOrderIdGenerator;
+use crate::types::{ClientOrderId, OpenOrder};
+
+/// Runs a strategy against an exchange, one transaction per tick. Only
+/// orders and fills with client order ids the engine issued are its own, so
+/// an [`Executor`](super::Executor) or manual orders may share the pool and
+/// manager. Orders left by an earlier engine are not adopted.
+pub struct Engine<S, X> {
+    pub strategy: S,
+    pub exchange: X,
+    pub params: QuotingParams,
+    ids: ClientOrderIdGenerator,
+    /// Client order ids of every order placed
+    issued: HashSet<ClientOrderId>,
+}
+
+impl<S: Strategy, X: Exchange> Engine<S, X> {
+    pub fn new(strategy: S, exchange: X, params: QuotingParams) -> Self {
+        Self {
+            strategy,
+            exchange,
+            params,
+            ids: ClientOrderIdGenerator::new(),
+            issued: HashSet::new(),
+        }
+    }
+
+    /// Feed the strategy, then bring live orders in line with its quotes.
+    /// Returns the actions sent, empty when nothing changed.
+    pub async fn tick(&mut self, now_ms: u64) -> Result<Vec<Action>> {
+        self.strategy.on_timer(now_ms);
+        for fill in self.exchange.fills().await? {
+            if self.issued.contains(&fill.client_order_id) {
+                self.strategy.on_fill(&fill);
+            }
+        }
+
+        // Our own quotes would otherwise move mid and block requotes as
+        // post-only crossers
+        let live: Vec<OpenOrder> = self
+            .exchange
+            .open_orders()
+            .await?
+            .into_iter()
+            .filter(|order| self.issued.contains(&order.client_order_id))
+            .collect();
+        let book = self.exchange.book().await?.excluding(&live);
+        let inventory = self.exchange.inventory().await?;
+        let desired = self.strategy.on_book(&book, &inventory);
+
+        let actions = plan_actions(
+            &desired,
+            &live,
+            &book,
+            &inventory,
+            &self.params,
+            &mut self.ids,
+        );
+        if !actions.is_empty() {
+            self.exchange.execute(&actions, &self.params).await?;
+        }
+        for action in &actions {
+            if let Action::Place {
+                client_order_id, ..
+            } = action
+            {
+                self.issued.insert(*client_order_id);
+            }
+        }
+        Ok(actions)
+    }
+
+    /// Tick every `interval`, `ticks` times
+    pub async fn run(&mut self, interval: Duration, ticks: usize) -> Result<()> {
+        for _ in 0..ticks {
+            let now_ms = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+            self.tick(now_ms).await?;
+            tokio::time::sleep(interval).await;
+        }
+        Ok(())
+    }
+}
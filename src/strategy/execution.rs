@@ -0,0 +1,460 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+
+use super::{
+    Action, BookSnapshot, Exchange, Quote, QuotingParams, ceil_units, floor_units, to_units,
+};
+use crate::orders::ClientOrderIdGenerator;
+use crate::types::{ClientOrderId, OpenOrder};
+
+/// How a parent order is worked
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    /// Split the parent over `slices` equal intervals of `duration`, sending
+    /// one child at the start of each that catches up with the schedule.
+    /// Aggressive children are market orders, or IOC at the parent's limit
+    /// price. Passive children rest at the touch and are replaced each
+    /// slice; whatever is unfilled at the end is left undone.
+    Twap {
+        duration: Duration,
+        slices: usize,
+        passive: bool,
+    },
+    /// Rest at most `clip` at the parent's limit price, placing the next
+    /// clip once the previous one has filled or left the book
+    Iceberg { clip: f64 },
+}
+
+/// A large order to work through child orders, in human units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParentOrder {
+    pub is_bid: bool,
+    pub quantity: f64,
+    /// Children never trade worse than this. Required for icebergs, which
+    /// rest at it.
+    pub limit_price: Option<f64>,
+}
+
+/// Progress of a parent order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionReport {
+    pub is_bid: bool,
+    /// Base filled so far
+    pub filled: f64,
+    /// Quote paid or received for `filled`
+    pub notional: f64,
+    /// Child orders sent
+    pub children: usize,
+    /// Mid when execution started, without our orders
+    pub arrival_mid: Option<f64>,
+}
+
+impl ExecutionReport {
+    pub fn average_price(&self) -> Option<f64> {
+        (self.filled > 0.0).then(|| self.notional / self.filled)
+    }
+
+    /// Cost of the average price against arrival mid, in basis points.
+    /// Positive when buys paid more or sells received less.
+    pub fn slippage_bps(&self) -> Option<f64> {
+        let arrival = self.arrival_mid?;
+        let difference = self.average_price()? - arrival;
+        let cost = if self.is_bid { difference } else { -difference };
+        Some(cost / arrival * 10_000.0)
+    }
+}
+
+/// Works one parent order on an exchange, one transaction per tick. Fills
+/// count towards the parent by client order id, so other strategies may
+/// trade the same pool and manager. Children are aligned to the tick and lot
+/// sizes in `params`, and a child below `min_size` waits for a later slice.
+/// Passive and iceberg children use the order type in `params`. Children
+/// are sized from what was sent less what is known unfilled, so quantity
+/// that traded before its fills were reported is not sent again.
+pub struct Executor<X> {
+    pub exchange: X,
+    pub params: QuotingParams,
+    parent: ParentOrder,
+    algorithm: Algorithm,
+    report: ExecutionReport,
+    start_ms: Option<u64>,
+    /// TWAP slices sent so far
+    slices_sent: usize,
+    /// Base sent in children, to tell when fills are still to be reported
+    sent: f64,
+    /// Base of children known to have left the book unfilled
+    unfilled: f64,
+    /// Takes sent on the previous tick, as (client order id, quantity)
+    takes: Vec<(ClientOrderId, f64)>,
+    /// Base reported filled per child
+    children: HashMap<ClientOrderId, f64>,
+    /// Quantity of children placed to rest that have not yet left the book
+    resting: HashMap<ClientOrderId, f64>,
+    ids: ClientOrderIdGenerator,
+    done: bool,
+}
+
+impl<X: Exchange> Executor<X> {
+    pub fn new(
+        exchange: X,
+        params: QuotingParams,
+        parent: ParentOrder,
+        algorithm: Algorithm,
+    ) -> Result<Self> {
+        anyhow::ensure!(
+            parent.quantity >= params.min_size,
+            "Parent quantity {} is below the pool's min size {}",
+            parent.quantity,
+            params.min_size
+        );
+        match algorithm {
+            Algorithm::Twap { slices, .. } => {
+                anyhow::ensure!(slices > 0, "A TWAP needs at least one slice");
+            }
+            Algorithm::Iceberg { clip } => {
+                anyhow::ensure!(
+                    parent.limit_price.is_some(),
+                    "An iceberg needs a limit price"
+                );
+                anyhow::ensure!(
+                    clip >= params.min_size,
+                    "Clip {} is below the pool's min size {}",
+                    clip,
+                    params.min_size
+                );
+            }
+        }
+
+        Ok(Self {
+            exchange,
+            params,
+            parent,
+            algorithm,
+            report: ExecutionReport {
+                is_bid: parent.is_bid,
+                ..Default::default()
+            },
+            start_ms: None,
+            slices_sent: 0,
+            sent: 0.0,
+            unfilled: 0.0,
+            takes: vec![],
+            children: HashMap::new(),
+            resting: HashMap::new(),
+            ids: ClientOrderIdGenerator::new(),
+            done: false,
+        })
+    }
+
+    pub fn report(&self) -> &ExecutionReport {
+        &self.report
+    }
+
+    /// Base still to fill
+    pub fn remaining(&self) -> f64 {
+        (self.parent.quantity - self.report.filled).max(0.0)
+    }
+
+    /// Whether the parent is filled, out of time or cancelled
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Count new fills, then send the children due at `now_ms`. Returns the
+    /// actions sent, empty when nothing was due.
+    pub async fn tick(&mut self, now_ms: u64) -> Result<Vec<Action>> {
+        if self.done {
+            return Ok(vec![]);
+        }
+
+        let mut reported = HashSet::new();
+        for fill in self.exchange.fills().await? {
+            if let Some(filled) = self.children.get_mut(&fill.client_order_id) {
+                *filled += fill.base_quantity;
+                self.report.filled += fill.base_quantity;
+                self.report.notional += fill.quote_quantity;
+                reported.insert(fill.client_order_id);
+            }
+        }
+        // Takes trade within our own transaction, so their fills are in by
+        // now and the rest was cancelled
+        for (client_order_id, quantity) in std::mem::take(&mut self.takes) {
+            self.unfilled += quantity - self.children[&client_order_id];
+        }
+        let live = self.live_children().await?;
+        // A child gone from the book with no more fills reported was
+        // cancelled, by us or anyone else, for what it had not filled
+        self.resting.retain(|client_order_id, quantity| {
+            let gone = !reported.contains(client_order_id)
+                && !live
+                    .iter()
+                    .any(|order| order.client_order_id == *client_order_id);
+            if gone {
+                self.unfilled += *quantity - self.children[client_order_id];
+            }
+            !gone
+        });
+        let book = self.exchange.book().await?.excluding(&live);
+        let start_ms = *self.start_ms.get_or_insert_with(|| {
+            self.report.arrival_mid = book.mid();
+            now_ms
+        });
+
+        let (actions, done) = match self.algorithm {
+            Algorithm::Twap {
+                duration,
+                slices,
+                passive,
+            } => {
+                let elapsed = now_ms.saturating_sub(start_ms);
+                self.plan_twap(elapsed, duration, slices, passive, &live, &book)
+            }
+            Algorithm::Iceberg { clip } => self.plan_iceberg(clip, &live, &book),
+        };
+
+        if !actions.is_empty() {
+            self.exchange.execute(&actions, &self.params).await?;
+        }
+        for action in &actions {
+            if let Action::Place {
+                quote: Quote { quantity, .. },
+                client_order_id,
+            }
+            | Action::Take {
+                quantity,
+                client_order_id,
+                ..
+            } = action
+            {
+                self.children.insert(*client_order_id, 0.0);
+                self.report.children += 1;
+                self.sent += quantity;
+            }
+            if let Action::Take {
+                quantity,
+                client_order_id,
+                ..
+            } = action
+            {
+                self.takes.push((*client_order_id, *quantity));
+            }
+            if let Action::Place {
+                quote: Quote { quantity, .. },
+                client_order_id,
+            } = action
+            {
+                self.resting.insert(*client_order_id, *quantity);
+            }
+        }
+        self.done = done;
+        Ok(actions)
+    }
+
+    /// Tick every `interval` until done, or cancel once `deadline` has
+    /// passed since the call. Returns the final report.
+    pub async fn run(&mut self, interval: Duration, deadline: Duration) -> Result<ExecutionReport> {
+        let started = Instant::now();
+        while !self.done {
+            if started.elapsed() >= deadline {
+                self.cancel().await?;
+                break;
+            }
+            let now_ms = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+            self.tick(now_ms).await?;
+            if !self.done {
+                tokio::time::sleep(interval).await;
+            }
+        }
+        Ok(self.report.clone())
+    }
+
+    /// Cancel resting children and stop
+    pub async fn cancel(&mut self) -> Result<()> {
+        let cancels = cancel_all(&self.live_children().await?);
+        if !cancels.is_empty() {
+            self.exchange.execute(&cancels, &self.params).await?;
+        }
+        self.done = true;
+        Ok(())
+    }
+
+    async fn live_children(&mut self) -> Result<Vec<OpenOrder>> {
+        Ok(self
+            .exchange
+            .open_orders()
+            .await?
+            .into_iter()
+            .filter(|order| self.children.contains_key(&order.client_order_id))
+            .collect())
+    }
+
+    /// Actions due `elapsed` ms into a TWAP, and whether it is over
+    fn plan_twap(
+        &mut self,
+        elapsed: u64,
+        duration: Duration,
+        slices: usize,
+        passive: bool,
+        live: &[OpenOrder],
+        book: &BookSnapshot,
+    ) -> (Vec<Action>, bool) {
+        let duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        let interval_ms = (duration_ms / slices as u64).max(1);
+        let due = if elapsed >= duration_ms {
+            slices
+        } else {
+            (elapsed / interval_ms) as usize + 1
+        };
+        let due = due.min(slices);
+
+        if due <= self.slices_sent {
+            let over = elapsed >= duration_ms || self.remaining() < self.params.min_size;
+            return if over {
+                (cancel_all(live), true)
+            } else {
+                (vec![], false)
+            };
+        }
+        self.slices_sent = due;
+
+        let target = self.parent.quantity * due as f64 / slices as f64;
+        let mut actions = vec![];
+        if passive {
+            actions = cancel_all(live);
+            for order in live {
+                self.unfilled += order.quantity;
+                self.resting.remove(&order.client_order_id);
+            }
+        }
+        // What left the book filled, whether or not its fills are reported
+        let executed = (self.sent - self.unfilled).max(self.report.filled);
+        let Some(quantity) = self.child_size(target - executed) else {
+            return (actions, false);
+        };
+        let client_order_id = self.ids.next_id();
+
+        if !passive {
+            actions.push(Action::Take {
+                is_bid: self.parent.is_bid,
+                quantity,
+                limit_price: self.parent.limit_price.map(|price| self.align(price)),
+                client_order_id,
+            });
+        } else if let Some(price) = self.passive_price(book) {
+            actions.push(Action::Place {
+                quote: Quote {
+                    is_bid: self.parent.is_bid,
+                    price,
+                    quantity,
+                },
+                client_order_id,
+            });
+        }
+        (actions, false)
+    }
+
+    /// The next clip once the previous one has filled, and whether the
+    /// iceberg is over. A post-only clip that would take waits for the book
+    /// to move rather than abort the transaction.
+    fn plan_iceberg(
+        &mut self,
+        clip: f64,
+        live: &[OpenOrder],
+        book: &BookSnapshot,
+    ) -> (Vec<Action>, bool) {
+        // A clip that left the book filled but before its fills were reported
+        let live_quantity: f64 = live.iter().map(|order| order.quantity).sum();
+        let unreported = self.sent - self.report.filled - self.unfilled - live_quantity;
+        if !live.is_empty() || unreported > self.params.lot_size / 2.0 {
+            return (vec![], false);
+        }
+
+        let Some(quantity) = self.child_size(clip.min(self.remaining())) else {
+            return (vec![], true);
+        };
+        let Some(limit_price) = self.parent.limit_price else {
+            return (vec![], true);
+        };
+        let price = self.align(limit_price);
+        let tick = self.params.tick_size;
+        let ticks = to_units(price, tick);
+        let crosses = if self.parent.is_bid {
+            book.best_ask()
+                .is_some_and(|ask| ticks >= to_units(ask, tick))
+        } else {
+            book.best_bid()
+                .is_some_and(|bid| ticks <= to_units(bid, tick))
+        };
+        if self.params.post_only && crosses {
+            return (vec![], false);
+        }
+        let quote = Quote {
+            is_bid: self.parent.is_bid,
+            price,
+            quantity,
+        };
+        let client_order_id = self.ids.next_id();
+        (
+            vec![Action::Place {
+                quote,
+                client_order_id,
+            }],
+            false,
+        )
+    }
+
+    /// `quantity` capped at what remains and rounded down to lots, if at
+    /// least the pool's min size
+    fn child_size(&self, quantity: f64) -> Option<f64> {
+        let quantity = quantity.min(self.remaining());
+        let lots = floor_units(quantity.max(0.0), self.params.lot_size);
+        let size = lots as f64 * self.params.lot_size;
+        (lots > 0 && size >= self.params.min_size - self.params.lot_size / 2.0).then_some(size)
+    }
+
+    /// Best price on our side, capped by the limit price and kept one tick
+    /// off the other side
+    fn passive_price(&self, book: &BookSnapshot) -> Option<f64> {
+        let tick = self.params.tick_size;
+        let (touch, opposite) = if self.parent.is_bid {
+            (book.best_bid(), book.best_ask().map(|ask| ask - tick))
+        } else {
+            (book.best_ask(), book.best_bid().map(|bid| bid + tick))
+        };
+        let less_aggressive = |a: f64, b: f64| {
+            if self.parent.is_bid {
+                a.min(b)
+            } else {
+                a.max(b)
+            }
+        };
+
+        let price = [touch, self.parent.limit_price, opposite]
+            .into_iter()
+            .flatten()
+            .reduce(less_aggressive)?;
+        Some(self.align(price)).filter(|price| *price > 0.0)
+    }
+
+    /// Round a price to a tick on the passive side, never worse than it
+    fn align(&self, price: f64) -> f64 {
+        let tick = self.params.tick_size;
+        let ticks = if self.parent.is_bid {
+            floor_units(price, tick)
+        } else {
+            ceil_units(price, tick)
+        };
+        ticks as f64 * tick
+    }
+}
+
+fn cancel_all(orders: &[OpenOrder]) -> Vec<Action> {
+    orders
+        .iter()
+        .map(|order| Action::Cancel {
+            order_id: order.order_id,
+        })
+        .collect()
+}
@@ -0,0 +1,445 @@
+//! Quoting strategies. A [`Strategy`] says which quotes it wants; the
+//! [`Engine`] diffs them against live orders and sends the minimal set of
+//! cancels, modifies and places to an [`Exchange`] in one transaction.
+//! An [`Executor`] works one large order through the same exchanges.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+
+use anyhow::Result;
+
+use crate::orders::ClientOrderIdGenerator;
+use crate::types::{ClientOrderId, OpenOrder, OrderFill};
+
+mod deepbook;
+mod engine;
+mod execution;
+mod simulated;
+
+pub use crate::utils::offline::TransactionSigner;
+pub use deepbook::DeepBookExchange;
+pub use engine::Engine;
+pub use execution::{Algorithm, ExecutionReport, Executor, ParentOrder};
+pub use simulated::SimulatedExchange;
+
+/// An order a strategy wants on the book, in human units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quote {
+    pub is_bid: bool,
+    pub price: f64,
+    pub quantity: f64,
+}
+
+impl Quote {
+    pub fn bid(price: f64, quantity: f64) -> Self {
+        Self {
+            is_bid: true,
+            price,
+            quantity,
+        }
+    }
+
+    pub fn ask(price: f64, quantity: f64) -> Self {
+        Self {
+            is_bid: false,
+            price,
+            quantity,
+        }
+    }
+}
+
+/// Aggregated price levels, best first, as (price, quantity)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BookSnapshot {
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+    pub timestamp_ms: u64,
+}
+
+impl BookSnapshot {
+    pub fn best_bid(&self) -> Option<f64> {
+        self.bids.first().map(|(price, _)| *price)
+    }
+
+    pub fn best_ask(&self) -> Option<f64> {
+        self.asks.first().map(|(price, _)| *price)
+    }
+
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.best_bid()? + self.best_ask()?) / 2.0)
+    }
+
+    /// The book as other traders see it, without `orders`
+    pub fn excluding(&self, orders: &[OpenOrder]) -> Self {
+        let remove = |levels: &[(f64, f64)], is_bid: bool| {
+            levels
+                .iter()
+                .map(|&(price, quantity)| {
+                    let own: f64 = orders
+                        .iter()
+                        .filter(|order| order.is_bid == is_bid)
+                        .filter(|order| (order.price - price).abs() <= price * UNIT_EPSILON)
+                        .map(|order| order.quantity)
+                        .sum();
+                    (price, quantity - own)
+                })
+                .filter(|&(_, quantity)| quantity > UNIT_EPSILON)
+                .collect()
+        };
+        Self {
+            bids: remove(&self.bids, true),
+            asks: remove(&self.asks, false),
+            timestamp_ms: self.timestamp_ms,
+        }
+    }
+}
+
+/// The strategy's funds, in human units
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Inventory {
+    /// Free to fund new orders
+    pub base: f64,
+    pub quote: f64,
+    /// Held by the pool for resting orders and settled fills
+    pub locked_base: f64,
+    pub locked_quote: f64,
+}
+
+impl Inventory {
+    /// Base owned, free or locked
+    pub fn position(&self) -> f64 {
+        self.base + self.locked_base
+    }
+}
+
+/// Pool constraints and risk settings applied to every set of quotes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuotingParams {
+    pub tick_size: f64,
+    pub lot_size: f64,
+    pub min_size: f64,
+    /// New bids are trimmed so that filling all bids keeps the base
+    /// position at or below this. Resting bids are never trimmed for it.
+    pub max_base: f64,
+    /// New asks are trimmed so that filling all asks keeps the base
+    /// position at or above this
+    pub min_base: f64,
+    /// Drop quotes that would cross the book instead of letting the whole
+    /// transaction abort on chain
+    pub post_only: bool,
+    pub pay_with_deep: bool,
+}
+
+impl QuotingParams {
+    /// From `DeepBookClient::get_pool_book_params`, without inventory limits
+    pub fn new(tick_size: f64, lot_size: f64, min_size: f64) -> Self {
+        Self {
+            tick_size,
+            lot_size,
+            min_size,
+            max_base: f64::INFINITY,
+            min_base: f64::NEG_INFINITY,
+            post_only: true,
+            pay_with_deep: true,
+        }
+    }
+}
+
+/// One change to live orders
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Cancel {
+        order_id: u128,
+    },
+    /// Shrink a resting order to `quantity` remaining, keeping its queue
+    /// position
+    Modify {
+        order_id: u128,
+        quantity: f64,
+    },
+    Place {
+        quote: Quote,
+        client_order_id: ClientOrderId,
+    },
+    /// Trade now and rest nothing: a market order, or an immediate-or-cancel
+    /// limit order when `limit_price` is set
+    Take {
+        is_bid: bool,
+        quantity: f64,
+        limit_price: Option<f64>,
+        client_order_id: ClientOrderId,
+    },
+}
+
+/// Decides which quotes to keep on the book
+pub trait Strategy {
+    /// Desired quotes for the current book. Prices and sizes need not be
+    /// aligned; the engine rounds them to the pool's tick and lot sizes.
+    fn on_book(&mut self, book: &BookSnapshot, inventory: &Inventory) -> Vec<Quote>;
+
+    /// Called for each fill of the strategy's orders, before `on_book`
+    fn on_fill(&mut self, _fill: &OrderFill) {}
+
+    /// Called once per engine tick, before fills and `on_book`
+    fn on_timer(&mut self, _now_ms: u64) {}
+}
+
+/// Venue the engine trades on: DeepBook itself or a simulation
+pub trait Exchange {
+    fn book(&mut self) -> impl Future<Output = Result<BookSnapshot>>;
+
+    fn open_orders(&mut self) -> impl Future<Output = Result<Vec<OpenOrder>>>;
+
+    /// Fills since the previous call
+    fn fills(&mut self) -> impl Future<Output = Result<Vec<OrderFill>>>;
+
+    fn inventory(&mut self) -> impl Future<Output = Result<Inventory>>;
+
+    /// Apply all actions atomically, like one PTB. `params` decides the
+    /// order type and fee payment of places.
+    fn execute(
+        &mut self,
+        actions: &[Action],
+        params: &QuotingParams,
+    ) -> impl Future<Output = Result<()>>;
+}
+
+/// Quotes `levels` orders each side of mid, `step` apart, starting
+/// `half_spread` away from mid
+#[derive(Debug, Clone)]
+pub struct SymmetricQuoter {
+    pub half_spread: f64,
+    pub step: f64,
+    pub levels: usize,
+    pub size: f64,
+}
+
+impl Strategy for SymmetricQuoter {
+    fn on_book(&mut self, book: &BookSnapshot, _inventory: &Inventory) -> Vec<Quote> {
+        let Some(mid) = book.mid() else {
+            return vec![];
+        };
+        (0..self.levels)
+            .flat_map(|level| {
+                let offset = self.half_spread + self.step * level as f64;
+                [
+                    Quote::bid(mid - offset, self.size),
+                    Quote::ask(mid + offset, self.size),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Round and filter `desired` with `params`, then diff it against `live`.
+/// Cancels come first, then modifies, then places. Places take client order
+/// ids from `ids`.
+pub fn plan_actions(
+    desired: &[Quote],
+    live: &[OpenOrder],
+    book: &BookSnapshot,
+    inventory: &Inventory,
+    params: &QuotingParams,
+    ids: &mut ClientOrderIdGenerator,
+) -> Vec<Action> {
+    let target = target_levels(desired, live, book, inventory, params);
+
+    // Live orders per level in the order reported; surplus is trimmed from
+    // the end
+    let mut resting: BTreeMap<(bool, u64), Vec<(u128, u64)>> = BTreeMap::new();
+    for order in live {
+        let level = (order.is_bid, to_units(order.price, params.tick_size));
+        resting
+            .entry(level)
+            .or_default()
+            .push((order.order_id, to_units(order.quantity, params.lot_size)));
+    }
+
+    let min_lots = to_units(params.min_size, params.lot_size);
+    let mut cancels = vec![];
+    let mut modifies = vec![];
+    let mut places = vec![];
+    let mut kept: BTreeMap<(bool, u64), u64> = BTreeMap::new();
+    for (level, orders) in &resting {
+        let wanted = target.get(level).copied().unwrap_or(0);
+        let mut total: u64 = orders.iter().map(|(_, lots)| lots).sum();
+
+        for &(order_id, lots) in orders.iter().rev() {
+            if total <= wanted {
+                break;
+            }
+            let keep = lots.saturating_sub(total - wanted);
+            // An order shrunk below the minimum size is replaced instead
+            if keep < min_lots {
+                cancels.push(Action::Cancel { order_id });
+                total -= lots;
+            } else {
+                modifies.push(Action::Modify {
+                    order_id,
+                    quantity: keep as f64 * params.lot_size,
+                });
+                total = wanted;
+            }
+        }
+        kept.insert(*level, total);
+    }
+
+    for (level, &lots) in &target {
+        let resting_lots = kept.get(level).copied().unwrap_or(0);
+        if lots > resting_lots && lots - resting_lots >= min_lots {
+            let &(is_bid, ticks) = level;
+            places.push(Action::Place {
+                quote: Quote {
+                    is_bid,
+                    price: ticks as f64 * params.tick_size,
+                    quantity: (lots - resting_lots) as f64 * params.lot_size,
+                },
+                client_order_id: ids.next_id(),
+            });
+        }
+    }
+
+    cancels.into_iter().chain(modifies).chain(places).collect()
+}
+
+/// Desired lots per (is_bid, price in ticks) after rounding and post-only.
+/// Lots already resting stay; what is added on top is limited by the
+/// inventory limits and by the funds free once this plan's cancels and
+/// modifies have run.
+fn target_levels(
+    desired: &[Quote],
+    live: &[OpenOrder],
+    book: &BookSnapshot,
+    inventory: &Inventory,
+    params: &QuotingParams,
+) -> BTreeMap<(bool, u64), u64> {
+    let min_lots = to_units(params.min_size, params.lot_size);
+    let best_bid = book
+        .best_bid()
+        .map(|price| to_units(price, params.tick_size));
+    let best_ask = book
+        .best_ask()
+        .map(|price| to_units(price, params.tick_size));
+
+    let mut quotes: Vec<(bool, u64, u64)> = desired
+        .iter()
+        .filter(|quote| quote.price > 0.0 && quote.quantity > 0.0)
+        .map(|quote| {
+            // Round towards the passive side
+            let ticks = if quote.is_bid {
+                floor_units(quote.price, params.tick_size)
+            } else {
+                ceil_units(quote.price, params.tick_size)
+            };
+            (
+                quote.is_bid,
+                ticks,
+                floor_units(quote.quantity, params.lot_size),
+            )
+        })
+        .filter(|&(is_bid, ticks, lots)| {
+            let crosses = if is_bid {
+                best_ask.is_some_and(|ask| ticks >= ask)
+            } else {
+                best_bid.is_some_and(|bid| ticks <= bid)
+            };
+            ticks > 0 && lots >= min_lots && !(params.post_only && crosses)
+        })
+        .collect();
+
+    // Most aggressive quotes keep their size when limits bite
+    quotes.sort_by(|a, b| match (a.0, b.0) {
+        (true, true) => b.1.cmp(&a.1),
+        (false, false) => a.1.cmp(&b.1),
+        _ => b.0.cmp(&a.0),
+    });
+    let mut levels: Vec<((bool, u64), u64)> = vec![];
+    for (is_bid, ticks, lots) in quotes {
+        match levels.last_mut() {
+            Some((level, total)) if *level == (is_bid, ticks) => *total += lots,
+            _ => levels.push(((is_bid, ticks), lots)),
+        }
+    }
+
+    let mut resting: BTreeMap<(bool, u64), u64> = BTreeMap::new();
+    for order in live {
+        let level = (order.is_bid, to_units(order.price, params.tick_size));
+        *resting.entry(level).or_default() += to_units(order.quantity, params.lot_size);
+    }
+    let kept: BTreeMap<(bool, u64), u64> = levels
+        .iter()
+        .map(|&(level, lots)| {
+            let resting_lots = resting.get(&level).copied().unwrap_or(0);
+            (level, lots.min(resting_lots))
+        })
+        .collect();
+
+    // Resting orders that stay use up room first; trimmed ones free funds
+    let mut buy_room = limit_lots(params.max_base - inventory.position(), params.lot_size);
+    let mut sell_room = limit_lots(inventory.position() - params.min_base, params.lot_size);
+    let mut free_base = inventory.base;
+    let mut free_quote = inventory.quote;
+    for (&(is_bid, ticks), &resting_lots) in &resting {
+        let kept_lots = kept.get(&(is_bid, ticks)).copied().unwrap_or(0);
+        let released = (resting_lots - kept_lots) as f64 * params.lot_size;
+        if is_bid {
+            buy_room = buy_room.saturating_sub(kept_lots);
+            free_quote += released * ticks as f64 * params.tick_size;
+        } else {
+            sell_room = sell_room.saturating_sub(kept_lots);
+            free_base += released;
+        }
+    }
+
+    let mut target = BTreeMap::new();
+    for ((is_bid, ticks), lots) in levels {
+        let kept_lots = kept[&(is_bid, ticks)];
+        let price = ticks as f64 * params.tick_size;
+        let (room, affordable) = if is_bid {
+            (
+                &mut buy_room,
+                floor_units(free_quote, price * params.lot_size),
+            )
+        } else {
+            (&mut sell_room, floor_units(free_base, params.lot_size))
+        };
+        let mut added = (lots - kept_lots).min(*room).min(affordable);
+        if added < min_lots {
+            added = 0;
+        }
+        *room -= added;
+        if is_bid {
+            free_quote -= added as f64 * params.lot_size * price;
+        } else {
+            free_base -= added as f64 * params.lot_size;
+        }
+        if kept_lots + added > 0 {
+            target.insert((is_bid, ticks), kept_lots + added);
+        }
+    }
+    target
+}
+
+// Quantities near a multiple of the unit are treated as that multiple, so
+// 0.3 / 0.1 counts as 3 units
+const UNIT_EPSILON: f64 = 1e-6;
+
+fn to_units(value: f64, unit: f64) -> u64 {
+    (value / unit).round().max(0.0) as u64
+}
+
+fn floor_units(value: f64, unit: f64) -> u64 {
+    (value / unit + UNIT_EPSILON).floor().max(0.0) as u64
+}
+
+fn ceil_units(value: f64, unit: f64) -> u64 {
+    (value / unit - UNIT_EPSILON).ceil().max(0.0) as u64
+}
+
+fn limit_lots(room: f64, lot_size: f64) -> u64 {
+    if room.is_infinite() {
+        u64::MAX
+    } else {
+        floor_units(room.max(0.0), lot_size)
+    }
+}
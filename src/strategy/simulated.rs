@@ -0,0 +1,339 @@
+use anyhow::{Result, anyhow};
+
+use super::{Action, BookSnapshot, Exchange, Inventory, QuotingParams};
+use crate::types::{ClientOrderId, OpenOrder, OrderFill};
+
+const EPSILON: f64 = 1e-9;
+
+/// In-memory exchange for testing strategies. Other traders are modelled by
+/// an external book, which our crossing orders take from, and by
+/// [`SimulatedExchange::take`], which trades against our resting orders.
+#[derive(Debug, Clone)]
+pub struct SimulatedExchange {
+    pub pool_key: String,
+    pub base_coin: String,
+    pub quote_coin: String,
+    /// Liquidity from other traders, best first
+    pub external: BookSnapshot,
+    /// Everything we hold; [`Exchange::inventory`] splits off what our
+    /// resting orders lock
+    pub inventory: Inventory,
+    /// Every batch of actions executed, one per transaction
+    pub transactions: Vec<Vec<Action>>,
+    orders: Vec<OpenOrder>,
+    fills: Vec<OrderFill>,
+    next_order_id: u128,
+}
+
+impl SimulatedExchange {
+    pub fn new(
+        pool_key: &str,
+        base_coin: &str,
+        quote_coin: &str,
+        external: BookSnapshot,
+        inventory: Inventory,
+    ) -> Self {
+        Self {
+            pool_key: pool_key.to_string(),
+            base_coin: base_coin.to_string(),
+            quote_coin: quote_coin.to_string(),
+            external,
+            inventory,
+            transactions: vec![],
+            orders: vec![],
+            fills: vec![],
+            next_order_id: 1,
+        }
+    }
+
+    /// Our resting orders, oldest first
+    pub fn orders(&self) -> &[OpenOrder] {
+        &self.orders
+    }
+
+    /// An outside taker trades up to `quantity` base against our orders at
+    /// `limit_price` or better. Returns the quantity filled.
+    pub fn take(&mut self, taker_is_bid: bool, quantity: f64, limit_price: f64) -> f64 {
+        let mut remaining = quantity;
+        loop {
+            // Best price first, then oldest
+            let best = self
+                .orders
+                .iter()
+                .enumerate()
+                .filter(|(_, order)| order.is_bid != taker_is_bid)
+                .filter(|(_, order)| {
+                    if taker_is_bid {
+                        order.price <= limit_price
+                    } else {
+                        order.price >= limit_price
+                    }
+                })
+                .min_by(|(i, a), (j, b)| {
+                    let by_price = if taker_is_bid {
+                        a.price.total_cmp(&b.price)
+                    } else {
+                        b.price.total_cmp(&a.price)
+                    };
+                    by_price.then(i.cmp(j))
+                })
+                .map(|(i, _)| i);
+            let Some(i) = best else {
+                break;
+            };
+            if remaining <= EPSILON {
+                break;
+            }
+
+            let order = &mut self.orders[i];
+            let filled = remaining.min(order.quantity);
+            order.quantity -= filled;
+            order.filled_quantity += filled;
+            let order = order.clone();
+            if order.quantity <= EPSILON {
+                self.orders.remove(i);
+            }
+            remaining -= filled;
+            self.record_fill(&order, filled, order.price);
+        }
+        quantity - remaining
+    }
+
+    /// Record a fill of `order`, ours, at `price`
+    fn record_fill(&mut self, order: &OpenOrder, base_quantity: f64, price: f64) {
+        let quote_quantity = base_quantity * price;
+        if order.is_bid {
+            self.inventory.base += base_quantity;
+            self.inventory.quote -= quote_quantity;
+        } else {
+            self.inventory.base -= base_quantity;
+            self.inventory.quote += quote_quantity;
+        }
+        self.fills.push(OrderFill {
+            pool_key: self.pool_key.clone(),
+            base_coin: self.base_coin.clone(),
+            quote_coin: self.quote_coin.clone(),
+            order_id: order.order_id,
+            client_order_id: order.client_order_id,
+            is_buy: order.is_bid,
+            base_quantity,
+            quote_quantity,
+            deep_fee: 0.0,
+            timestamp_ms: self.external.timestamp_ms,
+        });
+    }
+
+    fn apply(&mut self, action: &Action, params: &QuotingParams) -> Result<()> {
+        match action {
+            Action::Cancel { order_id } => {
+                let i = self.position(*order_id)?;
+                self.orders.remove(i);
+            }
+            Action::Modify { order_id, quantity } => {
+                let i = self.position(*order_id)?;
+                let order = &mut self.orders[i];
+                if *quantity <= 0.0 || *quantity >= order.quantity {
+                    return Err(anyhow!(
+                        "Order {} can only shrink, from {} to {}",
+                        order_id,
+                        order.quantity,
+                        quantity
+                    ));
+                }
+                order.quantity = *quantity;
+            }
+            Action::Place {
+                quote,
+                client_order_id,
+            } => {
+                let crosses = self.opposite_crosses(quote.is_bid, quote.price);
+                if params.post_only && crosses {
+                    return Err(anyhow!("Post-only order at {} would take", quote.price));
+                }
+
+                let mut order =
+                    self.new_order(quote.is_bid, quote.price, quote.quantity, *client_order_id);
+                self.sweep(&mut order, Some(quote.price))?;
+                if order.quantity > EPSILON {
+                    self.orders.push(order);
+                }
+            }
+            Action::Take {
+                is_bid,
+                quantity,
+                limit_price,
+                client_order_id,
+            } => {
+                // The unfilled remainder is cancelled
+                let price = limit_price.unwrap_or_default();
+                let mut order = self.new_order(*is_bid, price, *quantity, *client_order_id);
+                self.sweep(&mut order, *limit_price)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn new_order(
+        &mut self,
+        is_bid: bool,
+        price: f64,
+        quantity: f64,
+        client_order_id: ClientOrderId,
+    ) -> OpenOrder {
+        let order_id = self.next_order_id;
+        self.next_order_id += 1;
+        OpenOrder {
+            order_id,
+            client_order_id,
+            is_bid,
+            price,
+            quantity,
+            filled_quantity: 0.0,
+            expire_timestamp: u64::MAX,
+        }
+    }
+
+    /// Whether the best external or own opposite price is at or through
+    /// `price`
+    fn opposite_crosses(&self, is_bid: bool, price: f64) -> bool {
+        let crosses = |level: f64| {
+            if is_bid {
+                level <= price
+            } else {
+                level >= price
+            }
+        };
+        let external = if is_bid {
+            &self.external.asks
+        } else {
+            &self.external.bids
+        };
+        external.first().is_some_and(|(level, _)| crosses(*level))
+            || self
+                .orders
+                .iter()
+                .any(|order| order.is_bid != is_bid && crosses(order.price))
+    }
+
+    /// Fill `order` from the external book up to `limit_price`, or without
+    /// limit
+    fn sweep(&mut self, order: &mut OpenOrder, limit_price: Option<f64>) -> Result<()> {
+        let is_bid = order.is_bid;
+        let crosses = |level: f64| match limit_price {
+            Some(limit) if is_bid => level <= limit,
+            Some(limit) => level >= limit,
+            None => true,
+        };
+        let own_cross = self
+            .orders
+            .iter()
+            .any(|other| other.is_bid != is_bid && crosses(other.price));
+        if own_cross {
+            return Err(anyhow!(
+                "Order {} would match our own order",
+                order.client_order_id
+            ));
+        }
+
+        let external = if is_bid {
+            &mut self.external.asks
+        } else {
+            &mut self.external.bids
+        };
+        let mut taken = vec![];
+        while order.quantity > EPSILON {
+            let Some(level) = external.first_mut() else {
+                break;
+            };
+            if !crosses(level.0) {
+                break;
+            }
+            let filled = order.quantity.min(level.1);
+            taken.push((filled, level.0));
+            level.1 -= filled;
+            order.quantity -= filled;
+            order.filled_quantity += filled;
+            if level.1 <= EPSILON {
+                external.remove(0);
+            }
+        }
+        for (filled, price) in taken {
+            self.record_fill(order, filled, price);
+        }
+        Ok(())
+    }
+
+    fn position(&self, order_id: u128) -> Result<usize> {
+        self.orders
+            .iter()
+            .position(|order| order.order_id == order_id)
+            .ok_or_else(|| anyhow!("Order {} is not open", order_id))
+    }
+}
+
+impl Exchange for SimulatedExchange {
+    /// External liquidity merged with our resting orders, as the chain reports it
+    async fn book(&mut self) -> Result<BookSnapshot> {
+        let mut book = self.external.clone();
+        for order in &self.orders {
+            let levels = if order.is_bid {
+                &mut book.bids
+            } else {
+                &mut book.asks
+            };
+            match levels
+                .iter_mut()
+                .find(|(price, _)| (price - order.price).abs() <= EPSILON)
+            {
+                Some(level) => level.1 += order.quantity,
+                None => levels.push((order.price, order.quantity)),
+            }
+        }
+        book.bids.sort_by(|a, b| b.0.total_cmp(&a.0));
+        book.asks.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(book)
+    }
+
+    async fn open_orders(&mut self) -> Result<Vec<OpenOrder>> {
+        Ok(self.orders.clone())
+    }
+
+    async fn fills(&mut self) -> Result<Vec<OrderFill>> {
+        Ok(std::mem::take(&mut self.fills))
+    }
+
+    async fn inventory(&mut self) -> Result<Inventory> {
+        let locked = |is_bid: bool| -> f64 {
+            self.orders
+                .iter()
+                .filter(|order| order.is_bid == is_bid)
+                .map(|order| {
+                    if is_bid {
+                        order.quantity * order.price
+                    } else {
+                        order.quantity
+                    }
+                })
+                .sum()
+        };
+        let locked_base = locked(false);
+        let locked_quote = locked(true);
+        Ok(Inventory {
+            base: self.inventory.base - locked_base,
+            quote: self.inventory.quote - locked_quote,
+            locked_base,
+            locked_quote,
+        })
+    }
+
+    /// All or nothing, like a PTB that aborts
+    async fn execute(&mut self, actions: &[Action], params: &QuotingParams) -> Result<()> {
+        let mut next = self.clone();
+        for action in actions {
+            next.apply(action, params)?;
+        }
+        next.transactions.push(actions.to_vec());
+        *self = next;
+        Ok(())
+    }
+}
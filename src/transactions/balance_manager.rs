@@ -7,6 +7,7 @@ use anyhow::{Context, Ok, Result, anyhow};
 use sui_sdk::SuiClient;
 use sui_sdk::rpc_types::{SuiObjectDataOptions, SuiObjectResponse};
 use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+use sui_sdk::types::id::ID;
 use sui_sdk::types::transaction::{
     Argument, CallArg, Command, ObjectArg, ProgrammableMoveCall, ProgrammableTransaction,
     TransactionKind,
@@ -15,9 +16,11 @@ use sui_sdk::types::{
     programmable_transaction_builder::ProgrammableTransactionBuilder, transaction::Transaction,
 };
 
+use crate::errors::ManagerAccessError;
+use crate::types::CapKind;
 use crate::utils::config::DeepBookConfig;
 use crate::utils::get_object_arg;
-use crate::utils::{merge_and_split_coins, parse_type_input};
+use crate::utils::{merge_and_split_coins, parse_type_input, transfer_objects};
 
 #[derive(Clone)]
 pub struct BalanceManagerContract {
@@ -30,6 +33,19 @@ impl BalanceManagerContract {
         Self { client, config }
     }
 
+    /// Refuse owner-only operations when the manager is used in delegated mode,
+    /// instead of letting the transaction abort on chain
+    fn ensure_owner(&self, manager_key: &str, operation: &'static str) -> Result<()> {
+        if self.config.get_balance_manager(manager_key).is_delegated() {
+            return Err(ManagerAccessError::OwnerOnly {
+                manager: manager_key.to_string(),
+                operation,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
     pub async fn create_and_share_balance_manager(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
@@ -44,6 +60,36 @@ impl BalanceManagerContract {
             arguments: vec![],
         })));
 
+        self.share_balance_manager(ptb, manager)
+    }
+
+    /// Create a balance manager owned by `owner` instead of the sender.
+    /// The returned manager must be shared with `share_balance_manager`.
+    pub async fn create_balance_manager_with_owner(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        owner: SuiAddress,
+    ) -> Result<Argument> {
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+        let owner_arg = ptb.pure(owner)?;
+
+        Ok(
+            ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+                package: package_id,
+                module: "balance_manager".to_string(),
+                function: "new_with_custom_owner".to_string(),
+                type_arguments: vec![],
+                arguments: vec![owner_arg],
+            }))),
+        )
+    }
+
+    /// Share a balance manager created earlier in the same PTB
+    pub fn share_balance_manager(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager: Argument,
+    ) -> Result<()> {
         ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
             package: ObjectID::from_hex_literal("0x2")?, // Sui Framework
             module: "transfer".to_string(),
@@ -64,13 +110,13 @@ impl BalanceManagerContract {
         manager_key: &str,
         coin_key: &str,
         amount_to_withdraw: f64,
-        recipient: SuiAddress,
-    ) -> Result<()> {
+    ) -> Result<Argument> {
+        self.ensure_owner(manager_key, "withdraw")?;
         let manager_id = self.config.get_balance_manager(manager_key).address;
 
         let coin = self.config.get_coin(coin_key);
         let withdraw_input = (amount_to_withdraw * coin.scalar as f64) as u64;
-        let manager_object = get_object_arg(&self.client, manager_id)
+        let manager_object = get_object_arg(&self.client, &manager_id)
             .await
             .context("Failed to get object argument for manager_id")?;
 
@@ -90,19 +136,31 @@ impl BalanceManagerContract {
             arguments: vec![manager_arg, withdraw_arg],
         })));
 
-        let recipient_arg = ptb.pure(recipient)?;
-        ptb.command(Command::TransferObjects(vec![coin_object], recipient_arg));
-
-        Ok(())
+        Ok(coin_object)
     }
 
-    pub async fn withdraw_all_from_manager(
+    /// `withdraw_from_manager`, sending the coin to `recipient`
+    pub async fn withdraw_from_manager_to(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
         manager_key: &str,
         coin_key: &str,
+        amount_to_withdraw: f64,
         recipient: SuiAddress,
     ) -> Result<()> {
+        let coin = self
+            .withdraw_from_manager(ptb, manager_key, coin_key, amount_to_withdraw)
+            .await?;
+        transfer_objects(ptb, vec![coin], recipient)
+    }
+
+    pub async fn withdraw_all_from_manager(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        coin_key: &str,
+    ) -> Result<Argument> {
+        self.ensure_owner(manager_key, "withdraw_all")?;
         // ✅ Fetch Manager ID
         let manager_id = self.config.get_balance_manager(manager_key).address;
 
@@ -110,7 +168,7 @@ impl BalanceManagerContract {
         let coin = self.config.get_coin(coin_key);
 
         // ✅ Convert Manager ID to ObjectRef
-        let manager_object = get_object_arg(&self.client, manager_id)
+        let manager_object = get_object_arg(&self.client, &manager_id)
             .await
             .context("Failed to get object argument for manager_id")?;
 
@@ -132,15 +190,21 @@ impl BalanceManagerContract {
             arguments: vec![manager_arg],
         })));
 
-        // ✅ Transfer Withdrawn Coin to Recipient
-        let recipient_arg = ptb.pure(recipient)?;
-        ptb.command(Command::TransferObjects(
-            vec![withdrawal_coin],
-            recipient_arg,
-        ));
+        Ok(withdrawal_coin)
+    }
 
-        // ✅ Finalize Transaction
-        Ok(())
+    /// `withdraw_all_from_manager`, sending the coin to `recipient`
+    pub async fn withdraw_all_from_manager_to(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        coin_key: &str,
+        recipient: SuiAddress,
+    ) -> Result<()> {
+        let coin = self
+            .withdraw_all_from_manager(ptb, manager_key, coin_key)
+            .await?;
+        transfer_objects(ptb, vec![coin], recipient)
     }
 
     pub async fn deposit_into_manager(
@@ -150,8 +214,7 @@ impl BalanceManagerContract {
         coin_key: &str,
         amount_to_deposit: f64,
     ) -> Result<()> {
-        // Fetch manager ID and coin details
-        let manager_id = self.config.get_balance_manager(manager_key).address;
+        self.ensure_owner(manager_key, "deposit")?;
         let coin = self.config.get_coin(coin_key);
 
         // Convert deposit amount to correct precision
@@ -170,6 +233,22 @@ impl BalanceManagerContract {
         .next()
         .ok_or_else(|| anyhow::anyhow!("Failed to get coin argument from split result"))?;
 
+        self.deposit_coin_into_manager(ptb, manager_key, coin_key, coin_arg)
+            .await
+    }
+
+    /// Deposit a coin produced earlier in the PTB, e.g. a swap output
+    pub async fn deposit_coin_into_manager(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        coin_key: &str,
+        coin_arg: Argument,
+    ) -> Result<()> {
+        self.ensure_owner(manager_key, "deposit")?;
+        let manager_id = self.config.get_balance_manager(manager_key).address;
+        let coin = self.config.get_coin(coin_key);
+
         // Get manager object
         let manager_object = get_object_arg(&self.client, &manager_id)
             .await
@@ -194,7 +273,6 @@ impl BalanceManagerContract {
             arguments: vec![manager_arg, coin_arg],
         })));
 
-        // Finalize transaction
         Ok(())
     }
 
@@ -210,7 +288,7 @@ impl BalanceManagerContract {
         let type_argument =
             parse_type_input(coin_type).context("Failed to parse type input for coin_type")?;
 
-        let manager_object = get_object_arg(&self.client, manager_id)
+        let manager_object = get_object_arg(&self.client, &manager_id)
             .await
             .context("Failed to get object argument for manager_id")?;
 
@@ -238,13 +316,13 @@ impl BalanceManagerContract {
         let balance_manager = self.config.get_balance_manager(manager_key);
 
         // ✅ Determine which proof generation function to call
-        if let Some(trade_cap) = balance_manager.trade_cap {
+        if let Some(trade_cap) = &balance_manager.trade_cap {
             Ok(self
-                .generate_proof_as_trader(ptb, balance_manager.address, trade_cap)
+                .generate_proof_as_trader(ptb, &balance_manager.address, trade_cap)
                 .await?)
         } else {
             Ok(self
-                .generate_proof_as_owner(ptb, balance_manager.address)
+                .generate_proof_as_owner(ptb, &balance_manager.address)
                 .await?)
         }
     }
@@ -310,6 +388,7 @@ impl BalanceManagerContract {
         ptb: &mut ProgrammableTransactionBuilder,
         manager_key: &str,
     ) -> Result<Argument> {
+        self.ensure_owner(manager_key, "mint_trade_cap")?;
         let manager = self.config.get_balance_manager(manager_key);
         let manager_arg = ptb.input(get_object_arg(&self.client, &manager.address).await?)?;
         Ok(
@@ -328,6 +407,7 @@ impl BalanceManagerContract {
         ptb: &mut ProgrammableTransactionBuilder,
         manager_key: &str,
     ) -> Result<Argument> {
+        self.ensure_owner(manager_key, "mint_deposit_cap")?;
         let manager = self.config.get_balance_manager(manager_key);
         let manager_arg = ptb.input(get_object_arg(&self.client, &manager.address).await?)?;
         Ok(
@@ -346,6 +426,7 @@ impl BalanceManagerContract {
         ptb: &mut ProgrammableTransactionBuilder,
         manager_key: &str,
     ) -> Result<Argument> {
+        self.ensure_owner(manager_key, "mint_withdraw_cap")?;
         let manager = self.config.get_balance_manager(manager_key);
         let manager_arg = ptb.input(get_object_arg(&self.client, &manager.address).await?)?;
         Ok(
@@ -359,6 +440,80 @@ impl BalanceManagerContract {
         )
     }
 
+    pub async fn mint_cap(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        kind: CapKind,
+    ) -> Result<Argument> {
+        match kind {
+            CapKind::Trade => self.mint_trade_cap(ptb, manager_key).await,
+            CapKind::Deposit => self.mint_deposit_cap(ptb, manager_key).await,
+            CapKind::Withdraw => self.mint_withdraw_cap(ptb, manager_key).await,
+        }
+    }
+
+    /// Mint a capability and hand it to `delegate`
+    pub async fn mint_cap_for(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        kind: CapKind,
+        delegate: SuiAddress,
+    ) -> Result<Argument> {
+        let cap = self.mint_cap(ptb, manager_key, kind).await?;
+        self.transfer_cap(ptb, cap, delegate)?;
+        Ok(cap)
+    }
+
+    pub fn transfer_cap(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        cap: Argument,
+        recipient: SuiAddress,
+    ) -> Result<()> {
+        transfer_objects(ptb, vec![cap], recipient)
+    }
+
+    /// Remove a cap from the manager's allow list. Despite its Move name this
+    /// revokes trade, deposit and withdraw caps alike.
+    pub async fn revoke_trade_cap(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        cap_id: ObjectID,
+    ) -> Result<()> {
+        self.ensure_owner(manager_key, "revoke_trade_cap")?;
+        let manager = self.config.get_balance_manager(manager_key);
+        let manager_arg = ptb.input(get_object_arg(&self.client, &manager.address).await?)?;
+        let cap_id_arg = ptb.pure(ID::new(cap_id))?;
+
+        ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package: ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+            module: "balance_manager".to_string(),
+            function: "revoke_trade_cap".to_string(),
+            type_arguments: vec![],
+            arguments: vec![manager_arg, cap_id_arg],
+        })));
+
+        Ok(())
+    }
+
+    /// Replace `old_cap_id` with a freshly minted cap sent to `delegate`,
+    /// in the same transaction so there is no window with both or neither
+    pub async fn rotate_cap(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        kind: CapKind,
+        old_cap_id: ObjectID,
+        delegate: SuiAddress,
+    ) -> Result<Argument> {
+        let cap = self.mint_cap_for(ptb, manager_key, kind, delegate).await?;
+        self.revoke_trade_cap(ptb, manager_key, old_cap_id).await?;
+        Ok(cap)
+    }
+
     pub async fn deposit_with_cap(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
@@ -366,11 +521,35 @@ impl BalanceManagerContract {
         coin_key: &str,
         amount: f64,
     ) -> Result<()> {
-        let manager = self.config.get_balance_manager(manager_key);
         let coin = self.config.get_coin(coin_key);
 
         let deposit_input = (amount * coin.scalar as f64).round() as u64;
 
+        let coin_arg = merge_and_split_coins(
+            &self.client,
+            ptb,
+            self.config.sender_address,
+            &coin.coin_type,
+            vec![deposit_input],
+        )
+        .await?
+        .remove(0);
+
+        self.deposit_coin_with_cap(ptb, manager_key, coin_key, coin_arg)
+            .await
+    }
+
+    /// Deposit a coin produced earlier in the PTB through the DepositCap
+    pub async fn deposit_coin_with_cap(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        coin_key: &str,
+        coin_arg: Argument,
+    ) -> Result<()> {
+        let manager = self.config.get_balance_manager(manager_key);
+        let coin = self.config.get_coin(coin_key);
+
         let manager_arg = ptb.input(get_object_arg(&self.client, &manager.address).await?)?;
         let deposit_cap_arg = ptb.input(
             get_object_arg(
@@ -382,16 +561,6 @@ impl BalanceManagerContract {
             .await?,
         )?;
 
-        let coin_arg = merge_and_split_coins(
-            &self.client,
-            ptb,
-            self.config.sender_address,
-            &coin.coin_type,
-            vec![deposit_input],
-        )
-        .await?
-        .remove(0);
-
         ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
             package: ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
             module: "balance_manager".to_string(),
@@ -409,7 +578,7 @@ impl BalanceManagerContract {
         manager_key: &str,
         coin_key: &str,
         amount: f64,
-    ) -> Result<()> {
+    ) -> Result<Argument> {
         let manager = self.config.get_balance_manager(manager_key);
         let coin = self.config.get_coin(coin_key);
 
@@ -428,15 +597,30 @@ impl BalanceManagerContract {
 
         let amount_arg = ptb.pure(withdraw_amount)?;
 
-        ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
-            package: ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
-            module: "balance_manager".to_string(),
-            function: "withdraw_with_cap".to_string(),
-            type_arguments: vec![parse_type_input(&coin.coin_type)?],
-            arguments: vec![manager_arg, withdraw_cap_arg, amount_arg],
-        })));
+        Ok(
+            ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+                package: ObjectID::from_hex_literal(&self.config.deepbook_package_id)?,
+                module: "balance_manager".to_string(),
+                function: "withdraw_with_cap".to_string(),
+                type_arguments: vec![parse_type_input(&coin.coin_type)?],
+                arguments: vec![manager_arg, withdraw_cap_arg, amount_arg],
+            }))),
+        )
+    }
 
-        Ok(())
+    /// `withdraw_with_cap`, sending the coin to `recipient`
+    pub async fn withdraw_with_cap_to(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        manager_key: &str,
+        coin_key: &str,
+        amount: f64,
+        recipient: SuiAddress,
+    ) -> Result<()> {
+        let coin = self
+            .withdraw_with_cap(ptb, manager_key, coin_key, amount)
+            .await?;
+        transfer_objects(ptb, vec![coin], recipient)
     }
 
     pub async fn get_manager_owner(
@@ -448,7 +632,7 @@ impl BalanceManagerContract {
         let manager_id = self.config.get_balance_manager(manager_key).address;
 
         // ✅ Convert Manager ID to ObjectRef
-        let manager_object = get_object_arg(&self.client, manager_id)
+        let manager_object = get_object_arg(&self.client, &manager_id)
             .await
             .context("Failed to get object argument for manager_id")?;
 
@@ -480,7 +664,7 @@ impl BalanceManagerContract {
         let manager_id = self.config.get_balance_manager(manager_key).address;
 
         // ✅ Convert Manager ID to ObjectRef
-        let manager_object = get_object_arg(&self.client, manager_id)
+        let manager_object = get_object_arg(&self.client, &manager_id)
             .await
             .context("Failed to get object argument for manager_id")?;
 
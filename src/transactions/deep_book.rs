@@ -1,4 +1,4 @@
-use anyhow::{Context, Ok, Result, anyhow};
+use anyhow::{Context, Result, anyhow};
 use sui_sdk::SuiClient;
 use sui_sdk::types::base_types::ObjectID;
 use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
@@ -6,10 +6,15 @@ use sui_sdk::types::transaction::{CallArg, Command, ProgrammableMoveCall};
 use sui_types::transaction::{Argument, ProgrammableTransaction};
 
 use super::balance_manager::BalanceManagerContract;
+use super::deep_book_admin::{validate_lot_size, validate_tick_size};
+use crate::errors::LadderError;
 use crate::types::{
-    OrderType, PlaceLimitOrderParams, PlaceMarketOrderParams, SelfMatchingOptions, SwapParams,
+    BookParams, ClientOrderId, LadderDistribution, LadderParams, OrderType, PlaceLimitOrderParams,
+    PlaceMarketOrderParams, SelfMatchingOptions, SwapParams,
+};
+use crate::utils::config::{
+    DEEP_SCALAR, DeepBookConfig, FLOAT_SCALAR, GAS_BUDGET, MAX_TIMESTAMP, POOL_CREATION_FEE,
 };
-use crate::utils::config::{DEEP_SCALAR, DeepBookConfig, FLOAT_SCALAR, GAS_BUDGET, MAX_TIMESTAMP};
 use crate::utils::{get_object_arg, merge_and_split_coins, parse_type_input};
 
 #[derive(Clone)]
@@ -36,6 +41,22 @@ impl DeepBookContract {
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
         params: &PlaceLimitOrderParams,
+    ) -> Result<()> {
+        let trade_proof = self
+            .balance_manager
+            .generate_proof(ptb, &params.balance_manager_key)
+            .await?;
+        self.place_limit_order_with_proof(ptb, params, trade_proof)
+            .await
+    }
+
+    /// `place_limit_order` with a trade proof already in the PTB, so a batch
+    /// of orders can share one
+    pub async fn place_limit_order_with_proof(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        params: &PlaceLimitOrderParams,
+        trade_proof: Argument,
     ) -> Result<()> {
         let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
 
@@ -55,22 +76,17 @@ impl DeepBookContract {
         let balance_manager = self
             .config
             .get_balance_manager(balance_manager_key.as_str());
-        let balance_manager_object = get_object_arg(&self.client, balance_manager.address)
+        let balance_manager_object = get_object_arg(&self.client, &balance_manager.address)
             .await
             .context("Failed to get object argument for balance_manager")?;
 
         let balance_manager_arg = ptb.input(balance_manager_object)?;
 
-        let trade_proof = self
-            .balance_manager
-            .generate_proof(ptb, balance_manager_key)
-            .await?;
-
         let pools = self.config.get_pool(pool_key);
-        let base_coin = self.config.get_coin(pools.base_coin);
+        let base_coin = self.config.get_coin(&pools.base_coin);
 
-        let quote_coin = self.config.get_coin(pools.quote_coin);
-        let pool_object = get_object_arg(&self.client, pools.address)
+        let quote_coin = self.config.get_coin(&pools.quote_coin);
+        let pool_object = get_object_arg(&self.client, &pools.address)
             .await
             .context("Failed to get object argument for pool")?;
         let type_argument_base_coin = parse_type_input(base_coin.coin_type)?;
@@ -79,7 +95,7 @@ impl DeepBookContract {
 
         let pool_arg = ptb.input(pool_object)?;
 
-        let client_order_id_arg = ptb.pure(client_order_id.parse().unwrap_or(0u64))?;
+        let client_order_id_arg = ptb.pure(client_order_id.0)?;
 
         let input_order_number = match order_type {
             Some(value) => *value as u8,
@@ -148,10 +164,45 @@ impl DeepBookContract {
         Ok(())
     }
 
+    /// Place the orders of [`ladder_orders`] under one trade proof.
+    /// Returns the orders added to the PTB.
+    pub async fn place_ladder(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        params: &LadderParams,
+        book: &BookParams,
+    ) -> Result<Vec<PlaceLimitOrderParams>> {
+        let orders = ladder_orders(params, book)?;
+        let trade_proof = self
+            .balance_manager
+            .generate_proof(ptb, &params.balance_manager_key)
+            .await?;
+        for order in &orders {
+            self.place_limit_order_with_proof(ptb, order, trade_proof)
+                .await?;
+        }
+        Ok(orders)
+    }
+
     pub async fn place_market_order(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
         params: &PlaceMarketOrderParams,
+    ) -> Result<()> {
+        let trade_proof = self
+            .balance_manager
+            .generate_proof(ptb, &params.balance_manager_key)
+            .await?;
+        self.place_market_order_with_proof(ptb, params, trade_proof)
+            .await
+    }
+
+    /// `place_market_order` with a trade proof already in the PTB
+    pub async fn place_market_order_with_proof(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        params: &PlaceMarketOrderParams,
+        trade_proof: Argument,
     ) -> Result<()> {
         let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
 
@@ -169,22 +220,17 @@ impl DeepBookContract {
             .config
             .get_balance_manager(balance_manager_key.as_str());
 
-        let balance_manager_object = get_object_arg(&self.client, balance_manager.address)
+        let balance_manager_object = get_object_arg(&self.client, &balance_manager.address)
             .await
             .context("Failed to get object argument for balance_manager")?;
 
         let balance_manager_arg = ptb.input(balance_manager_object)?;
 
-        let trade_proof = self
-            .balance_manager
-            .generate_proof(ptb, balance_manager_key)
-            .await?;
-
         let pools = self.config.get_pool(pool_key);
-        let base_coin = self.config.get_coin(pools.base_coin);
+        let base_coin = self.config.get_coin(&pools.base_coin);
 
-        let quote_coin = self.config.get_coin(pools.quote_coin);
-        let pool_object = get_object_arg(&self.client, pools.address)
+        let quote_coin = self.config.get_coin(&pools.quote_coin);
+        let pool_object = get_object_arg(&self.client, &pools.address)
             .await
             .context("Failed to get object argument for pool")?;
         let type_argument_base_coin = parse_type_input(base_coin.coin_type)?;
@@ -193,7 +239,7 @@ impl DeepBookContract {
 
         let pool_arg = ptb.input(pool_object)?;
 
-        let client_order_id_arg = ptb.pure(client_order_id.parse().unwrap_or(0u64))?;
+        let client_order_id_arg = ptb.pure(client_order_id.0)?;
 
         let self_matching_option_number = match self_matching_option {
             Some(value) => *value as u8,
@@ -244,27 +290,39 @@ impl DeepBookContract {
         pool_key: &str,
         balance_manager_key: &str,
         order_id: u128,
+    ) -> Result<()> {
+        let trade_proof = self
+            .balance_manager
+            .generate_proof(ptb, balance_manager_key)
+            .await?;
+        self.cancel_order_with_proof(ptb, pool_key, balance_manager_key, order_id, trade_proof)
+            .await
+    }
+
+    /// `cancel_order` with a trade proof already in the PTB
+    pub async fn cancel_order_with_proof(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        balance_manager_key: &str,
+        order_id: u128,
+        trade_proof: Argument,
     ) -> Result<()> {
         let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
 
         let balance_manager = self.config.get_balance_manager(balance_manager_key);
 
-        let balance_manager_object = get_object_arg(&self.client, balance_manager.address)
+        let balance_manager_object = get_object_arg(&self.client, &balance_manager.address)
             .await
             .context("Failed to get object argument for balance_manager")?;
 
         let balance_manager_arg = ptb.input(balance_manager_object)?;
 
-        let trade_proof = self
-            .balance_manager
-            .generate_proof(ptb, balance_manager_key)
-            .await?;
-
         let pools = self.config.get_pool(pool_key);
-        let base_coin = self.config.get_coin(pools.base_coin);
+        let base_coin = self.config.get_coin(&pools.base_coin);
 
-        let quote_coin = self.config.get_coin(pools.quote_coin);
-        let pool_object = get_object_arg(&self.client, pools.address)
+        let quote_coin = self.config.get_coin(&pools.quote_coin);
+        let pool_object = get_object_arg(&self.client, &pools.address)
             .await
             .context("Failed to get object argument for pool")?;
         let type_argument_base_coin = parse_type_input(base_coin.coin_type)?;
@@ -294,6 +352,84 @@ impl DeepBookContract {
         Ok(())
     }
 
+    /// Reduce an open order to `new_quantity` (in the base coin), keeping its
+    /// place in the queue. DeepBook only allows shrinking an order.
+    pub async fn modify_order(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        balance_manager_key: &str,
+        order_id: u128,
+        new_quantity: f64,
+    ) -> Result<()> {
+        let trade_proof = self
+            .balance_manager
+            .generate_proof(ptb, balance_manager_key)
+            .await?;
+        self.modify_order_with_proof(
+            ptb,
+            pool_key,
+            balance_manager_key,
+            order_id,
+            new_quantity,
+            trade_proof,
+        )
+        .await
+    }
+
+    /// `modify_order` with a trade proof already in the PTB
+    pub async fn modify_order_with_proof(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        balance_manager_key: &str,
+        order_id: u128,
+        new_quantity: f64,
+        trade_proof: Argument,
+    ) -> Result<()> {
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let balance_manager = self.config.get_balance_manager(balance_manager_key);
+        let balance_manager_arg = ptb.input(
+            get_object_arg(&self.client, &balance_manager.address)
+                .await
+                .context("Failed to get object argument for balance_manager")?,
+        )?;
+
+        let pool = self.config.get_pool(pool_key);
+        let base_coin = self.config.get_coin(&pool.base_coin);
+        let quote_coin = self.config.get_coin(&pool.quote_coin);
+        let pool_arg = ptb.input(
+            get_object_arg(&self.client, &pool.address)
+                .await
+                .context("Failed to get object argument for pool")?,
+        )?;
+
+        let order_id_arg = ptb.pure(order_id)?;
+        let new_quantity_arg = ptb.pure((new_quantity * base_coin.scalar as f64).round() as u64)?;
+        let clock_arg = ptb.input(CallArg::CLOCK_IMM)?;
+
+        ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package: package_id,
+            module: "pool".to_string(),
+            function: "modify_order".to_string(),
+            arguments: vec![
+                pool_arg,
+                balance_manager_arg,
+                trade_proof,
+                order_id_arg,
+                new_quantity_arg,
+                clock_arg,
+            ],
+            type_arguments: vec![
+                parse_type_input(base_coin.coin_type)?,
+                parse_type_input(quote_coin.coin_type)?,
+            ],
+        })));
+
+        Ok(())
+    }
+
     pub async fn cancel_all_orders(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
@@ -304,7 +440,7 @@ impl DeepBookContract {
 
         let balance_manager = self.config.get_balance_manager(balance_manager_key);
 
-        let balance_manager_object = get_object_arg(&self.client, balance_manager.address)
+        let balance_manager_object = get_object_arg(&self.client, &balance_manager.address)
             .await
             .context("Failed to get object argument for balance_manager")?;
 
@@ -316,10 +452,10 @@ impl DeepBookContract {
             .await?;
 
         let pools = self.config.get_pool(pool_key);
-        let base_coin = self.config.get_coin(pools.base_coin);
+        let base_coin = self.config.get_coin(&pools.base_coin);
 
-        let quote_coin = self.config.get_coin(pools.quote_coin);
-        let pool_object = get_object_arg(&self.client, pools.address)
+        let quote_coin = self.config.get_coin(&pools.quote_coin);
+        let pool_object = get_object_arg(&self.client, &pools.address)
             .await
             .context("Failed to get object argument for pool")?;
         let type_argument_base_coin = parse_type_input(base_coin.coin_type)?;
@@ -561,6 +697,74 @@ impl DeepBookContract {
         Ok(())
     }
 
+    /// Whether the manager has an account in the pool, i.e. has traded in it
+    pub async fn account_exists(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        manager_key: &str,
+    ) -> Result<()> {
+        let pool = self.config.get_pool(pool_key);
+        let manager = self.config.get_balance_manager(manager_key);
+        let base_coin = self.config.get_coin(&pool.base_coin);
+        let quote_coin = self.config.get_coin(&pool.quote_coin);
+
+        let pool_object = get_object_arg(&self.client, &pool.address)
+            .await
+            .context("Failed to get pool object argument")?;
+        let manager_object = get_object_arg(&self.client, &manager.address)
+            .await
+            .context("Failed to get manager object argument")?;
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let pool_object_arg = ptb.input(pool_object)?;
+        let manager_object_arg = ptb.input(manager_object)?;
+        ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package: package_id,
+            module: "pool".to_string(),
+            function: "account_exists".to_string(),
+            type_arguments: vec![
+                parse_type_input(&base_coin.coin_type)?,
+                parse_type_input(&quote_coin.coin_type)?,
+            ],
+            arguments: vec![pool_object_arg, manager_object_arg],
+        })));
+
+        Ok(())
+    }
+
+    pub async fn get_orders(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        order_ids: Vec<u128>,
+    ) -> Result<()> {
+        let pool = self.config.get_pool(pool_key);
+        let base_coin = self.config.get_coin(&pool.base_coin);
+        let quote_coin = self.config.get_coin(&pool.quote_coin);
+
+        let pool_object = get_object_arg(&self.client, &pool.address)
+            .await
+            .context("Failed to get pool object argument")?;
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let pool_object_arg = ptb.input(pool_object)?;
+        let order_ids_arg = ptb.pure(order_ids)?;
+
+        ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package: package_id,
+            module: "pool".to_string(),
+            function: "get_orders".to_string(),
+            type_arguments: vec![
+                parse_type_input(&base_coin.coin_type)?,
+                parse_type_input(&quote_coin.coin_type)?,
+            ],
+            arguments: vec![pool_object_arg, order_ids_arg],
+        })));
+
+        Ok(())
+    }
+
     pub async fn get_level2_range(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
@@ -708,6 +912,73 @@ impl DeepBookContract {
         Ok(())
     }
 
+    /// Create a pool without the admin cap, paying `POOL_CREATION_FEE` DEEP
+    /// from the sender's coins. Returns the new pool's ID.
+    pub async fn create_permissionless_pool(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        base_coin_key: &str,
+        quote_coin_key: &str,
+        tick_size: f64,
+        lot_size: f64,
+        min_size: f64,
+    ) -> Result<Argument> {
+        let base_coin = self.config.get_coin(base_coin_key);
+        let quote_coin = self.config.get_coin(quote_coin_key);
+        let deep_coin = self.config.get_coin("DEEP");
+
+        let base_scalar = base_coin.scalar as f64;
+        let quote_scalar = quote_coin.scalar as f64;
+
+        let adjusted_tick_size =
+            ((tick_size * FLOAT_SCALAR * quote_scalar) / base_scalar).round() as u64;
+        let adjusted_lot_size = (lot_size * base_scalar).round() as u64;
+        let adjusted_min_size = (min_size * base_scalar).round() as u64;
+        validate_tick_size(adjusted_tick_size)?;
+        validate_lot_size(adjusted_lot_size, adjusted_min_size)?;
+
+        let creation_fee = merge_and_split_coins(
+            &self.client,
+            ptb,
+            self.config.sender_address,
+            &deep_coin.coin_type,
+            vec![(POOL_CREATION_FEE * deep_coin.scalar as f64).round() as u64],
+        )
+        .await
+        .context("Failed to split DEEP for the pool creation fee")?
+        .remove(0);
+
+        let registry_object = get_object_arg(&self.client, &self.config.registry_id)
+            .await
+            .context("Failed to get registry object argument")?;
+
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let registry_input = ptb.input(registry_object)?;
+        let tick_size_input = ptb.pure(adjusted_tick_size)?;
+        let lot_size_input = ptb.pure(adjusted_lot_size)?;
+        let min_size_input = ptb.pure(adjusted_min_size)?;
+
+        Ok(
+            ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+                package: package_id,
+                module: "pool".to_string(),
+                function: "create_permissionless_pool".to_string(),
+                type_arguments: vec![
+                    parse_type_input(&base_coin.coin_type)?,
+                    parse_type_input(&quote_coin.coin_type)?,
+                ],
+                arguments: vec![
+                    registry_input,
+                    tick_size_input,
+                    lot_size_input,
+                    min_size_input,
+                    creation_fee,
+                ],
+            }))),
+        )
+    }
+
     pub async fn pool_trade_params(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
@@ -906,8 +1177,6 @@ impl DeepBookContract {
         ptb: &mut ProgrammableTransactionBuilder,
         params: &SwapParams,
     ) -> Result<(Argument, Argument, Argument)> {
-        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
-
         let SwapParams {
             pool_key,
             amount,      // base amount to input
@@ -917,15 +1186,8 @@ impl DeepBookContract {
 
         let pool = self.config.get_pool(pool_key);
         let base_coin = self.config.get_coin(&pool.base_coin);
-        let quote_coin = self.config.get_coin(&pool.quote_coin);
         let deep_coin = self.config.get_coin("DEEP");
 
-        // Get pool object
-        let pool_object = get_object_arg(&self.client, &pool.address)
-            .await
-            .context("Failed to get pool object argument")?;
-        let pool_object_arg = ptb.input(pool_object)?;
-
         let base_amount_input = (amount * base_coin.scalar as f64).round() as u64;
         let deep_amount_input = (deep_amount * deep_coin.scalar as f64).round() as u64;
 
@@ -967,6 +1229,38 @@ impl DeepBookContract {
             (base_coin_input, deep_coin_input)
         };
 
+        self.swap_exact_base_for_quote_with_coins(
+            ptb,
+            pool_key,
+            base_coin_input,
+            deep_coin_input,
+            *min_out,
+        )
+        .await
+    }
+
+    /// Swap a base coin already present in the PTB (e.g. a flash loan or a
+    /// previous swap output) for quote. Returns `(base, quote, deep)` coins.
+    pub async fn swap_exact_base_for_quote_with_coins(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        base_coin_input: Argument,
+        deep_coin_input: Argument,
+        min_out: f64,
+    ) -> Result<(Argument, Argument, Argument)> {
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let pool = self.config.get_pool(pool_key);
+        let base_coin = self.config.get_coin(&pool.base_coin);
+        let quote_coin = self.config.get_coin(&pool.quote_coin);
+
+        // Get pool object
+        let pool_object = get_object_arg(&self.client, &pool.address)
+            .await
+            .context("Failed to get pool object argument")?;
+        let pool_object_arg = ptb.input(pool_object)?;
+
         // Min quote out (should use quote coin scalar!)
         let min_out_input = ptb.pure((min_out * quote_coin.scalar as f64).round() as u64)?;
 
@@ -1013,8 +1307,6 @@ impl DeepBookContract {
         ptb: &mut ProgrammableTransactionBuilder,
         params: &SwapParams,
     ) -> Result<(Argument, Argument, Argument)> {
-        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
-
         let SwapParams {
             pool_key,
             amount, // this is quoteAmount
@@ -1023,15 +1315,9 @@ impl DeepBookContract {
         } = params;
 
         let pool = self.config.get_pool(pool_key);
-        let base_coin = self.config.get_coin(&pool.base_coin);
         let quote_coin = self.config.get_coin(&pool.quote_coin);
         let deep_coin = self.config.get_coin("DEEP");
 
-        let pool_object = get_object_arg(&self.client, &pool.address)
-            .await
-            .context("Failed to get pool object argument")?;
-        let pool_object_arg = ptb.input(pool_object)?;
-
         let quote_amount_input = (amount * quote_coin.scalar as f64).round() as u64;
         let deep_amount_input = (deep_amount * deep_coin.scalar as f64).round() as u64;
 
@@ -1076,6 +1362,37 @@ impl DeepBookContract {
             (quote_coin_input, deep_coin_input)
         };
 
+        self.swap_exact_quote_for_base_with_coins(
+            ptb,
+            pool_key,
+            quote_coin_input,
+            deep_coin_input,
+            *min_out,
+        )
+        .await
+    }
+
+    /// Swap a quote coin already present in the PTB for base.
+    /// Returns `(base, quote, deep)` coins.
+    pub async fn swap_exact_quote_for_base_with_coins(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        quote_coin_input: Argument,
+        deep_coin_input: Argument,
+        min_out: f64,
+    ) -> Result<(Argument, Argument, Argument)> {
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let pool = self.config.get_pool(pool_key);
+        let base_coin = self.config.get_coin(&pool.base_coin);
+        let quote_coin = self.config.get_coin(&pool.quote_coin);
+
+        let pool_object = get_object_arg(&self.client, &pool.address)
+            .await
+            .context("Failed to get pool object argument")?;
+        let pool_object_arg = ptb.input(pool_object)?;
+
         let min_base_input = ptb.pure((min_out * base_coin.scalar as f64).round() as u64)?;
         let clock_arg = ptb.input(CallArg::CLOCK_IMM)?;
 
@@ -1096,8 +1413,6 @@ impl DeepBookContract {
             ],
         })));
 
-        println!("{}", swap_call);
-
         // Extract results from the move call
         let command_index = match swap_call {
             Argument::Result(index) => index,
@@ -1115,3 +1430,90 @@ impl DeepBookContract {
         Ok((base_coin_result, quote_coin_result, deep_coin_result))
     }
 }
+
+/// Post-only orders for a ladder, from `start_price` to `end_price`.
+/// Prices snap to ticks away from the spread (bids down, asks up) and sizes
+/// to lots, with leftover lots going to the levels nearest `start_price`.
+/// Level `i` gets client order id `first_client_order_id + i`.
+pub fn ladder_orders(
+    params: &LadderParams,
+    book: &BookParams,
+) -> Result<Vec<PlaceLimitOrderParams>, LadderError> {
+    let LadderParams {
+        start_price: start,
+        end_price: end,
+        levels,
+        ..
+    } = *params;
+    if levels == 0 {
+        return Err(LadderError::NoLevels);
+    }
+    if !(start > 0.0 && end > 0.0) {
+        return Err(LadderError::InvalidPrice { start, end });
+    }
+    let first = params.first_client_order_id.0;
+    if first.checked_add(levels as u64 - 1).is_none() {
+        return Err(LadderError::ClientOrderIdOverflow { first, levels });
+    }
+
+    // Slack so prices already on a tick don't round to the next one
+    const EPSILON: f64 = 1e-6;
+    let steps = (levels - 1).max(1) as f64;
+    let ticks: Vec<u64> = (0..levels)
+        .map(|level| {
+            let t = level as f64 / steps;
+            let price = match params.distribution {
+                LadderDistribution::Arithmetic => start + (end - start) * t,
+                LadderDistribution::Geometric => start * (end / start).powf(t),
+            };
+            let ticks = price / book.tick_size;
+            if params.is_bid {
+                (ticks + EPSILON).floor() as u64
+            } else {
+                (ticks - EPSILON).ceil() as u64
+            }
+        })
+        .collect();
+    if ticks.contains(&0) {
+        return Err(LadderError::InvalidPrice { start, end });
+    }
+    if ticks.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err(LadderError::LevelsOverlap {
+            levels,
+            start,
+            end,
+            tick_size: book.tick_size,
+        });
+    }
+
+    let total_lots = (params.total_size / book.lot_size + EPSILON).floor() as u64;
+    let per_level = total_lots / levels as u64;
+    let extra = total_lots % levels as u64;
+    let min_lots = (book.min_size / book.lot_size).round() as u64;
+    if per_level < min_lots || per_level == 0 {
+        return Err(LadderError::SizeTooSmall {
+            size: per_level as f64 * book.lot_size,
+            min_size: book.min_size,
+        });
+    }
+
+    Ok(ticks
+        .into_iter()
+        .enumerate()
+        .map(|(level, ticks)| {
+            let lots = per_level + u64::from((level as u64) < extra);
+            PlaceLimitOrderParams {
+                pool_key: params.pool_key.clone(),
+                balance_manager_key: params.balance_manager_key.clone(),
+                client_order_id: ClientOrderId(first + level as u64),
+                price: ticks as f64 * book.tick_size,
+                quantity: lots as f64 * book.lot_size,
+                is_bid: params.is_bid,
+                expiration: params.expiration,
+                order_type: Some(OrderType::PostOnly),
+                self_matching_option: None,
+                pay_with_deep: params.pay_with_deep,
+            }
+        })
+        .collect())
+}
@@ -2,10 +2,15 @@ use anyhow::{Context, Result};
 use sui_sdk::SuiClient;
 use sui_sdk::types::base_types::{ObjectID, SuiAddress};
 use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
-use sui_sdk::types::transaction::{Command, ProgrammableMoveCall};
+use sui_sdk::types::transaction::{
+    CallArg, Command, ProgrammableMoveCall, ProgrammableTransaction, TransactionData,
+};
 
+use crate::errors::BookParamsError;
 use crate::utils::config::{DeepBookConfig, FLOAT_SCALAR};
-use crate::utils::{get_object_arg, parse_type_input};
+use crate::utils::explain::describe_commands;
+use crate::utils::offline::encode_transaction_data;
+use crate::utils::{get_object_arg, parse_type_input, select_gas_coin};
 
 #[derive(Clone)]
 pub struct DeepBookAdminContract {
@@ -90,8 +95,8 @@ impl DeepBookAdminContract {
         pool_key: &str,
     ) -> Result<()> {
         let pool = self.config.get_pool(pool_key);
-        let base_coin = self.config.get_coin(pool.base_coin);
-        let quote_coin = self.config.get_coin(pool.quote_coin);
+        let base_coin = self.config.get_coin(&pool.base_coin);
+        let quote_coin = self.config.get_coin(&pool.quote_coin);
 
         let pool_object = get_object_arg(&self.client, &pool.address).await?;
         let registry_id = get_object_arg(&self.client, &self.config.registry_id).await?;
@@ -124,8 +129,8 @@ impl DeepBookAdminContract {
         pool_key: &str,
     ) -> Result<()> {
         let pool = self.config.get_pool(pool_key);
-        let base_coin = self.config.get_coin(pool.base_coin);
-        let quote_coin = self.config.get_coin(pool.quote_coin);
+        let base_coin = self.config.get_coin(&pool.base_coin);
+        let quote_coin = self.config.get_coin(&pool.quote_coin);
 
         let pool_object = get_object_arg(&self.client, &pool.address).await?;
         let registry_id = get_object_arg(&self.client, &self.config.registry_id).await?;
@@ -228,4 +233,305 @@ impl DeepBookAdminContract {
 
         Ok(())
     }
+
+    /// Change a pool's tick size, in quote per base
+    pub async fn adjust_tick_size_admin(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        new_tick_size: f64,
+    ) -> Result<()> {
+        let pool = self.config.get_pool(pool_key);
+        let base_coin = self.config.get_coin(&pool.base_coin);
+        let quote_coin = self.config.get_coin(&pool.quote_coin);
+
+        let adjusted_tick_size = ((new_tick_size * FLOAT_SCALAR * quote_coin.scalar as f64)
+            / base_coin.scalar as f64)
+            .round() as u64;
+        validate_tick_size(adjusted_tick_size)?;
+
+        let pool_object = get_object_arg(&self.client, &pool.address).await?;
+        let admin_cap = get_object_arg(&self.client, &self.admin_cap()?).await?;
+
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let pool_object_input = ptb.input(pool_object)?;
+        let tick_size_input = ptb.pure(adjusted_tick_size)?;
+        let admin_cap_input = ptb.input(admin_cap)?;
+        let clock_input = ptb.input(CallArg::CLOCK_IMM)?;
+
+        ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package: package_id,
+            module: "pool".to_string(),
+            function: "adjust_tick_size_admin".to_string(),
+            type_arguments: vec![
+                parse_type_input(&base_coin.coin_type)?,
+                parse_type_input(&quote_coin.coin_type)?,
+            ],
+            arguments: vec![
+                pool_object_input,
+                tick_size_input,
+                admin_cap_input,
+                clock_input,
+            ],
+        })));
+
+        Ok(())
+    }
+
+    /// Change a pool's lot size and min size, in base units
+    pub async fn adjust_min_lot_size_admin(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        new_lot_size: f64,
+        new_min_size: f64,
+    ) -> Result<()> {
+        let pool = self.config.get_pool(pool_key);
+        let base_coin = self.config.get_coin(&pool.base_coin);
+        let quote_coin = self.config.get_coin(&pool.quote_coin);
+
+        let adjusted_lot_size = (new_lot_size * base_coin.scalar as f64).round() as u64;
+        let adjusted_min_size = (new_min_size * base_coin.scalar as f64).round() as u64;
+        validate_lot_size(adjusted_lot_size, adjusted_min_size)?;
+
+        let pool_object = get_object_arg(&self.client, &pool.address).await?;
+        let admin_cap = get_object_arg(&self.client, &self.admin_cap()?).await?;
+
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let pool_object_input = ptb.input(pool_object)?;
+        let lot_size_input = ptb.pure(adjusted_lot_size)?;
+        let min_size_input = ptb.pure(adjusted_min_size)?;
+        let admin_cap_input = ptb.input(admin_cap)?;
+        let clock_input = ptb.input(CallArg::CLOCK_IMM)?;
+
+        ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package: package_id,
+            module: "pool".to_string(),
+            function: "adjust_min_lot_size_admin".to_string(),
+            type_arguments: vec![
+                parse_type_input(&base_coin.coin_type)?,
+                parse_type_input(&quote_coin.coin_type)?,
+            ],
+            arguments: vec![
+                pool_object_input,
+                lot_size_input,
+                min_size_input,
+                admin_cap_input,
+                clock_input,
+            ],
+        })));
+
+        Ok(())
+    }
+
+    /// Record a DEEP price point on `target_pool_key` from a whitelisted
+    /// DEEP pool, so the target can charge fees in DEEP. Permissionless on
+    /// chain, unlike the other builders here.
+    pub async fn add_deep_price_point(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        target_pool_key: &str,
+        reference_pool_key: &str,
+    ) -> Result<()> {
+        let target_pool = self.config.get_pool(target_pool_key);
+        let reference_pool = self.config.get_pool(reference_pool_key);
+        let target_base_coin = self.config.get_coin(&target_pool.base_coin);
+        let target_quote_coin = self.config.get_coin(&target_pool.quote_coin);
+        let reference_base_coin = self.config.get_coin(&reference_pool.base_coin);
+        let reference_quote_coin = self.config.get_coin(&reference_pool.quote_coin);
+
+        let target_pool_object = get_object_arg(&self.client, &target_pool.address).await?;
+        let reference_pool_object = get_object_arg(&self.client, &reference_pool.address).await?;
+
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let target_pool_input = ptb.input(target_pool_object)?;
+        let reference_pool_input = ptb.input(reference_pool_object)?;
+        let clock_input = ptb.input(CallArg::CLOCK_IMM)?;
+
+        ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package: package_id,
+            module: "pool".to_string(),
+            function: "add_deep_price_point".to_string(),
+            type_arguments: vec![
+                parse_type_input(&target_base_coin.coin_type)?,
+                parse_type_input(&target_quote_coin.coin_type)?,
+                parse_type_input(&reference_base_coin.coin_type)?,
+                parse_type_input(&reference_quote_coin.coin_type)?,
+            ],
+            arguments: vec![target_pool_input, reference_pool_input, clock_input],
+        })));
+
+        Ok(())
+    }
+
+    /// Sync a pool's allowed versions with the registry. Permissionless on chain.
+    pub async fn update_pool_allowed_versions(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+    ) -> Result<()> {
+        let pool = self.config.get_pool(pool_key);
+        let base_coin = self.config.get_coin(&pool.base_coin);
+        let quote_coin = self.config.get_coin(&pool.quote_coin);
+
+        let pool_object = get_object_arg(&self.client, &pool.address).await?;
+        let registry_id = get_object_arg(&self.client, &self.config.registry_id).await?;
+
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let pool_object_input = ptb.input(pool_object)?;
+        let registry_id_input = ptb.input(registry_id)?;
+
+        ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package: package_id,
+            module: "pool".to_string(),
+            function: "update_pool_allowed_versions".to_string(),
+            type_arguments: vec![
+                parse_type_input(&base_coin.coin_type)?,
+                parse_type_input(&quote_coin.coin_type)?,
+            ],
+            arguments: vec![pool_object_input, registry_id_input],
+        })));
+
+        Ok(())
+    }
+
+    /// Register a stablecoin. Pools created between two stablecoins are stable pools.
+    pub async fn add_stablecoin(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        coin_key: &str,
+    ) -> Result<()> {
+        self.registry_coin_admin_call(ptb, "add_stablecoin", coin_key)
+            .await
+    }
+
+    /// Remove a stablecoin registered with `add_stablecoin`
+    pub async fn remove_stablecoin(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        coin_key: &str,
+    ) -> Result<()> {
+        self.registry_coin_admin_call(ptb, "remove_stablecoin", coin_key)
+            .await
+    }
+
+    /// Authorize an app, given by its witness type, to use DeepBook app features
+    pub async fn authorize_app(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        app_type: &str,
+    ) -> Result<()> {
+        self.registry_type_admin_call(ptb, "authorize_app", app_type)
+            .await
+    }
+
+    /// Revoke an app authorized with `authorize_app`
+    pub async fn deauthorize_app(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        app_type: &str,
+    ) -> Result<()> {
+        self.registry_type_admin_call(ptb, "deauthorize_app", app_type)
+            .await
+    }
+
+    async fn registry_coin_admin_call(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        function: &str,
+        coin_key: &str,
+    ) -> Result<()> {
+        let coin_type = self.config.get_coin(coin_key).coin_type.to_string();
+        self.registry_type_admin_call(ptb, function, &coin_type)
+            .await
+    }
+
+    /// `registry::<function><type_argument>(registry, admin_cap)`
+    async fn registry_type_admin_call(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        function: &str,
+        type_argument: &str,
+    ) -> Result<()> {
+        let registry_id = get_object_arg(&self.client, &self.config.registry_id).await?;
+        let admin_cap = get_object_arg(&self.client, &self.admin_cap()?).await?;
+
+        let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
+
+        let registry_id_input = ptb.input(registry_id)?;
+        let admin_cap_input = ptb.input(admin_cap)?;
+
+        ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package: package_id,
+            module: "registry".to_string(),
+            function: function.to_string(),
+            type_arguments: vec![
+                parse_type_input(type_argument)
+                    .with_context(|| format!("Invalid type argument {}", type_argument))?,
+            ],
+            arguments: vec![registry_id_input, admin_cap_input],
+        })));
+
+        Ok(())
+    }
+
+    /// Wrap an admin PTB into unsigned transaction data for `sender` (e.g. the
+    /// multisig holding the admin cap), encoded as base64 BCS for offline signing
+    pub async fn export_transaction(
+        &self,
+        pt: ProgrammableTransaction,
+        sender: SuiAddress,
+        gas_budget: u64,
+    ) -> Result<String> {
+        let gas_coin = select_gas_coin(&self.client, sender, gas_budget).await?;
+        let gas_price = self.client.read_api().get_reference_gas_price().await?;
+
+        let tx_data =
+            TransactionData::new_programmable(sender, vec![gas_coin], pt, gas_budget, gas_price);
+
+        encode_transaction_data(&tx_data)
+    }
+}
+
+/// One line per command for reviewers, naming known objects (registry,
+/// admin cap, pools, clock) and coins instead of raw ids and type tags
+pub fn summarize_admin_calls(config: &DeepBookConfig, pt: &ProgrammableTransaction) -> Vec<String> {
+    describe_commands(config, pt)
+        .into_iter()
+        .map(|command| format!("#{} {}", command.index, command.summary))
+        .collect()
+}
+
+/// Check a raw tick size the way `book` does on chain
+pub fn validate_tick_size(tick_size: u64) -> Result<(), BookParamsError> {
+    if !is_power_of_ten(tick_size) {
+        return Err(BookParamsError::InvalidTickSize(tick_size));
+    }
+    Ok(())
+}
+
+/// Check that raw lot and min sizes are powers of ten and that min size is
+/// a multiple of lot size. Other on-chain limits are left to the chain.
+pub fn validate_lot_size(lot_size: u64, min_size: u64) -> Result<(), BookParamsError> {
+    if !is_power_of_ten(lot_size) {
+        return Err(BookParamsError::InvalidLotSize(lot_size));
+    }
+    if !is_power_of_ten(min_size) || min_size % lot_size != 0 {
+        return Err(BookParamsError::InvalidMinSize { min_size, lot_size });
+    }
+    Ok(())
+}
+
+fn is_power_of_ten(mut value: u64) -> bool {
+    if value == 0 {
+        return false;
+    }
+    while value % 10 == 0 {
+        value /= 10;
+    }
+    value == 1
 }
@@ -1,15 +1,17 @@
-use core::borrow;
-
 use sui_sdk::{
     SuiClient,
     types::{
-        base_types::ObjectID,
+        base_types::{ObjectID, SuiAddress},
         programmable_transaction_builder::ProgrammableTransactionBuilder,
         transaction::{Argument, Command, ProgrammableMoveCall},
     },
 };
 
-use crate::utils::{config::DeepBookConfig, get_object_arg, parse_type_input};
+use crate::transactions::deep_book::DeepBookContract;
+use crate::types::{SwapDirection, SwapLeg};
+use crate::utils::{
+    config::DeepBookConfig, get_object_arg, merge_and_split_coins, parse_type_input, zero_coin,
+};
 use anyhow::{Context, Ok, Result, anyhow};
 
 #[derive(Clone)]
@@ -32,10 +34,10 @@ impl FlashLoanContract {
 
         let pools = self.config.get_pool(pool_key);
 
-        let base_coin = self.config.get_coin(pools.base_coin);
+        let base_coin = self.config.get_coin(&pools.base_coin);
 
-        let quote_coin = self.config.get_coin(pools.quote_coin);
-        let pool_object = get_object_arg(&self.client, pools.address)
+        let quote_coin = self.config.get_coin(&pools.quote_coin);
+        let pool_object = get_object_arg(&self.client, &pools.address)
             .await
             .context("Failed to get object argument for pool")?;
         let type_argument_base_coin = parse_type_input(&base_coin.coin_type)?;
@@ -84,10 +86,10 @@ impl FlashLoanContract {
 
         let pools = self.config.get_pool(pool_key);
 
-        let base_coin = self.config.get_coin(pools.base_coin);
+        let base_coin = self.config.get_coin(&pools.base_coin);
 
-        let quote_coin = self.config.get_coin(pools.quote_coin);
-        let pool_object = get_object_arg(&self.client, pools.address)
+        let quote_coin = self.config.get_coin(&pools.quote_coin);
+        let pool_object = get_object_arg(&self.client, &pools.address)
             .await
             .context("Failed to get object argument for pool")?;
         let type_argument_base_coin = parse_type_input(&base_coin.coin_type)?;
@@ -122,10 +124,10 @@ impl FlashLoanContract {
 
         let pools = self.config.get_pool(pool_key);
 
-        let base_coin = self.config.get_coin(pools.base_coin);
+        let base_coin = self.config.get_coin(&pools.base_coin);
 
-        let quote_coin = self.config.get_coin(pools.quote_coin);
-        let pool_object = get_object_arg(&self.client, pools.address)
+        let quote_coin = self.config.get_coin(&pools.quote_coin);
+        let pool_object = get_object_arg(&self.client, &pools.address)
             .await
             .context("Failed to get object argument for pool")?;
         let type_argument_base_coin = parse_type_input(&base_coin.coin_type)?;
@@ -134,7 +136,7 @@ impl FlashLoanContract {
 
         let pool_arg = ptb.input(pool_object)?;
 
-        let amount_input = (amount * base_coin.scalar as f64).round() as u64;
+        let amount_input = (amount * quote_coin.scalar as f64).round() as u64;
 
         let amount_arg = ptb.pure(amount_input)?;
 
@@ -174,10 +176,10 @@ impl FlashLoanContract {
 
         let pools = self.config.get_pool(pool_key);
 
-        let base_coin = self.config.get_coin(pools.base_coin);
+        let base_coin = self.config.get_coin(&pools.base_coin);
 
-        let quote_coin = self.config.get_coin(pools.quote_coin);
-        let pool_object = get_object_arg(&self.client, pools.address)
+        let quote_coin = self.config.get_coin(&pools.quote_coin);
+        let pool_object = get_object_arg(&self.client, &pools.address)
             .await
             .context("Failed to get object argument for pool")?;
         let type_argument_base_coin = parse_type_input(&base_coin.coin_type)?;
@@ -186,20 +188,238 @@ impl FlashLoanContract {
 
         let pool_arg = ptb.input(pool_object)?;
 
-        let borrow_amount_input = (borrow_amount * base_coin.scalar as f64).round() as u64;
+        let borrow_amount_input = (borrow_amount * quote_coin.scalar as f64).round() as u64;
 
         let split_borrow_amount = ptb.pure(borrow_amount_input)?;
 
-        let base_coin_return = ptb.command(Command::SplitCoins(coin, vec![split_borrow_amount]));
+        let quote_coin_return = ptb.command(Command::SplitCoins(coin, vec![split_borrow_amount]));
 
         ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
             package: package_id,
             module: "pool".to_string(),
             function: "return_flashloan_quote".to_string(),
             type_arguments: vec![type_argument_base_coin, type_argument_quote_coin],
-            arguments: vec![pool_arg, base_coin_return, flash_loan],
+            arguments: vec![pool_arg, quote_coin_return, flash_loan],
         })));
 
         Ok(coin)
     }
 }
+
+/// A flash loan followed by a chain of swaps that must end in the borrowed
+/// asset. Building the plan borrows, routes each leg's output into the next
+/// leg, repays exactly the borrowed amount and sends whatever is left
+/// (the profit plus swap remainders) to the recipient.
+#[derive(Debug, Clone)]
+pub struct FlashLoanPlan {
+    pub pool_key: String,
+    pub borrow_base: bool,
+    pub amount: f64,
+    pub legs: Vec<SwapLeg>,
+}
+
+/// Command indexes produced by `FlashLoanPlan::build`
+#[derive(Debug, Clone)]
+pub struct FlashLoanPlanCommands {
+    pub borrow_command: u16,
+    pub swap_commands: Vec<u16>,
+    pub profit_coin: Argument,
+}
+
+impl FlashLoanPlan {
+    pub fn borrow_base(pool_key: &str, amount: f64) -> Self {
+        Self {
+            pool_key: pool_key.to_string(),
+            borrow_base: true,
+            amount,
+            legs: vec![],
+        }
+    }
+
+    pub fn borrow_quote(pool_key: &str, amount: f64) -> Self {
+        Self {
+            pool_key: pool_key.to_string(),
+            borrow_base: false,
+            amount,
+            legs: vec![],
+        }
+    }
+
+    pub fn swap(mut self, leg: SwapLeg) -> Self {
+        self.legs.push(leg);
+        self
+    }
+
+    /// Coin key of the borrowed asset
+    pub fn borrowed_coin_key(&self, config: &DeepBookConfig) -> String {
+        let pool = config.get_pool(&self.pool_key);
+        if self.borrow_base {
+            pool.base_coin.to_string()
+        } else {
+            pool.quote_coin.to_string()
+        }
+    }
+
+    /// Check that every leg consumes the coin produced by the previous one
+    /// and that the last leg gives back the borrowed asset.
+    pub fn validate(&self, config: &DeepBookConfig) -> Result<()> {
+        if self.amount <= 0.0 {
+            return Err(anyhow!("Flash loan amount must be positive"));
+        }
+        if self.legs.is_empty() {
+            return Err(anyhow!("Flash loan plan has no swap legs"));
+        }
+
+        let borrowed = self.borrowed_coin_key(config);
+        let mut current = borrowed.clone();
+        for (i, leg) in self.legs.iter().enumerate() {
+            let pool = config.get_pool(&leg.pool_key);
+            let (input, output) = match leg.direction {
+                SwapDirection::BaseForQuote => (&pool.base_coin, &pool.quote_coin),
+                SwapDirection::QuoteForBase => (&pool.quote_coin, &pool.base_coin),
+            };
+            if *input != current {
+                return Err(anyhow!(
+                    "Leg {} on {} expects {} but the previous step produces {}",
+                    i,
+                    leg.pool_key,
+                    input,
+                    current
+                ));
+            }
+            current = output.to_string();
+        }
+
+        if current != borrowed {
+            return Err(anyhow!(
+                "Plan ends in {} but the loan must be repaid in {}",
+                current,
+                borrowed
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Append the whole plan to `ptb`
+    pub async fn build(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        flash_loans: &FlashLoanContract,
+        deep_book: &DeepBookContract,
+        recipient: SuiAddress,
+    ) -> Result<FlashLoanPlanCommands> {
+        let config = &flash_loans.config;
+        self.validate(config)?;
+
+        let deep_coin = config.get_coin("DEEP");
+
+        let (loan_coin, flash_loan) = if self.borrow_base {
+            flash_loans
+                .borrow_base_asset(ptb, &self.pool_key, self.amount)
+                .await?
+        } else {
+            flash_loans
+                .borrow_quote_asset(ptb, &self.pool_key, self.amount)
+                .await?
+        };
+        let borrow_command = match loan_coin {
+            Argument::NestedResult(index, _) => index,
+            _ => return Err(anyhow!("Expected NestedResult from flash loan borrow")),
+        };
+
+        // Split every leg's DEEP in one go; merging the DEEP coins again for
+        // a later leg would reuse coins the first merge consumed
+        let deep_amounts: Vec<u64> = self
+            .legs
+            .iter()
+            .map(|leg| (leg.deep_amount * deep_coin.scalar as f64).round() as u64)
+            .collect();
+        let paid: Vec<u64> = deep_amounts.iter().copied().filter(|a| *a > 0).collect();
+        let mut deep_splits = if paid.is_empty() {
+            vec![]
+        } else {
+            merge_and_split_coins(
+                &flash_loans.client,
+                ptb,
+                config.sender_address,
+                deep_coin.coin_type,
+                paid,
+            )
+            .await?
+        }
+        .into_iter();
+
+        let mut current = loan_coin;
+        let mut leftovers = vec![];
+        let mut swap_commands = vec![];
+
+        for (leg, deep_amount) in self.legs.iter().zip(deep_amounts) {
+            let deep_coin_input = if deep_amount == 0 {
+                zero_coin(ptb, deep_coin.coin_type)?
+            } else {
+                deep_splits
+                    .next()
+                    .ok_or_else(|| anyhow!("Missing DEEP split for leg on {}", leg.pool_key))?
+            };
+
+            let (base_out, quote_out, deep_out) = match leg.direction {
+                SwapDirection::BaseForQuote => {
+                    deep_book
+                        .swap_exact_base_for_quote_with_coins(
+                            ptb,
+                            &leg.pool_key,
+                            current,
+                            deep_coin_input,
+                            leg.min_out,
+                        )
+                        .await?
+                }
+                SwapDirection::QuoteForBase => {
+                    deep_book
+                        .swap_exact_quote_for_base_with_coins(
+                            ptb,
+                            &leg.pool_key,
+                            current,
+                            deep_coin_input,
+                            leg.min_out,
+                        )
+                        .await?
+                }
+            };
+
+            if let Argument::NestedResult(index, _) = base_out {
+                swap_commands.push(index);
+            }
+
+            // Unspent input and unused DEEP go back to the recipient
+            let (next, unspent) = match leg.direction {
+                SwapDirection::BaseForQuote => (quote_out, base_out),
+                SwapDirection::QuoteForBase => (base_out, quote_out),
+            };
+            leftovers.push(unspent);
+            leftovers.push(deep_out);
+            current = next;
+        }
+
+        let profit_coin = if self.borrow_base {
+            flash_loans
+                .return_flashloan_base(ptb, &self.pool_key, self.amount, current, flash_loan)
+                .await?
+        } else {
+            flash_loans
+                .return_flashloan_quote(ptb, &self.pool_key, self.amount, current, flash_loan)
+                .await?
+        };
+
+        let mut transfers = vec![profit_coin];
+        transfers.extend(leftovers);
+        ptb.transfer_args(recipient, transfers);
+
+        Ok(FlashLoanPlanCommands {
+            borrow_command,
+            swap_commands,
+            profit_coin,
+        })
+    }
+}
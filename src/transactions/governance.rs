@@ -1,11 +1,23 @@
-use anyhow::{Context, Result};
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow};
 use sui_sdk::SuiClient;
+use sui_sdk::rpc_types::SuiParsedData;
+use sui_sdk::types::TypeTag;
 use sui_sdk::types::base_types::ObjectID;
+use sui_sdk::types::dynamic_field::DynamicFieldName;
+use sui_sdk::types::id::ID;
 use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_sdk::types::transaction::{Command, ProgrammableMoveCall};
 
-use crate::utils::config::{DEEP_SCALAR, DeepBookConfig, FLOAT_SCALAR};
-use crate::utils::{get_object_arg, parse_type_input};
+use crate::errors::ProposalError;
+use crate::types::{GovernanceState, Proposal, TradeParams};
+use crate::utils::config::{
+    DEEP_SCALAR, DeepBookConfig, FLOAT_SCALAR, MAX_MAKER_STABLE, MAX_MAKER_VOLATILE,
+    MAX_TAKER_STABLE, MAX_TAKER_VOLATILE, MIN_MAKER_STABLE, MIN_MAKER_VOLATILE, MIN_TAKER_STABLE,
+    MIN_TAKER_VOLATILE,
+};
+use crate::utils::{get_object_arg, get_object_json, json_field, json_u64, parse_type_input};
 
 use super::balance_manager::BalanceManagerContract;
 
@@ -113,7 +125,8 @@ impl GovernanceContract {
         Ok(())
     }
 
-    /// Submit a governance proposal
+    /// Submit a governance proposal. Fees are checked against the pool's
+    /// stable/volatile bounds before the Move call is built.
     pub async fn submit_proposal(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
@@ -123,6 +136,9 @@ impl GovernanceContract {
         maker_fee: f64,
         stake_required: f64,
     ) -> Result<()> {
+        let governance = self.get_governance(pool_key).await?;
+        validate_proposal(&governance, pool_key, taker_fee, maker_fee, stake_required)?;
+
         let pool = self.config.get_pool(pool_key);
         let balance_manager = self.config.get_balance_manager(balance_manager_key);
         let trade_proof_arg = self
@@ -168,13 +184,84 @@ impl GovernanceContract {
         Ok(())
     }
 
-    /// Vote on a governance proposal
+    /// Vote on a governance proposal. The proposal must be open in the pool
+    /// and the manager must hold active stake for the current epoch.
     pub async fn vote(
         &self,
         ptb: &mut ProgrammableTransactionBuilder,
         pool_key: &str,
         balance_manager_key: &str,
-        proposal_id: &str,
+        proposal_id: ObjectID,
+    ) -> Result<()> {
+        let governance = self.get_governance(pool_key).await?;
+        if !governance.proposals.iter().any(|p| p.id == proposal_id) {
+            return Err(anyhow!(
+                "Proposal {} is not open in pool {}",
+                proposal_id,
+                pool_key
+            ));
+        }
+
+        let active_stake = self
+            .get_active_stake(pool_key, balance_manager_key, governance.epoch)
+            .await?;
+        if active_stake <= 0.0 {
+            return Err(anyhow!(
+                "Balance manager {} has no active stake in pool {} for epoch {}",
+                balance_manager_key,
+                pool_key,
+                governance.epoch
+            ));
+        }
+
+        self.build_vote(ptb, pool_key, balance_manager_key, proposal_id)
+            .await
+    }
+
+    /// Vote for the proposal with the highest `score`, e.g.
+    /// `|p| -p.taker_fee` to back the cheapest taker fee.
+    /// Returns the chosen proposal id.
+    pub async fn vote_for_best_proposal<F>(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        balance_manager_key: &str,
+        score: F,
+    ) -> Result<ObjectID>
+    where
+        F: Fn(&Proposal) -> f64,
+    {
+        let governance = self.get_governance(pool_key).await?;
+        let best = governance
+            .proposals
+            .iter()
+            .max_by(|a, b| score(a).total_cmp(&score(b)))
+            .ok_or_else(|| anyhow!("No open proposals in pool {}", pool_key))?;
+
+        let active_stake = self
+            .get_active_stake(pool_key, balance_manager_key, governance.epoch)
+            .await?;
+        if active_stake <= 0.0 {
+            return Err(anyhow!(
+                "Balance manager {} has no active stake in pool {} for epoch {}",
+                balance_manager_key,
+                pool_key,
+                governance.epoch
+            ));
+        }
+
+        self.build_vote(ptb, pool_key, balance_manager_key, best.id)
+            .await?;
+
+        Ok(best.id)
+    }
+
+    async fn build_vote(
+        &self,
+        ptb: &mut ProgrammableTransactionBuilder,
+        pool_key: &str,
+        balance_manager_key: &str,
+        proposal_id: ObjectID,
     ) -> Result<()> {
         let pool = self.config.get_pool(pool_key);
         let balance_manager = self.config.get_balance_manager(balance_manager_key);
@@ -188,7 +275,7 @@ impl GovernanceContract {
 
         let pool_object = get_object_arg(&self.client, &pool.address).await?;
         let manager_object = get_object_arg(&self.client, &balance_manager.address).await?;
-        let proposal_id_arg = ptb.pure(proposal_id)?;
+        let proposal_id_arg = ptb.pure(ID::new(proposal_id))?;
 
         let package_id = ObjectID::from_hex_literal(&self.config.deepbook_package_id)?;
         let pool_object_arg = ptb.input(pool_object)?;
@@ -212,4 +299,190 @@ impl GovernanceContract {
 
         Ok(())
     }
+
+    /// Read the `PoolInner` behind a pool's `Versioned` wrapper
+    pub(crate) async fn get_pool_inner_json(&self, pool_key: &str) -> Result<serde_json::Value> {
+        let pool = self.config.get_pool(pool_key);
+        let pool_json = get_object_json(&self.client, ObjectID::from_hex_literal(&pool.address)?)
+            .await
+            .context("Failed to read pool object")?;
+
+        let versioned_id = json_field(&pool_json, &["inner", "id", "id"])?
+            .as_str()
+            .ok_or_else(|| anyhow!("Invalid versioned id for pool {}", pool_key))?;
+
+        let dynamic_fields = self
+            .client
+            .read_api()
+            .get_dynamic_fields(ObjectID::from_hex_literal(versioned_id)?, None, None)
+            .await
+            .context("Failed to list versioned pool fields")?;
+
+        let inner_field = dynamic_fields
+            .data
+            .first()
+            .ok_or_else(|| anyhow!("No inner state found for pool {}", pool_key))?;
+
+        let inner_json = get_object_json(&self.client, inner_field.object_id)
+            .await
+            .context("Failed to read pool inner state")?;
+
+        Ok(json_field(&inner_json, &["value"])?.clone())
+    }
+
+    /// Get the governance state of a pool: epoch, quorum, voting power,
+    /// proposals and current/next trade params
+    pub async fn get_governance(&self, pool_key: &str) -> Result<GovernanceState> {
+        let inner = self.get_pool_inner_json(pool_key).await?;
+        let governance = json_field(&inner, &["state", "governance"])?;
+
+        let deep_scalar = self.config.get_coin("DEEP").scalar as f64;
+        let trade_params = |value: &serde_json::Value| -> Result<TradeParams> {
+            Ok(TradeParams {
+                taker_fee: json_u64(json_field(value, &["taker_fee"])?)? as f64 / FLOAT_SCALAR,
+                maker_fee: json_u64(json_field(value, &["maker_fee"])?)? as f64 / FLOAT_SCALAR,
+                stake_required: json_u64(json_field(value, &["stake_required"])?)? as f64
+                    / deep_scalar,
+            })
+        };
+
+        let entries = json_field(governance, &["proposals", "contents"])?
+            .as_array()
+            .ok_or_else(|| anyhow!("Invalid proposal list for pool {}", pool_key))?;
+
+        let mut proposals = vec![];
+        for entry in entries {
+            let id = json_field(entry, &["key"])?
+                .as_str()
+                .ok_or_else(|| anyhow!("Invalid proposal id for pool {}", pool_key))?;
+            let value = json_field(entry, &["value"])?;
+            let params = trade_params(value)?;
+
+            proposals.push(Proposal {
+                id: ObjectID::from_hex_literal(id)?,
+                taker_fee: params.taker_fee,
+                maker_fee: params.maker_fee,
+                stake_required: params.stake_required,
+                votes: json_u64(json_field(value, &["votes"])?)? as f64 / deep_scalar,
+            });
+        }
+
+        Ok(GovernanceState {
+            epoch: json_u64(json_field(governance, &["epoch"])?)?,
+            whitelisted: json_field(governance, &["whitelisted"])?
+                .as_bool()
+                .ok_or_else(|| anyhow!("Invalid whitelisted flag for pool {}", pool_key))?,
+            stable: json_field(governance, &["stable"])?
+                .as_bool()
+                .ok_or_else(|| anyhow!("Invalid stable flag for pool {}", pool_key))?,
+            quorum: json_u64(json_field(governance, &["quorum"])?)? as f64 / deep_scalar,
+            voting_power: json_u64(json_field(governance, &["voting_power"])?)? as f64
+                / deep_scalar,
+            proposals,
+            trade_params: trade_params(json_field(governance, &["trade_params"])?)?,
+            next_trade_params: trade_params(json_field(governance, &["next_trade_params"])?)?,
+        })
+    }
+
+    /// Stake that counts for voting in `epoch`. Stake added in an earlier
+    /// epoch becomes active once the account rolls over.
+    pub async fn get_active_stake(
+        &self,
+        pool_key: &str,
+        balance_manager_key: &str,
+        epoch: u64,
+    ) -> Result<f64> {
+        let inner = self.get_pool_inner_json(pool_key).await?;
+        let accounts_id = json_field(&inner, &["state", "accounts", "id", "id"])?
+            .as_str()
+            .ok_or_else(|| anyhow!("Invalid accounts table id for pool {}", pool_key))?;
+        let manager = self.config.get_balance_manager(balance_manager_key);
+
+        let response = self
+            .client
+            .read_api()
+            .get_dynamic_field_object(
+                ObjectID::from_hex_literal(accounts_id)?,
+                DynamicFieldName {
+                    type_: TypeTag::from_str("0x2::object::ID")?,
+                    value: serde_json::Value::String(manager.address.to_string()),
+                },
+            )
+            .await
+            .context("Failed to read account from pool")?;
+
+        // No account in this pool means nothing was ever staked
+        let Some(SuiParsedData::MoveObject(object)) = response.data.and_then(|d| d.content) else {
+            return Ok(0.0);
+        };
+        let account_json = object.fields.to_json_value();
+        let account = json_field(&account_json, &["value"])?;
+
+        let account_epoch = json_u64(json_field(account, &["epoch"])?)?;
+        let mut active_stake = json_u64(json_field(account, &["active_stake"])?)?;
+        if account_epoch < epoch {
+            active_stake += json_u64(json_field(account, &["inactive_stake"])?)?;
+        }
+
+        Ok(active_stake as f64 / self.config.get_coin("DEEP").scalar as f64)
+    }
+}
+
+/// Check proposal parameters against the bounds enforced by
+/// `governance::add_proposal` for the pool's current flags
+pub fn validate_proposal(
+    governance: &GovernanceState,
+    pool_key: &str,
+    taker_fee: f64,
+    maker_fee: f64,
+    stake_required: f64,
+) -> Result<(), ProposalError> {
+    if governance.whitelisted {
+        return Err(ProposalError::WhitelistedPool(pool_key.to_string()));
+    }
+
+    let (kind, taker_range, maker_range) = if governance.stable {
+        (
+            "stable",
+            (MIN_TAKER_STABLE, MAX_TAKER_STABLE),
+            (MIN_MAKER_STABLE, MAX_MAKER_STABLE),
+        )
+    } else {
+        (
+            "volatile",
+            (MIN_TAKER_VOLATILE, MAX_TAKER_VOLATILE),
+            (MIN_MAKER_VOLATILE, MAX_MAKER_VOLATILE),
+        )
+    };
+
+    let taker_fee_input = (taker_fee * FLOAT_SCALAR) as u64;
+    let maker_fee_input = (maker_fee * FLOAT_SCALAR) as u64;
+
+    if taker_fee < 0.0 || taker_fee_input < taker_range.0 || taker_fee_input > taker_range.1 {
+        return Err(ProposalError::TakerFeeOutOfRange {
+            fee: taker_fee,
+            min: taker_range.0 as f64 / FLOAT_SCALAR,
+            max: taker_range.1 as f64 / FLOAT_SCALAR,
+            kind,
+        });
+    }
+    if maker_fee < 0.0 || maker_fee_input < maker_range.0 || maker_fee_input > maker_range.1 {
+        return Err(ProposalError::MakerFeeOutOfRange {
+            fee: maker_fee,
+            min: maker_range.0 as f64 / FLOAT_SCALAR,
+            max: maker_range.1 as f64 / FLOAT_SCALAR,
+            kind,
+        });
+    }
+    if maker_fee_input > taker_fee_input {
+        return Err(ProposalError::MakerAboveTaker {
+            maker_fee,
+            taker_fee,
+        });
+    }
+    if stake_required < 0.0 {
+        return Err(ProposalError::InvalidStakeRequired(stake_required));
+    }
+
+    Ok(())
 }
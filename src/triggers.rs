@@ -0,0 +1,614 @@
+//! Stop-loss, take-profit and trailing-stop orders emulated off chain.
+//! DeepBook has no conditional orders, so a [`TriggerService`] watches prices
+//! and sends a market or IOC order when a trigger hits.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sui_sdk::SuiClient;
+use sui_sdk::rpc_types::{
+    SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions,
+};
+use sui_sdk::types::base_types::SuiAddress;
+use sui_sdk::types::digests::TransactionDigest;
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::signature::GenericSignature;
+use sui_sdk::types::transaction::TransactionData;
+
+use crate::client::DeepBookClient;
+use crate::orders::ClientOrderIdGenerator;
+use crate::types::{
+    BookParams, ClientOrderId, OrderType, PlaceLimitOrderParams, PlaceMarketOrderParams,
+};
+use crate::utils::config::{GAS_BUDGET, MAX_TRIGGER_FAILURES};
+use crate::utils::offline::{TransactionSigner, decode_transaction_data, execute_signed};
+
+/// When a trigger fires, relative to the side it trades
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TriggerCondition {
+    /// Sells fire at or below `price`, buys at or above
+    StopLoss { price: f64 },
+    /// Sells fire at or above `price`, buys at or below
+    TakeProfit { price: f64 },
+    /// Sells fire `distance` below the highest price seen, buys `distance`
+    /// above the lowest
+    TrailingStop {
+        distance: f64,
+        #[serde(default)]
+        extreme: Option<f64>,
+    },
+}
+
+/// Price a trigger watches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceSource {
+    #[default]
+    Mid,
+    /// Best bid for sells and best ask for buys, the side the order would hit
+    Touch,
+}
+
+/// Order sent when a trigger fires
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TriggerExecution {
+    Market,
+    /// Immediate-or-cancel limit order `slippage` (a fraction) worse than
+    /// the trigger price
+    Ioc {
+        slippage: f64,
+    },
+}
+
+/// What to watch and what to send, as registered with [`TriggerService::add`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerSpec {
+    pub pool_key: String,
+    pub manager_key: String,
+    /// Side of the order sent on firing
+    pub is_bid: bool,
+    /// Base quantity of the order sent on firing, reduced by partial fills
+    pub quantity: f64,
+    pub condition: TriggerCondition,
+    #[serde(default)]
+    pub source: PriceSource,
+    pub execution: TriggerExecution,
+    pub pay_with_deep: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trigger {
+    pub id: u64,
+    pub spec: TriggerSpec,
+    /// Client order id of the order sent on firing
+    pub client_order_id: ClientOrderId,
+    /// Error from the last attempt to fire, retried on the next poll
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Attempts in a row that failed or filled nothing. The trigger stops
+    /// firing at [`MAX_TRIGGER_FAILURES`] and waits to be cancelled.
+    #[serde(default)]
+    pub failures: u32,
+    /// Order sent on firing whose outcome has not been read yet. It is
+    /// never sent again, only read back by digest.
+    #[serde(default)]
+    pub submitted: Option<SubmittedOrder>,
+}
+
+/// A transaction sent for a trigger, recorded before submission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmittedOrder {
+    pub digest: String,
+    /// Reference price that hit
+    pub price: f64,
+}
+
+impl Trigger {
+    /// The watched price, if the book has one
+    pub fn reference_price(&self, prices: &MarketPrices) -> Option<f64> {
+        match self.spec.source {
+            PriceSource::Mid => Some(prices.mid),
+            PriceSource::Touch if self.spec.is_bid => prices.best_ask,
+            PriceSource::Touch => prices.best_bid,
+        }
+    }
+
+    /// Update trailing state with `price` and report whether the trigger hits
+    pub fn observe(&mut self, price: f64) -> bool {
+        let is_sell = !self.spec.is_bid;
+        match &mut self.spec.condition {
+            TriggerCondition::StopLoss { price: stop } => {
+                if is_sell {
+                    price <= *stop
+                } else {
+                    price >= *stop
+                }
+            }
+            TriggerCondition::TakeProfit { price: target } => {
+                if is_sell {
+                    price >= *target
+                } else {
+                    price <= *target
+                }
+            }
+            TriggerCondition::TrailingStop { distance, extreme } => {
+                let best = match *extreme {
+                    Some(seen) if is_sell => seen.max(price),
+                    Some(seen) => seen.min(price),
+                    None => price,
+                };
+                *extreme = Some(best);
+                if is_sell {
+                    price <= best - *distance
+                } else {
+                    price >= best + *distance
+                }
+            }
+        }
+    }
+}
+
+/// Prices of one pool at a poll, in human units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketPrices {
+    pub mid: f64,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+}
+
+/// A trigger whose order executed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiredTrigger {
+    /// The trigger as it fired
+    pub trigger: Trigger,
+    /// Reference price that hit
+    pub price: f64,
+    pub digest: String,
+    /// Base quantity the order filled
+    pub filled: f64,
+    /// Whether the trigger stays registered for the unfilled quantity
+    pub pending: bool,
+}
+
+/// Triggers persisted to a JSON file so they survive restarts
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TriggerService {
+    next_id: u64,
+    triggers: Vec<Trigger>,
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    ids: ClientOrderIdGenerator,
+}
+
+impl TriggerService {
+    /// Load the triggers stored at `path`, or start with none
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if !path.exists() {
+            return Ok(Self {
+                next_id: 1,
+                triggers: vec![],
+                path,
+                ids: ClientOrderIdGenerator::new(),
+            });
+        }
+
+        let data = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read trigger file {}", path.display()))?;
+        let mut service: Self = serde_json::from_str(&data)
+            .with_context(|| format!("Invalid trigger file {}", path.display()))?;
+        if let Some(last) = service.triggers.iter().map(|t| t.client_order_id).max() {
+            service.ids = ClientOrderIdGenerator::starting_after(last);
+        }
+        service.path = path;
+        Ok(service)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        // Write then rename so a crash never leaves a truncated file
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+
+    pub fn triggers(&self) -> &[Trigger] {
+        &self.triggers
+    }
+
+    /// Register a trigger and save. Returns its id. The quantity is checked
+    /// against the pool's `book` so the order can't abort on firing.
+    pub fn add(&mut self, spec: TriggerSpec, book: &BookParams) -> Result<u64> {
+        anyhow::ensure!(
+            spec.quantity > 0.0,
+            "Trigger quantity must be positive, got {}",
+            spec.quantity
+        );
+        let lots = spec.quantity / book.lot_size;
+        anyhow::ensure!(
+            (lots - lots.round()).abs() < LOT_EPSILON,
+            "Trigger quantity {} is not a multiple of lot size {}",
+            spec.quantity,
+            book.lot_size
+        );
+        anyhow::ensure!(
+            lots.round() >= (book.min_size / book.lot_size).round(),
+            "Trigger quantity {} is below min size {}",
+            spec.quantity,
+            book.min_size
+        );
+        let id = self.next_id;
+        self.next_id += 1;
+        self.triggers.push(Trigger {
+            id,
+            spec,
+            client_order_id: self.ids.next_id(),
+            last_error: None,
+            failures: 0,
+            submitted: None,
+        });
+        self.save()?;
+        Ok(id)
+    }
+
+    /// Remove a trigger and save
+    pub fn cancel(&mut self, id: u64) -> Result<Option<Trigger>> {
+        let Some(index) = self.triggers.iter().position(|t| t.id == id) else {
+            return Ok(None);
+        };
+        let trigger = self.triggers.remove(index);
+        self.save()?;
+        Ok(Some(trigger))
+    }
+
+    /// Feed `prices` to the triggers of `pool_key` and return the ids that
+    /// hit. Trailing state is kept in memory until the next save.
+    pub fn evaluate(&mut self, pool_key: &str, prices: &MarketPrices) -> Vec<u64> {
+        self.triggers
+            .iter_mut()
+            .filter(|trigger| trigger.spec.pool_key == pool_key)
+            .filter_map(|trigger| {
+                let price = trigger.reference_price(prices)?;
+                trigger.observe(price).then_some(trigger.id)
+            })
+            .collect()
+    }
+
+    /// Read prices for every pool with triggers, send the orders of those
+    /// that hit and save. A trigger is removed once its order fills, and
+    /// kept for the rest after a partial fill while that is at least the
+    /// pool's min size. Failures and empty fills keep it for the next poll
+    /// with `last_error` set, up to [`MAX_TRIGGER_FAILURES`] in a row. Once
+    /// a transaction is submitted its digest is saved, and if its outcome
+    /// can't be read it is read again on later polls instead of resent.
+    /// A pool whose prices can't be read sets `last_error` on its triggers
+    /// and is skipped.
+    pub async fn poll(
+        &mut self,
+        client: &SuiClient,
+        deep_book: &DeepBookClient,
+        signer: &impl TransactionSigner,
+        sender: SuiAddress,
+    ) -> Result<Vec<FiredTrigger>> {
+        let mut fired = vec![];
+        let submitted: Vec<(u64, String)> = self
+            .triggers
+            .iter()
+            .filter_map(|t| Some((t.id, t.submitted.as_ref()?.digest.clone())))
+            .collect();
+        for (id, digest) in submitted {
+            let response = read_transaction(client, &digest).await;
+            self.settle(deep_book, id, response, &mut fired).await?;
+        }
+
+        let pools: BTreeSet<String> = self
+            .triggers
+            .iter()
+            .map(|trigger| trigger.spec.pool_key.clone())
+            .collect();
+        for pool_key in pools {
+            let prices = match self.market_prices(deep_book, &pool_key).await {
+                Ok(prices) => prices,
+                Err(error) => {
+                    self.pool_error(&pool_key, &error);
+                    continue;
+                }
+            };
+            let hits = self.evaluate(&pool_key, &prices);
+            if hits.is_empty() {
+                continue;
+            }
+            let book = match deep_book.get_pool_book_params(&pool_key).await {
+                Ok((tick_size, lot_size, min_size)) => BookParams {
+                    tick_size,
+                    lot_size,
+                    min_size,
+                },
+                Err(error) => {
+                    self.pool_error(&pool_key, &error);
+                    continue;
+                }
+            };
+            for id in hits {
+                let Some(index) = self.triggers.iter().position(|t| t.id == id) else {
+                    continue;
+                };
+                let trigger = &self.triggers[index];
+                if trigger.failures >= MAX_TRIGGER_FAILURES || trigger.submitted.is_some() {
+                    continue;
+                }
+                let Some(price) = trigger.reference_price(&prices) else {
+                    continue;
+                };
+
+                // Nothing reaches the chain before this succeeds, so errors
+                // are retried
+                let signed = sign_order(deep_book, signer, sender, trigger, price, &book).await;
+                let trigger = &mut self.triggers[index];
+                let (tx_data, signature) = match signed {
+                    Ok(signed) => signed,
+                    Err(error) => {
+                        trigger.failures += 1;
+                        trigger.last_error = Some(format!("{:#}", error));
+                        continue;
+                    }
+                };
+                let digest = tx_data.digest().to_string();
+                trigger.submitted = Some(SubmittedOrder { digest, price });
+                // Save before submitting so a restart can't fire it twice
+                self.save()?;
+
+                let response = execute_signed(client, tx_data, vec![signature]).await;
+                self.settle(deep_book, id, response, &mut fired).await?;
+            }
+        }
+        self.save()?;
+        Ok(fired)
+    }
+
+    /// Apply the outcome of trigger `id`'s submitted order, read as
+    /// `response`. The order stays submitted while the outcome is unknown.
+    async fn settle(
+        &mut self,
+        deep_book: &DeepBookClient,
+        id: u64,
+        response: Result<SuiTransactionBlockResponse>,
+        fired: &mut Vec<FiredTrigger>,
+    ) -> Result<()> {
+        let Some(index) = self.triggers.iter().position(|t| t.id == id) else {
+            return Ok(());
+        };
+        let outcome = match response {
+            Ok(response) => order_outcome(deep_book, &self.triggers[index], &response).await,
+            Err(error) => Err(error),
+        };
+        let trigger = &mut self.triggers[index];
+        let Some(submitted) = trigger.submitted.clone() else {
+            return Ok(());
+        };
+        let (filled, lot_size, min_size) = match outcome {
+            Ok(Outcome::Filled {
+                filled,
+                lot_size,
+                min_size,
+            }) => (filled, lot_size, min_size),
+            // A failed transaction traded nothing, so the order may be sent again
+            Ok(Outcome::Failed(error)) => {
+                trigger.submitted = None;
+                trigger.failures += 1;
+                trigger.last_error = Some(error);
+                self.save()?;
+                return Ok(());
+            }
+            Err(error) => {
+                trigger.last_error = Some(format!(
+                    "Outcome of {} unknown: {:#}",
+                    submitted.digest, error
+                ));
+                return Ok(());
+            }
+        };
+
+        trigger.submitted = None;
+        if filled <= 0.0 {
+            trigger.failures += 1;
+            trigger.last_error = Some("Order filled nothing".to_string());
+            self.save()?;
+            return Ok(());
+        }
+
+        let fired_trigger = trigger.clone();
+        let remaining_lots = ((trigger.spec.quantity - filled) / lot_size).round();
+        let pending = remaining_lots >= (min_size / lot_size).round();
+        if pending {
+            trigger.spec.quantity = remaining_lots * lot_size;
+            trigger.failures = 0;
+            trigger.last_error = None;
+        } else {
+            self.triggers.remove(index);
+        }
+        fired.push(FiredTrigger {
+            trigger: fired_trigger,
+            price: submitted.price,
+            digest: submitted.digest,
+            filled,
+            pending,
+        });
+        self.save()
+    }
+
+    fn pool_error(&mut self, pool_key: &str, error: &anyhow::Error) {
+        for trigger in &mut self.triggers {
+            if trigger.spec.pool_key == pool_key {
+                trigger.last_error = Some(format!("{:#}", error));
+            }
+        }
+    }
+
+    async fn market_prices(
+        &self,
+        deep_book: &DeepBookClient,
+        pool_key: &str,
+    ) -> Result<MarketPrices> {
+        let mid = deep_book.get_scaled_mid_price(pool_key).await?;
+        let needs_touch = self
+            .triggers
+            .iter()
+            .any(|t| t.spec.pool_key == pool_key && t.spec.source == PriceSource::Touch);
+        if !needs_touch {
+            return Ok(MarketPrices {
+                mid,
+                best_bid: None,
+                best_ask: None,
+            });
+        }
+
+        let (bids, asks) = deep_book
+            .get_scaled_level2_ticks_from_mid(pool_key, 1)
+            .await?;
+        Ok(MarketPrices {
+            mid,
+            best_bid: bids.first().map(|(price, _)| *price),
+            best_ask: asks.first().map(|(price, _)| *price),
+        })
+    }
+}
+
+// Slack for quantities already on a lot
+const LOT_EPSILON: f64 = 1e-6;
+
+/// Limit price for an IOC order `slippage` worse than `price`, rounded to a
+/// tick in the permissive direction
+pub fn ioc_limit_price(is_bid: bool, price: f64, slippage: f64, tick_size: f64) -> f64 {
+    // Slack so prices already on a tick don't round past it
+    const EPSILON: f64 = 1e-6;
+    let ticks = if is_bid {
+        (price * (1.0 + slippage) / tick_size - EPSILON).ceil()
+    } else {
+        (price * (1.0 - slippage) / tick_size + EPSILON).floor()
+    };
+    ticks.max(1.0) * tick_size
+}
+
+/// What a submitted trigger order did
+enum Outcome {
+    Filled {
+        filled: f64,
+        lot_size: f64,
+        min_size: f64,
+    },
+    /// The transaction failed on chain
+    Failed(String),
+}
+
+async fn order_outcome(
+    deep_book: &DeepBookClient,
+    trigger: &Trigger,
+    response: &SuiTransactionBlockResponse,
+) -> Result<Outcome> {
+    let effects = response
+        .effects
+        .as_ref()
+        .context("Transaction returned no effects")?;
+    if let SuiExecutionStatus::Failure { error } = effects.status() {
+        return Ok(Outcome::Failed(format!(
+            "Transaction {} failed: {}",
+            response.digest, error
+        )));
+    }
+
+    // A market or IOC order succeeds however little it fills
+    let filled: f64 = deep_book
+        .order_fills(&trigger.spec.manager_key, response)?
+        .iter()
+        .filter(|fill| fill.client_order_id == trigger.client_order_id)
+        .map(|fill| fill.base_quantity)
+        .sum();
+    let (_, lot_size, min_size) = deep_book
+        .get_pool_book_params(&trigger.spec.pool_key)
+        .await?;
+    Ok(Outcome::Filled {
+        filled,
+        lot_size,
+        min_size,
+    })
+}
+
+async fn read_transaction(client: &SuiClient, digest: &str) -> Result<SuiTransactionBlockResponse> {
+    let digest = TransactionDigest::from_str(digest)?;
+    client
+        .read_api()
+        .get_transaction_with_options(
+            digest,
+            SuiTransactionBlockResponseOptions::new()
+                .with_effects()
+                .with_events(),
+        )
+        .await
+        .with_context(|| format!("Failed to read transaction {}", digest))
+}
+
+/// Build and sign the order `trigger` sends at `price`
+async fn sign_order(
+    deep_book: &DeepBookClient,
+    signer: &impl TransactionSigner,
+    sender: SuiAddress,
+    trigger: &Trigger,
+    price: f64,
+    book: &BookParams,
+) -> Result<(TransactionData, GenericSignature)> {
+    let spec = &trigger.spec;
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    match spec.execution {
+        TriggerExecution::Market => {
+            let params = PlaceMarketOrderParams {
+                pool_key: spec.pool_key.clone(),
+                balance_manager_key: spec.manager_key.clone(),
+                client_order_id: trigger.client_order_id,
+                quantity: spec.quantity,
+                is_bid: spec.is_bid,
+                self_matching_option: None,
+                pay_with_deep: Some(spec.pay_with_deep),
+            };
+            deep_book
+                .deep_book
+                .place_market_order(&mut ptb, &params)
+                .await?;
+        }
+        TriggerExecution::Ioc { slippage } => {
+            let params = PlaceLimitOrderParams {
+                pool_key: spec.pool_key.clone(),
+                balance_manager_key: spec.manager_key.clone(),
+                client_order_id: trigger.client_order_id,
+                price: ioc_limit_price(spec.is_bid, price, slippage, book.tick_size),
+                quantity: spec.quantity,
+                is_bid: spec.is_bid,
+                expiration: None,
+                order_type: Some(OrderType::ImmediateOrCancel),
+                self_matching_option: None,
+                pay_with_deep: Some(spec.pay_with_deep),
+            };
+            deep_book
+                .deep_book
+                .place_limit_order(&mut ptb, &params)
+                .await?;
+        }
+    }
+
+    let unsigned = deep_book
+        .build_transaction_bytes(sender, None, GAS_BUDGET as u64, ptb)
+        .await?;
+    let tx_data = decode_transaction_data(&unsigned.tx_bytes)?;
+    let signature = signer.sign(&tx_data)?;
+    Ok((tx_data, signature))
+}
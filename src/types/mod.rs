@@ -1,12 +1,77 @@
-use serde::Deserialize;
-use sui_sdk::types::{collection_types::VecSet, id::ID};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+use std::time::Duration;
+use sui_sdk::types::{
+    base_types::{ObjectID, SuiAddress},
+    collection_types::VecSet,
+    id::ID,
+};
+
+use crate::errors::TimeInForceError;
+use crate::utils::config::MAX_TIMESTAMP;
 
 #[derive(Debug, Clone)]
 pub struct BalanceManager {
-    pub address: &'static str,
-    pub trade_cap: Option<&'static str>,
-    pub deposit_cap: Option<&'static str>,
-    pub withdraw_cap: Option<&'static str>,
+    pub address: String,
+    pub trade_cap: Option<String>,
+    pub deposit_cap: Option<String>,
+    pub withdraw_cap: Option<String>,
+}
+
+impl BalanceManager {
+    /// A manager used by its owner, without any capabilities
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            trade_cap: None,
+            deposit_cap: None,
+            withdraw_cap: None,
+        }
+    }
+
+    /// A manager used by a delegate through `trade_cap`
+    pub fn delegated(address: &str, trade_cap: &str) -> Self {
+        Self {
+            trade_cap: Some(trade_cap.to_string()),
+            ..Self::new(address)
+        }
+    }
+
+    /// Whether the sender trades through a TradeCap rather than as owner
+    pub fn is_delegated(&self) -> bool {
+        self.trade_cap.is_some()
+    }
+}
+
+/// Capabilities a balance manager owner can mint for delegates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapKind {
+    Trade,
+    Deposit,
+    Withdraw,
+}
+
+impl CapKind {
+    /// Name of the Move struct in `balance_manager`
+    pub fn struct_name(&self) -> &'static str {
+        match self {
+            CapKind::Trade => "TradeCap",
+            CapKind::Deposit => "DepositCap",
+            CapKind::Withdraw => "WithdrawCap",
+        }
+    }
+}
+
+/// A capability allow-listed on a balance manager
+#[derive(Debug, Clone)]
+pub struct ManagerCap {
+    pub id: ObjectID,
+    /// `None` if the cap object no longer exists
+    pub kind: Option<CapKind>,
+    pub owner: Option<SuiAddress>,
 }
 
 #[derive(Debug, Clone)]
@@ -18,9 +83,9 @@ pub struct Coin {
 
 #[derive(Debug, Clone)]
 pub struct Pool {
-    pub address: &'static str,
-    pub base_coin: &'static str,
-    pub quote_coin: &'static str,
+    pub address: String,
+    pub base_coin: String,
+    pub quote_coin: String,
 }
 
 #[derive(Debug, Clone)]
@@ -47,11 +112,39 @@ pub enum SelfMatchingOptions {
     CancelMaker,
 }
 
+/// Caller-chosen order id, stored on chain as a `u64`
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct ClientOrderId(pub u64);
+
+impl fmt::Display for ClientOrderId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u64> for ClientOrderId {
+    fn from(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+/// Only decimal `u64` values parse, so ids never silently become 0
+impl FromStr for ClientOrderId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PlaceLimitOrderParams {
     pub pool_key: String,
     pub balance_manager_key: String,
-    pub client_order_id: String,
+    pub client_order_id: ClientOrderId,
     pub price: f64,
     pub quantity: f64,
     pub is_bid: bool,
@@ -61,11 +154,99 @@ pub struct PlaceLimitOrderParams {
     pub pay_with_deep: Option<bool>,
 }
 
+impl PlaceLimitOrderParams {
+    /// Set `order_type` and `expiration` from `time_in_force` at chain time
+    /// `now_ms`
+    pub fn with_time_in_force(
+        mut self,
+        time_in_force: TimeInForce,
+        now_ms: u64,
+    ) -> Result<Self, TimeInForceError> {
+        let (order_type, expiration) = time_in_force.resolve(now_ms)?;
+        self.order_type = Some(order_type);
+        self.expiration = Some(expiration);
+        Ok(self)
+    }
+}
+
+/// How long a limit order may rest on the book
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeInForce {
+    /// Good till cancelled
+    Gtc,
+    /// Good for a duration from chain time
+    Gtt(Duration),
+    /// Good till a chain timestamp in ms
+    Gtd(u64),
+    /// Immediate or cancel
+    Ioc,
+    /// Fill or kill
+    Fok,
+    /// Good till cancelled, rejected if it would take
+    PostOnly,
+}
+
+impl TimeInForce {
+    /// Order type and expiration timestamp for an order placed at chain time
+    /// `now_ms`. Orders that would already be expired are refused.
+    pub fn resolve(self, now_ms: u64) -> Result<(OrderType, u64), TimeInForceError> {
+        let expire_timestamp = match self {
+            TimeInForce::Gtt(duration) => {
+                now_ms.saturating_add(u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+            }
+            TimeInForce::Gtd(timestamp) => timestamp,
+            _ => MAX_TIMESTAMP,
+        };
+        if expire_timestamp <= now_ms {
+            return Err(TimeInForceError::Expired {
+                expire_timestamp,
+                now_ms,
+            });
+        }
+
+        let order_type = match self {
+            TimeInForce::Ioc => OrderType::ImmediateOrCancel,
+            TimeInForce::Fok => OrderType::FillOrKill,
+            TimeInForce::PostOnly => OrderType::PostOnly,
+            _ => OrderType::NoRestriction,
+        };
+        Ok((order_type, expire_timestamp.min(MAX_TIMESTAMP)))
+    }
+}
+
+/// How ladder prices are spaced from the start to the end price
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LadderDistribution {
+    /// Equal price differences between levels
+    Arithmetic,
+    /// Equal price ratios between levels
+    Geometric,
+}
+
+/// Post-only orders spread over `levels` prices from `start_price` to
+/// `end_price` inclusive
+#[derive(Debug, Clone)]
+pub struct LadderParams {
+    pub pool_key: String,
+    pub balance_manager_key: String,
+    pub is_bid: bool,
+    pub start_price: f64,
+    pub end_price: f64,
+    pub levels: usize,
+    /// Base quantity split evenly across levels
+    pub total_size: f64,
+    pub distribution: LadderDistribution,
+    /// Level `i` gets client order id `first_client_order_id + i`
+    pub first_client_order_id: ClientOrderId,
+    pub expiration: Option<u64>,
+    pub pay_with_deep: Option<bool>,
+}
+
 #[derive(Debug, Clone)]
 pub struct PlaceMarketOrderParams {
     pub pool_key: String,
     pub balance_manager_key: String,
-    pub client_order_id: String,
+    pub client_order_id: ClientOrderId,
     pub quantity: f64,
     pub is_bid: bool,
     pub self_matching_option: Option<SelfMatchingOptions>,
@@ -89,6 +270,64 @@ pub struct SwapParams {
     pub min_out: f64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapDirection {
+    BaseForQuote,
+    QuoteForBase,
+}
+
+/// One swap hop of a flash loan plan. The input coin is whatever the
+/// previous leg (or the loan itself) produced.
+#[derive(Debug, Clone)]
+pub struct SwapLeg {
+    pub pool_key: String,
+    pub direction: SwapDirection,
+    pub deep_amount: f64,
+    pub min_out: f64,
+}
+
+/// Where a Move call aborted, from the execution status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveAbortLocation {
+    pub module: String,
+    pub function: Option<String>,
+    pub abort_code: u64,
+    pub command: Option<u64>,
+}
+
+/// Amounts observed for one swap leg during simulation (human units)
+#[derive(Debug, Clone)]
+pub struct LegSimulation {
+    pub pool_key: String,
+    pub direction: SwapDirection,
+    pub base_out: f64,
+    pub quote_out: f64,
+    pub deep_out: f64,
+}
+
+/// Outcome of dry-running a flash loan plan through dev-inspect
+#[derive(Debug, Clone)]
+pub struct FlashLoanSimulation {
+    /// Whether the plan ran to the end, repaying the loan
+    pub repaid: bool,
+    /// Empty unless repaid
+    pub legs: Vec<LegSimulation>,
+    /// Net gain per coin key once the loan is repaid
+    pub profit: HashMap<String, f64>,
+    pub gas_used: i64,
+    pub error: Option<String>,
+    pub abort: Option<MoveAbortLocation>,
+}
+
+impl FlashLoanSimulation {
+    /// Repaid, with no coin lost and some coin gained
+    pub fn is_profitable(&self) -> bool {
+        self.repaid
+            && self.profit.values().all(|gain| *gain >= 0.0)
+            && self.profit.values().any(|gain| *gain > 0.0)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CreatePoolAdminParams {
     pub base_coin_key: String,
@@ -136,15 +375,252 @@ pub struct Balances {
     pub deep: u64,
 }
 
+/// Pool book parameters in human units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookParams {
+    /// Quote per base
+    pub tick_size: f64,
+    pub lot_size: f64,
+    pub min_size: f64,
+}
+
+/// Book parameters before and after a dry-run of admin adjustments
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookParamsDiff {
+    pub old: BookParams,
+    pub new: BookParams,
+}
+
+impl BookParamsDiff {
+    pub fn is_unchanged(&self) -> bool {
+        self.old == self.new
+    }
+}
+
+/// A trade of a balance manager, from an `OrderFilled` event
+#[derive(Debug, Clone)]
+pub struct OrderFill {
+    pub pool_key: String,
+    pub base_coin: String,
+    pub quote_coin: String,
+    /// The manager's order, whether it made or took
+    pub order_id: u128,
+    pub client_order_id: ClientOrderId,
+    /// Whether the manager bought base
+    pub is_buy: bool,
+    pub base_quantity: f64,
+    pub quote_quantity: f64,
+    /// Fee paid by the manager in DEEP, zero if paid in the input coin
+    pub deep_fee: f64,
+    pub timestamp_ms: u64,
+}
+
+/// Base/quote/DEEP amounts in human units
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScaledBalances {
+    pub base: f64,
+    pub quote: f64,
+    pub deep: f64,
+}
+
+/// A balance manager's funds held by one pool
+#[derive(Debug, Clone)]
+pub struct PoolAccountBalances {
+    pub base_coin: String,
+    pub quote_coin: String,
+    /// Reserved by open orders, plus `settled`
+    pub locked: ScaledBalances,
+    /// Filled but not yet settled back into the manager
+    pub settled: ScaledBalances,
+    /// Owed to the pool at next settlement
+    pub owed: ScaledBalances,
+    pub unclaimed_rebates: ScaledBalances,
+}
+
+#[derive(Debug, Clone)]
+pub struct ManagerBalanceReport {
+    /// Coin key -> amount held directly by the manager
+    pub balances: HashMap<String, f64>,
+    /// Pool key -> funds in that pool, only for pools the manager has an account in
+    pub pools: HashMap<String, PoolAccountBalances>,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize)]
 pub struct OrderDeepPrice {
     pub asset_is_base: bool,
     pub deep_per_asset: u64,
 }
 
+/// BCS layout of `order::Order`, as returned by `pool::get_orders`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Order {
+    pub balance_manager_id: ID,
+    pub order_id: u128,
+    pub client_order_id: u64,
+    pub quantity: u64,
+    pub filled_quantity: u64,
+    pub fee_is_deep: bool,
+    pub order_deep_price: OrderDeepPrice,
+    pub epoch: u64,
+    pub status: u8,
+    pub expire_timestamp: u64,
+}
+
+/// An open order in human units
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenOrder {
+    pub order_id: u128,
+    pub client_order_id: ClientOrderId,
+    pub is_bid: bool,
+    pub price: f64,
+    /// Quantity still resting on the book, in the base coin
+    pub quantity: f64,
+    pub filled_quantity: f64,
+    pub expire_timestamp: u64,
+}
+
+/// An order that rested on the book, from an `OrderPlaced` event
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderPlacement {
+    pub pool_key: String,
+    pub order_id: u128,
+    pub client_order_id: ClientOrderId,
+    pub is_bid: bool,
+    pub price: f64,
+    /// Quantity left on the book after any immediate fills, in the base coin
+    pub quantity: f64,
+    pub expire_timestamp: u64,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize)]
 pub struct ScaledDeepPrice {
     pub asset_is_base: bool,
     pub deep_per_base: Option<f64>,
     pub deep_per_quote: Option<f64>,
 }
+
+/// BCS layout of a `Coin<T>` returned from a Move call
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoveCoin {
+    pub id: ID,
+    pub value: u64,
+}
+
+/// Fee and stake parameters, scaled to human units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradeParams {
+    pub taker_fee: f64,
+    pub maker_fee: f64,
+    pub stake_required: f64,
+}
+
+/// A governance proposal. `id` is the balance manager that submitted it.
+#[derive(Debug, Clone)]
+pub struct Proposal {
+    pub id: ObjectID,
+    pub taker_fee: f64,
+    pub maker_fee: f64,
+    pub stake_required: f64,
+    pub votes: f64,
+}
+
+/// Governance state of a pool as stored on chain
+#[derive(Debug, Clone)]
+pub struct GovernanceState {
+    pub epoch: u64,
+    pub whitelisted: bool,
+    pub stable: bool,
+    pub quorum: f64,
+    pub voting_power: f64,
+    pub proposals: Vec<Proposal>,
+    pub trade_params: TradeParams,
+    pub next_trade_params: TradeParams,
+}
+
+/// One PTB command, with known objects and coins named
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandDescription {
+    pub index: usize,
+    /// `MoveCall`, `TransferObjects`, `SplitCoins`, ...
+    pub kind: String,
+    /// `module::function`, prefixed with the package outside DeepBook
+    pub target: Option<String>,
+    pub type_arguments: Vec<String>,
+    pub arguments: Vec<String>,
+    pub summary: String,
+}
+
+/// Readable view of unsigned `TransactionData`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionDescription {
+    pub sender: SuiAddress,
+    pub gas_owner: SuiAddress,
+    pub gas_payment: Vec<ObjectID>,
+    pub gas_budget: u64,
+    pub gas_price: u64,
+    pub commands: Vec<CommandDescription>,
+}
+
+impl std::fmt::Display for TransactionDescription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "sender {}", self.sender)?;
+        if self.gas_owner != self.sender {
+            writeln!(f, "gas owner {}", self.gas_owner)?;
+        }
+        write!(
+            f,
+            "gas budget {} at price {}",
+            self.gas_budget, self.gas_price
+        )?;
+        for command in &self.commands {
+            write!(f, "\n#{} {}", command.index, command.summary)?;
+        }
+        Ok(())
+    }
+}
+
+/// Transaction for an external signer: base64 BCS `TransactionData` and
+/// what it does
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    pub tx_bytes: String,
+    pub description: TransactionDescription,
+}
+
+/// A DeepBook call argument scaled back to human units
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplainedParam {
+    pub name: String,
+    pub value: serde_json::Value,
+    /// Coin key for prices and quantities
+    pub unit: Option<String>,
+}
+
+impl ExplainedParam {
+    pub fn text(&self) -> String {
+        let value = match &self.value {
+            serde_json::Value::String(text) => text.clone(),
+            // `10` rather than JSON's `10.0`
+            serde_json::Value::Number(number) if number.is_f64() => {
+                number.as_f64().unwrap_or_default().to_string()
+            }
+            other => other.to_string(),
+        };
+        match &self.unit {
+            Some(unit) => format!("{} {}", value, unit),
+            None => value,
+        }
+    }
+}
+
+/// A PTB command as a reviewer would read it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplainedCommand {
+    pub index: usize,
+    /// `module::function`, for Move calls
+    pub call: Option<String>,
+    pub pool: Option<String>,
+    /// Named arguments, for recognised DeepBook calls
+    pub params: Vec<ExplainedParam>,
+    pub summary: String,
+}
@@ -1,10 +1,11 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
 use crate::transactions::balance_manager::BalanceManagerContract;
 use crate::types::{BalanceManager, Coin, Pool};
-use sui_sdk::types::base_types::SuiAddress;
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
 
 use crate::utils::constants::{
     MAINNET_PACKAGE_IDS, TESTNET_PACKAGE_IDS, get_mainnet_coins, get_mainnet_pools,
@@ -17,12 +18,28 @@ pub const FLOAT_SCALAR: f64 = 1_000_000_000.0;
 pub const MAX_TIMESTAMP: u64 = 1_844_674_407_370_955_161;
 pub const GAS_BUDGET: f64 = 0.5 * 500_000_000.0; // Adjust based on benchmarking
 pub const DEEP_SCALAR: f64 = 1_000_000.0;
+pub const POOL_CREATION_FEE: f64 = 500.0; // DEEP, charged by create_permissionless_pool
+pub const MAX_EVENT_PAGES: usize = 20; // Event pages read per call that follows a cursor
+pub const MAX_TRIGGER_FAILURES: u32 = 3; // Failed fires before a trigger stops retrying
+
+// Governance fee bounds, in FLOAT_SCALAR units (mirrors deepbook::constants)
+pub const MIN_TAKER_STABLE: u64 = 10_000; // 0.001%
+pub const MAX_TAKER_STABLE: u64 = 100_000; // 0.01%
+pub const MIN_MAKER_STABLE: u64 = 0;
+pub const MAX_MAKER_STABLE: u64 = 50_000; // 0.005%
+pub const MIN_TAKER_VOLATILE: u64 = 100_000; // 0.01%
+pub const MAX_TAKER_VOLATILE: u64 = 1_000_000; // 0.1%
+pub const MIN_MAKER_VOLATILE: u64 = 0;
+pub const MAX_MAKER_VOLATILE: u64 = 500_000; // 0.05%
 
 #[derive(Debug, Clone)]
 pub struct DeepBookConfig {
     coins: HashMap<String, Coin>,
-    pools: HashMap<String, Pool>,
-    balance_managers: HashMap<String, BalanceManager>,
+    // Shared like `balance_managers`, so pools created at runtime can be registered
+    pools: Arc<RwLock<HashMap<String, Pool>>>,
+    // Shared by every contract holding a clone of this config, so managers
+    // registered at runtime are visible everywhere
+    balance_managers: Arc<RwLock<HashMap<String, BalanceManager>>>,
     pub sender_address: SuiAddress,
 
     pub deepbook_package_id: String,
@@ -95,8 +112,8 @@ impl DeepBookConfig {
 
         Self {
             coins,
-            pools,
-            balance_managers,
+            pools: Arc::new(RwLock::new(pools)),
+            balance_managers: Arc::new(RwLock::new(balance_managers)),
             sender_address: sender_address,
             deepbook_package_id: package_ids.deepbook_package_id.to_owned(),
             registry_id: package_ids.registry_id.to_owned(),
@@ -112,15 +129,94 @@ impl DeepBookConfig {
             .expect(&format!("Coin not found for key: {}", key))
     }
 
-    pub fn get_pool(&self, key: &str) -> &Pool {
+    pub fn get_pool(&self, key: &str) -> Pool {
+        self.try_get_pool(key)
+            .expect(&format!("Pool not found for key: {}", key))
+    }
+
+    pub fn try_get_pool(&self, key: &str) -> Option<Pool> {
         self.pools
+            .read()
+            .expect("Pool registry lock poisoned")
             .get(key)
-            .expect(&format!("Pool not found for key: {}", key))
+            .cloned()
+    }
+
+    /// Add or replace a pool under `key`
+    pub fn register_pool(&self, key: &str, pool: Pool) {
+        self.pools
+            .write()
+            .expect("Pool registry lock poisoned")
+            .insert(key.to_string(), pool);
+    }
+
+    pub fn coin_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.coins.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    pub fn pool_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .pools
+            .read()
+            .expect("Pool registry lock poisoned")
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Key of the configured pool at `address`, if any
+    pub fn pool_key_by_address(&self, address: &ObjectID) -> Option<String> {
+        self.pools
+            .read()
+            .expect("Pool registry lock poisoned")
+            .iter()
+            .find(|(_, pool)| ObjectID::from_hex_literal(&pool.address).ok() == Some(*address))
+            .map(|(key, _)| key.clone())
+    }
+
+    pub fn get_balance_manager(&self, key: &str) -> BalanceManager {
+        self.try_get_balance_manager(key)
+            .expect(&format!("Balance manager with key {} not found.", key))
     }
 
-    pub fn get_balance_manager(&self, key: &str) -> &BalanceManager {
+    pub fn try_get_balance_manager(&self, key: &str) -> Option<BalanceManager> {
         self.balance_managers
+            .read()
+            .expect("Balance manager registry lock poisoned")
             .get(key)
-            .expect(&format!("Balance manager with key {} not found.", key))
+            .cloned()
+    }
+
+    /// Add or replace a balance manager under `key`
+    pub fn register_balance_manager(&self, key: &str, manager: BalanceManager) {
+        self.balance_managers
+            .write()
+            .expect("Balance manager registry lock poisoned")
+            .insert(key.to_string(), manager);
+    }
+
+    /// Key of the registered balance manager at `address`, if any
+    pub fn balance_manager_key_by_address(&self, address: &ObjectID) -> Option<String> {
+        self.balance_managers
+            .read()
+            .expect("Balance manager registry lock poisoned")
+            .iter()
+            .find(|(_, manager)| {
+                ObjectID::from_hex_literal(&manager.address).ok() == Some(*address)
+            })
+            .map(|(key, _)| key.clone())
+    }
+
+    pub fn balance_manager_keys(&self) -> Vec<String> {
+        self.balance_managers
+            .read()
+            .expect("Balance manager registry lock poisoned")
+            .keys()
+            .cloned()
+            .collect()
     }
 }
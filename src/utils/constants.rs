@@ -240,49 +240,55 @@ pub fn get_devnet_pools() -> HashMap<&'static str, Pool> {
         (
             "DEEP_SUI", // whitelisted pool
             Pool {
-                address: "0xfec71b6b2a00bfdb1e41acc43bbcd10c3f4d246973d39cb8bacee0366f79b5d9",
-                base_coin: "DEEP",
-                quote_coin: "SUI",
+                address: "0xfec71b6b2a00bfdb1e41acc43bbcd10c3f4d246973d39cb8bacee0366f79b5d9"
+                    .to_string(),
+                base_coin: "DEEP".to_string(),
+                quote_coin: "SUI".to_string(),
             },
         ),
         (
             "SUI_DBUSDC", // whitelisted pool
             Pool {
-                address: "0x8b5052f0b83d692c7ee157f8a44d0a3b272f23257130a8b286c49e7fc830ef54",
-                base_coin: "SUI",
-                quote_coin: "DBUSDC",
+                address: "0x8b5052f0b83d692c7ee157f8a44d0a3b272f23257130a8b286c49e7fc830ef54"
+                    .to_string(),
+                base_coin: "SUI".to_string(),
+                quote_coin: "DBUSDC".to_string(),
             },
         ),
         (
             "DEEP_DBUSDC",
             Pool {
-                address: "0x3571bfb4cad9fac64d489a5f1b6b91c66dfc9f21022d65b374a02903c1f2d474",
-                base_coin: "DEEP",
-                quote_coin: "DBUSDC",
+                address: "0x3571bfb4cad9fac64d489a5f1b6b91c66dfc9f21022d65b374a02903c1f2d474"
+                    .to_string(),
+                base_coin: "DEEP".to_string(),
+                quote_coin: "DBUSDC".to_string(),
             },
         ),
         (
             "DBUSDT_DBUSDC",
             Pool {
-                address: "0x4241fb8f4b8965f8df5a8a80d9c42292d35b7b24d7f3831bb555ffe869337416",
-                base_coin: "DBUSDT",
-                quote_coin: "DBUSDC",
+                address: "0x4241fb8f4b8965f8df5a8a80d9c42292d35b7b24d7f3831bb555ffe869337416"
+                    .to_string(),
+                base_coin: "DBUSDT".to_string(),
+                quote_coin: "DBUSDC".to_string(),
             },
         ),
         (
             "DBUSDT_SUI", // added price deep point
             Pool {
-                address: "0x9e60db786278bbd4912ff6fba077c99cc4f4b984b5c61391e9d9197fb605cbb5",
-                base_coin: "DBUSDT",
-                quote_coin: "SUI",
+                address: "0x9e60db786278bbd4912ff6fba077c99cc4f4b984b5c61391e9d9197fb605cbb5"
+                    .to_string(),
+                base_coin: "DBUSDT".to_string(),
+                quote_coin: "SUI".to_string(),
             },
         ),
         (
             "PI_SUI",
             Pool {
-                address: "0x4b4dbcbc7ae876e77accc279860a65775bacf73932963be1c3b433cd6e859c24",
-                base_coin: "PI",
-                quote_coin: "SUI",
+                address: "0x4b4dbcbc7ae876e77accc279860a65775bacf73932963be1c3b433cd6e859c24"
+                    .to_string(),
+                base_coin: "PI".to_string(),
+                quote_coin: "SUI".to_string(),
             },
         ),
     ])
@@ -293,33 +299,37 @@ pub fn get_testnet_pools() -> HashMap<&'static str, Pool> {
         (
             "DEEP_SUI",
             Pool {
-                address: "0x0d1b1746d220bd5ebac5231c7685480a16f1c707a46306095a4c67dc7ce4dcae",
-                base_coin: "DEEP",
-                quote_coin: "SUI",
+                address: "0x0d1b1746d220bd5ebac5231c7685480a16f1c707a46306095a4c67dc7ce4dcae"
+                    .to_string(),
+                base_coin: "DEEP".to_string(),
+                quote_coin: "SUI".to_string(),
             },
         ),
         (
             "SUI_DBUSDC",
             Pool {
-                address: "0x520c89c6c78c566eed0ebf24f854a8c22d8fdd06a6f16ad01f108dad7f1baaea",
-                base_coin: "SUI",
-                quote_coin: "DBUSDC",
+                address: "0x520c89c6c78c566eed0ebf24f854a8c22d8fdd06a6f16ad01f108dad7f1baaea"
+                    .to_string(),
+                base_coin: "SUI".to_string(),
+                quote_coin: "DBUSDC".to_string(),
             },
         ),
         (
             "DEEP_DBUSDC",
             Pool {
-                address: "0xee4bb0db95dc571b960354713388449f0158317e278ee8cda59ccf3dcd4b5288",
-                base_coin: "DEEP",
-                quote_coin: "DBUSDC",
+                address: "0xee4bb0db95dc571b960354713388449f0158317e278ee8cda59ccf3dcd4b5288"
+                    .to_string(),
+                base_coin: "DEEP".to_string(),
+                quote_coin: "DBUSDC".to_string(),
             },
         ),
         (
             "DBUSDT_DBUSDC",
             Pool {
-                address: "0x69cbb39a3821d681648469ff2a32b4872739d2294d30253ab958f85ace9e0491",
-                base_coin: "DBUSDT",
-                quote_coin: "DBUSDC",
+                address: "0x69cbb39a3821d681648469ff2a32b4872739d2294d30253ab958f85ace9e0491"
+                    .to_string(),
+                base_coin: "DBUSDT".to_string(),
+                quote_coin: "DBUSDC".to_string(),
             },
         ),
     ])
@@ -330,121 +340,136 @@ pub fn get_mainnet_pools() -> HashMap<&'static str, Pool> {
         (
             "DEEP_SUI",
             Pool {
-                address: "0xb663828d6217467c8a1838a03793da896cbe745b150ebd57d82f814ca579fc22",
-                base_coin: "DEEP",
-                quote_coin: "SUI",
+                address: "0xb663828d6217467c8a1838a03793da896cbe745b150ebd57d82f814ca579fc22"
+                    .to_string(),
+                base_coin: "DEEP".to_string(),
+                quote_coin: "SUI".to_string(),
             },
         ),
         (
             "SUI_USDC",
             Pool {
-                address: "0xe05dafb5133bcffb8d59f4e12465dc0e9faeaa05e3e342a08fe135800e3e4407",
-                base_coin: "SUI",
-                quote_coin: "USDC",
+                address: "0xe05dafb5133bcffb8d59f4e12465dc0e9faeaa05e3e342a08fe135800e3e4407"
+                    .to_string(),
+                base_coin: "SUI".to_string(),
+                quote_coin: "USDC".to_string(),
             },
         ),
         (
             "DEEP_USDC",
             Pool {
-                address: "0xf948981b806057580f91622417534f491da5f61aeaf33d0ed8e69fd5691c95ce",
-                base_coin: "DEEP",
-                quote_coin: "USDC",
+                address: "0xf948981b806057580f91622417534f491da5f61aeaf33d0ed8e69fd5691c95ce"
+                    .to_string(),
+                base_coin: "DEEP".to_string(),
+                quote_coin: "USDC".to_string(),
             },
         ),
         (
             "WUSDT_USDC",
             Pool {
-                address: "0x4e2ca3988246e1d50b9bf209abb9c1cbfec65bd95afdacc620a36c67bdb8452f",
-                base_coin: "WUSDT",
-                quote_coin: "USDC",
+                address: "0x4e2ca3988246e1d50b9bf209abb9c1cbfec65bd95afdacc620a36c67bdb8452f"
+                    .to_string(),
+                base_coin: "WUSDT".to_string(),
+                quote_coin: "USDC".to_string(),
             },
         ),
         (
             "WUSDC_USDC",
             Pool {
-                address: "0xa0b9ebefb38c963fd115f52d71fa64501b79d1adcb5270563f92ce0442376545",
-                base_coin: "WUSDC",
-                quote_coin: "USDC",
+                address: "0xa0b9ebefb38c963fd115f52d71fa64501b79d1adcb5270563f92ce0442376545"
+                    .to_string(),
+                base_coin: "WUSDC".to_string(),
+                quote_coin: "USDC".to_string(),
             },
         ),
         (
             "BETH_USDC",
             Pool {
-                address: "0x1109352b9112717bd2a7c3eb9a416fff1ba6951760f5bdd5424cf5e4e5b3e65c",
-                base_coin: "BETH",
-                quote_coin: "USDC",
+                address: "0x1109352b9112717bd2a7c3eb9a416fff1ba6951760f5bdd5424cf5e4e5b3e65c"
+                    .to_string(),
+                base_coin: "BETH".to_string(),
+                quote_coin: "USDC".to_string(),
             },
         ),
         (
             "NS_USDC",
             Pool {
-                address: "0x0c0fdd4008740d81a8a7d4281322aee71a1b62c449eb5b142656753d89ebc060",
-                base_coin: "NS",
-                quote_coin: "USDC",
+                address: "0x0c0fdd4008740d81a8a7d4281322aee71a1b62c449eb5b142656753d89ebc060"
+                    .to_string(),
+                base_coin: "NS".to_string(),
+                quote_coin: "USDC".to_string(),
             },
         ),
         (
             "NS_SUI",
             Pool {
-                address: "0x27c4fdb3b846aa3ae4a65ef5127a309aa3c1f466671471a806d8912a18b253e8",
-                base_coin: "NS",
-                quote_coin: "SUI",
+                address: "0x27c4fdb3b846aa3ae4a65ef5127a309aa3c1f466671471a806d8912a18b253e8"
+                    .to_string(),
+                base_coin: "NS".to_string(),
+                quote_coin: "SUI".to_string(),
             },
         ),
         (
             "TYPUS_SUI",
             Pool {
-                address: "0xe8e56f377ab5a261449b92ac42c8ddaacd5671e9fec2179d7933dd1a91200eec",
-                base_coin: "TYPUS",
-                quote_coin: "SUI",
+                address: "0xe8e56f377ab5a261449b92ac42c8ddaacd5671e9fec2179d7933dd1a91200eec"
+                    .to_string(),
+                base_coin: "TYPUS".to_string(),
+                quote_coin: "SUI".to_string(),
             },
         ),
         (
             "SUI_AUSD",
             Pool {
-                address: "0x183df694ebc852a5f90a959f0f563b82ac9691e42357e9a9fe961d71a1b809c8",
-                base_coin: "SUI",
-                quote_coin: "AUSD",
+                address: "0x183df694ebc852a5f90a959f0f563b82ac9691e42357e9a9fe961d71a1b809c8"
+                    .to_string(),
+                base_coin: "SUI".to_string(),
+                quote_coin: "AUSD".to_string(),
             },
         ),
         (
             "AUSD_USDC",
             Pool {
-                address: "0x5661fc7f88fbeb8cb881150a810758cf13700bb4e1f31274a244581b37c303c3",
-                base_coin: "AUSD",
-                quote_coin: "USDC",
+                address: "0x5661fc7f88fbeb8cb881150a810758cf13700bb4e1f31274a244581b37c303c3"
+                    .to_string(),
+                base_coin: "AUSD".to_string(),
+                quote_coin: "USDC".to_string(),
             },
         ),
         (
             "DRF_SUI",
             Pool {
-                address: "0x126865a0197d6ab44bfd15fd052da6db92fd2eb831ff9663451bbfa1219e2af2",
-                base_coin: "DRF",
-                quote_coin: "SUI",
+                address: "0x126865a0197d6ab44bfd15fd052da6db92fd2eb831ff9663451bbfa1219e2af2"
+                    .to_string(),
+                base_coin: "DRF".to_string(),
+                quote_coin: "SUI".to_string(),
             },
         ),
         (
             "SEND_USDC",
             Pool {
-                address: "0x1fe7b99c28ded39774f37327b509d58e2be7fff94899c06d22b407496a6fa990",
-                base_coin: "SEND",
-                quote_coin: "USDC",
+                address: "0x1fe7b99c28ded39774f37327b509d58e2be7fff94899c06d22b407496a6fa990"
+                    .to_string(),
+                base_coin: "SEND".to_string(),
+                quote_coin: "USDC".to_string(),
             },
         ),
         (
             "WAL_USDC",
             Pool {
-                address: "0x56a1c985c1f1123181d6b881714793689321ba24301b3585eec427436eb1c76d",
-                base_coin: "WAL",
-                quote_coin: "USDC",
+                address: "0x56a1c985c1f1123181d6b881714793689321ba24301b3585eec427436eb1c76d"
+                    .to_string(),
+                base_coin: "WAL".to_string(),
+                quote_coin: "USDC".to_string(),
             },
         ),
         (
             "WAL_SUI",
             Pool {
-                address: "0x81f5339934c83ea19dd6bcc75c52e83509629a5f71d3257428c2ce47cc94d08b",
-                base_coin: "WAL",
-                quote_coin: "SUI",
+                address: "0x81f5339934c83ea19dd6bcc75c52e83509629a5f71d3257428c2ce47cc94d08b"
+                    .to_string(),
+                base_coin: "WAL".to_string(),
+                quote_coin: "SUI".to_string(),
             },
         ),
     ])
@@ -0,0 +1,669 @@
+use anyhow::{Result, anyhow};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use sui_sdk::types::SUI_CLOCK_OBJECT_ID;
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+use sui_sdk::types::transaction::{
+    Argument, CallArg, Command, ObjectArg, ProgrammableMoveCall, ProgrammableTransaction,
+    TransactionData, TransactionDataAPI, TransactionKind,
+};
+use sui_sdk::types::type_input::TypeInput;
+
+use crate::types::{CommandDescription, ExplainedCommand, ExplainedParam};
+use crate::utils::config::{DeepBookConfig, FLOAT_SCALAR};
+use crate::utils::parse_type_input;
+
+/// One description per command, naming known objects (registry, admin cap,
+/// pools, managers, clock) and coins instead of raw ids and type tags.
+/// [`summarize_admin_calls`](crate::transactions::deep_book_admin::summarize_admin_calls)
+/// renders these as lines.
+pub fn describe_commands(
+    config: &DeepBookConfig,
+    pt: &ProgrammableTransaction,
+) -> Vec<CommandDescription> {
+    let package_id = ObjectID::from_hex_literal(&config.deepbook_package_id).ok();
+    let describe_all = |arguments: &[Argument]| -> Vec<String> {
+        arguments
+            .iter()
+            .map(|argument| describe_argument(config, pt, argument))
+            .collect()
+    };
+
+    pt.commands
+        .iter()
+        .enumerate()
+        .map(|(index, command)| match command {
+            Command::MoveCall(call) => {
+                let target = if Some(call.package) == package_id {
+                    format!("{}::{}", call.module, call.function)
+                } else {
+                    format!("{}::{}::{}", call.package, call.module, call.function)
+                };
+                let type_arguments = call
+                    .type_arguments
+                    .iter()
+                    .map(|type_input| {
+                        coin_key_for_type(config, type_input)
+                            .unwrap_or_else(|| format!("{:?}", type_input))
+                    })
+                    .collect::<Vec<_>>();
+                let arguments = describe_all(&call.arguments);
+
+                let summary = if type_arguments.is_empty() {
+                    format!("{}({})", target, arguments.join(", "))
+                } else {
+                    format!(
+                        "{}<{}>({})",
+                        target,
+                        type_arguments.join(", "),
+                        arguments.join(", ")
+                    )
+                };
+                CommandDescription {
+                    index,
+                    kind: "MoveCall".to_string(),
+                    target: Some(target),
+                    type_arguments,
+                    arguments,
+                    summary,
+                }
+            }
+            Command::TransferObjects(objects, recipient) => {
+                let mut arguments = describe_all(objects);
+                let recipient = describe_argument(config, pt, recipient);
+                let summary = format!("transfer [{}] to {}", arguments.join(", "), recipient);
+                arguments.push(recipient);
+                CommandDescription {
+                    index,
+                    kind: "TransferObjects".to_string(),
+                    target: None,
+                    type_arguments: vec![],
+                    arguments,
+                    summary,
+                }
+            }
+            Command::SplitCoins(coin, amounts) => {
+                let coin = describe_argument(config, pt, coin);
+                let amounts = describe_all(amounts);
+                let summary = format!("split {} into [{}]", coin, amounts.join(", "));
+                CommandDescription {
+                    index,
+                    kind: "SplitCoins".to_string(),
+                    target: None,
+                    type_arguments: vec![],
+                    arguments: std::iter::once(coin).chain(amounts).collect(),
+                    summary,
+                }
+            }
+            Command::MergeCoins(coin, sources) => {
+                let coin = describe_argument(config, pt, coin);
+                let sources = describe_all(sources);
+                let summary = format!("merge [{}] into {}", sources.join(", "), coin);
+                CommandDescription {
+                    index,
+                    kind: "MergeCoins".to_string(),
+                    target: None,
+                    type_arguments: vec![],
+                    arguments: std::iter::once(coin).chain(sources).collect(),
+                    summary,
+                }
+            }
+            other => CommandDescription {
+                index,
+                kind: "Other".to_string(),
+                target: None,
+                type_arguments: vec![],
+                arguments: vec![],
+                summary: format!("{:?}", other),
+            },
+        })
+        .collect()
+}
+
+pub(crate) fn describe_argument(
+    config: &DeepBookConfig,
+    pt: &ProgrammableTransaction,
+    argument: &Argument,
+) -> String {
+    match argument {
+        Argument::GasCoin => "gas".to_string(),
+        Argument::Result(i) => format!("result#{}", i),
+        Argument::NestedResult(i, j) => format!("result#{}.{}", i, j),
+        Argument::Input(i) => match pt.inputs.get(*i as usize) {
+            Some(CallArg::Pure(bytes)) => describe_pure(bytes),
+            Some(CallArg::Object(object)) => describe_object(config, object),
+            _ => format!("input#{}", i),
+        },
+    }
+}
+
+/// Best-effort decoding of pure bytes by length; BCS carries no type
+fn describe_pure(bytes: &[u8]) -> String {
+    match bytes.len() {
+        1 if bytes[0] <= 1 => (bytes[0] == 1).to_string(),
+        8 => bcs::from_bytes::<u64>(bytes)
+            .map(|value| value.to_string())
+            .unwrap_or_default(),
+        32 => bcs::from_bytes::<SuiAddress>(bytes)
+            .map(|address| address.to_string())
+            .unwrap_or_default(),
+        _ => format!(
+            "0x{}",
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        ),
+    }
+}
+
+fn describe_object(config: &DeepBookConfig, object: &ObjectArg) -> String {
+    let id = match object {
+        ObjectArg::ImmOrOwnedObject((id, _, _)) => *id,
+        ObjectArg::SharedObject { id, .. } => *id,
+        ObjectArg::Receiving((id, _, _)) => *id,
+    };
+    let is = |address: &str| ObjectID::from_hex_literal(address).ok() == Some(id);
+
+    if id == SUI_CLOCK_OBJECT_ID {
+        "clock".to_string()
+    } else if is(&config.registry_id) {
+        "registry".to_string()
+    } else if config.admin_cap.as_deref().is_some_and(is) {
+        "admin_cap".to_string()
+    } else if let Some(pool_key) = config.pool_key_by_address(&id) {
+        format!("pool {}", pool_key)
+    } else if let Some(manager_key) = config.balance_manager_key_by_address(&id) {
+        format!("manager {}", manager_key)
+    } else {
+        id.to_string()
+    }
+}
+
+/// Configured coin key for a Move type argument
+pub fn coin_key_for_type(config: &DeepBookConfig, type_input: &TypeInput) -> Option<String> {
+    config.coin_keys().into_iter().find(|key| {
+        parse_type_input(config.get_coin(key).coin_type)
+            .ok()
+            .as_ref()
+            == Some(type_input)
+    })
+}
+
+/// Which coin a quantity or `Coin<T>` argument is denominated in
+#[derive(Clone, Copy)]
+enum Denom {
+    Base,
+    Quote,
+    Deep,
+    /// The call's single type argument, e.g. `balance_manager::deposit<T>`
+    Type,
+}
+
+/// How to turn a call argument back into human units
+#[derive(Clone, Copy)]
+enum Param {
+    Object,
+    /// `price * FLOAT_SCALAR * quote_scalar / base_scalar`, like tick sizes
+    Price,
+    Quantity(Denom),
+    /// A coin, with its amount traced through `SplitCoins` when possible
+    Coin(Denom),
+    /// Fee rate in FLOAT_SCALAR units
+    Fee,
+    Bool,
+    OrderType,
+    SelfMatching,
+    U64,
+    U128,
+    Id,
+}
+
+use Denom::{Base, Deep, Quote, Type};
+use Param::*;
+
+/// Argument names and scaling for DeepBook calls built by this crate
+fn layout(module: &str, function: &str) -> Option<&'static [(&'static str, Param)]> {
+    const POOL: (&str, Param) = ("pool", Object);
+    const MANAGER: (&str, Param) = ("balance_manager", Object);
+    const PROOF: (&str, Param) = ("trade_proof", Object);
+    Some(match (module, function) {
+        ("pool", "place_limit_order") => &[
+            POOL,
+            MANAGER,
+            PROOF,
+            ("client_order_id", U64),
+            ("order_type", OrderType),
+            ("self_matching_option", SelfMatching),
+            ("price", Price),
+            ("quantity", Quantity(Base)),
+            ("is_bid", Bool),
+            ("pay_with_deep", Bool),
+            ("expire_timestamp", U64),
+            ("clock", Object),
+        ],
+        ("pool", "place_market_order") => &[
+            POOL,
+            MANAGER,
+            PROOF,
+            ("client_order_id", U64),
+            ("self_matching_option", SelfMatching),
+            ("quantity", Quantity(Base)),
+            ("is_bid", Bool),
+            ("pay_with_deep", Bool),
+            ("clock", Object),
+        ],
+        ("pool", "cancel_order") => &[POOL, MANAGER, PROOF, ("order_id", U128), ("clock", Object)],
+        ("pool", "modify_order") => &[
+            POOL,
+            MANAGER,
+            PROOF,
+            ("order_id", U128),
+            ("new_quantity", Quantity(Base)),
+            ("clock", Object),
+        ],
+        ("pool", "cancel_all_orders") => &[POOL, MANAGER, PROOF, ("clock", Object)],
+        ("pool", "swap_exact_base_for_quote") => &[
+            ("pool", Object),
+            ("base_in", Coin(Base)),
+            ("deep_in", Coin(Deep)),
+            ("min_quote_out", Quantity(Quote)),
+            ("clock", Object),
+        ],
+        ("pool", "swap_exact_quote_for_base") => &[
+            ("pool", Object),
+            ("quote_in", Coin(Quote)),
+            ("deep_in", Coin(Deep)),
+            ("min_base_out", Quantity(Base)),
+            ("clock", Object),
+        ],
+        ("pool", "stake") => &[POOL, MANAGER, PROOF, ("amount", Quantity(Deep))],
+        ("pool", "unstake") => &[POOL, MANAGER, PROOF],
+        ("pool", "submit_proposal") => &[
+            POOL,
+            MANAGER,
+            PROOF,
+            ("taker_fee", Fee),
+            ("maker_fee", Fee),
+            ("stake_required", Quantity(Deep)),
+        ],
+        ("pool", "vote") => &[POOL, MANAGER, PROOF, ("proposal_id", Id)],
+        ("pool", "borrow_flashloan_base") => &[("pool", Object), ("amount", Quantity(Base))],
+        ("pool", "borrow_flashloan_quote") => &[("pool", Object), ("amount", Quantity(Quote))],
+        ("pool", "return_flashloan_base") => &[
+            ("pool", Object),
+            ("coin", Coin(Base)),
+            ("flash_loan", Object),
+        ],
+        ("pool", "return_flashloan_quote") => &[
+            ("pool", Object),
+            ("coin", Coin(Quote)),
+            ("flash_loan", Object),
+        ],
+        ("pool", "adjust_tick_size_admin") => &[
+            ("pool", Object),
+            ("tick_size", Price),
+            ("admin_cap", Object),
+            ("clock", Object),
+        ],
+        ("pool", "adjust_min_lot_size_admin") => &[
+            ("pool", Object),
+            ("lot_size", Quantity(Base)),
+            ("min_size", Quantity(Base)),
+            ("admin_cap", Object),
+            ("clock", Object),
+        ],
+        ("pool", "create_pool_admin") => &[
+            ("registry", Object),
+            ("tick_size", Price),
+            ("lot_size", Quantity(Base)),
+            ("min_size", Quantity(Base)),
+            ("whitelisted", Bool),
+            ("stable", Bool),
+            ("admin_cap", Object),
+        ],
+        ("pool", "create_permissionless_pool") => &[
+            ("registry", Object),
+            ("tick_size", Price),
+            ("lot_size", Quantity(Base)),
+            ("min_size", Quantity(Base)),
+            ("creation_fee", Coin(Deep)),
+        ],
+        ("balance_manager", "deposit") => &[("balance_manager", Object), ("coin", Coin(Type))],
+        ("balance_manager", "withdraw") => {
+            &[("balance_manager", Object), ("amount", Quantity(Type))]
+        }
+        ("balance_manager", "withdraw_all") => &[("balance_manager", Object)],
+        ("balance_manager", "deposit_with_cap") => &[
+            ("balance_manager", Object),
+            ("deposit_cap", Object),
+            ("coin", Coin(Type)),
+        ],
+        ("balance_manager", "withdraw_with_cap") => &[
+            ("balance_manager", Object),
+            ("withdraw_cap", Object),
+            ("amount", Quantity(Type)),
+        ],
+        _ => return None,
+    })
+}
+
+/// Walk `pt` and explain every command. DeepBook calls get named arguments
+/// scaled to human prices and quantities; anything else falls back to
+/// [`describe_commands`].
+pub fn explain_transaction(
+    config: &DeepBookConfig,
+    pt: &ProgrammableTransaction,
+) -> Vec<ExplainedCommand> {
+    let package_id = ObjectID::from_hex_literal(&config.deepbook_package_id).ok();
+
+    pt.commands
+        .iter()
+        .zip(describe_commands(config, pt))
+        .map(|(command, description)| match command {
+            Command::MoveCall(call) if Some(call.package) == package_id => {
+                explain_call(config, pt, call, description.index)
+                    .unwrap_or_else(|| fallback(description))
+            }
+            _ => fallback(description),
+        })
+        .collect()
+}
+
+/// [`explain_transaction`] for finished transaction data
+pub fn explain_transaction_data(
+    config: &DeepBookConfig,
+    tx_data: &TransactionData,
+) -> Result<Vec<ExplainedCommand>> {
+    match tx_data.kind() {
+        TransactionKind::ProgrammableTransaction(pt) => Ok(explain_transaction(config, pt)),
+        _ => Err(anyhow!("Not a programmable transaction")),
+    }
+}
+
+/// One `#index summary` line per command
+pub fn render_explanation(commands: &[ExplainedCommand]) -> String {
+    commands
+        .iter()
+        .map(|command| format!("#{} {}", command.index, command.summary))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn fallback(description: CommandDescription) -> ExplainedCommand {
+    ExplainedCommand {
+        index: description.index,
+        call: description.target,
+        pool: None,
+        params: vec![],
+        summary: description.summary,
+    }
+}
+
+fn explain_call(
+    config: &DeepBookConfig,
+    pt: &ProgrammableTransaction,
+    call: &ProgrammableMoveCall,
+    index: usize,
+) -> Option<ExplainedCommand> {
+    let layout = layout(&call.module, &call.function)?;
+    if layout.len() != call.arguments.len() {
+        return None;
+    }
+
+    let coins: Vec<Option<String>> = call
+        .type_arguments
+        .iter()
+        .map(|type_input| coin_key_for_type(config, type_input))
+        .collect();
+    let coin = |position: usize| coins.get(position).cloned().flatten();
+    let denom_coin = |denom: Denom| match denom {
+        Base | Type => coin(0),
+        Quote => coin(1),
+        Deep => Some("DEEP".to_string()),
+    };
+
+    let pool = call
+        .arguments
+        .first()
+        .and_then(|argument| object_id(pt, argument))
+        .and_then(|id| config.pool_key_by_address(&id))
+        .or_else(|| pool_key_for_coins(config, coin(0)?, coin(1)?));
+
+    let params: Vec<ExplainedParam> = layout
+        .iter()
+        .zip(&call.arguments)
+        .map(|((name, param), argument)| {
+            let raw = || describe_argument(config, pt, argument);
+            let (value, unit) = match *param {
+                Price => match (pure::<u64>(pt, argument), coin(0), coin(1)) {
+                    (Some(price), Some(base), Some(quote)) => {
+                        let base_scalar = config.get_coin(&base).scalar as f64;
+                        let quote_scalar = config.get_coin(&quote).scalar as f64;
+                        (
+                            Value::from(price as f64 * base_scalar / (FLOAT_SCALAR * quote_scalar)),
+                            Some(quote),
+                        )
+                    }
+                    _ => (Value::from(raw()), None),
+                },
+                Quantity(denom) => scaled(config, pure::<u64>(pt, argument), denom_coin(denom))
+                    .unwrap_or_else(|| (Value::from(raw()), None)),
+                Coin(denom) => scaled(config, split_amount(pt, argument), denom_coin(denom))
+                    .unwrap_or_else(|| (Value::from(raw()), denom_coin(denom))),
+                Fee => match pure::<u64>(pt, argument) {
+                    Some(fee) => (Value::from(fee as f64 / FLOAT_SCALAR), None),
+                    None => (Value::from(raw()), None),
+                },
+                Bool => match pure::<bool>(pt, argument) {
+                    Some(flag) => (Value::from(flag), None),
+                    None => (Value::from(raw()), None),
+                },
+                OrderType => (
+                    Value::from(
+                        pure::<u8>(pt, argument)
+                            .and_then(order_type_name)
+                            .map(str::to_string)
+                            .unwrap_or_else(raw),
+                    ),
+                    None,
+                ),
+                SelfMatching => (
+                    Value::from(
+                        pure::<u8>(pt, argument)
+                            .and_then(self_matching_name)
+                            .map(str::to_string)
+                            .unwrap_or_else(raw),
+                    ),
+                    None,
+                ),
+                U64 => match pure::<u64>(pt, argument) {
+                    Some(value) => (Value::from(value), None),
+                    None => (Value::from(raw()), None),
+                },
+                // u128 does not fit a JSON number
+                U128 => (
+                    Value::from(
+                        pure::<u128>(pt, argument)
+                            .map(|value| value.to_string())
+                            .unwrap_or_else(raw),
+                    ),
+                    None,
+                ),
+                Id => (
+                    Value::from(
+                        pure::<ObjectID>(pt, argument)
+                            .map(|id| id.to_string())
+                            .unwrap_or_else(raw),
+                    ),
+                    None,
+                ),
+                Object => (Value::from(raw()), None),
+            };
+            ExplainedParam {
+                name: name.to_string(),
+                value,
+                unit,
+            }
+        })
+        .collect();
+
+    let target = format!("{}::{}", call.module, call.function);
+    let summary = summarize_call(&target, pool.as_deref(), &params);
+    Some(ExplainedCommand {
+        index,
+        call: Some(target),
+        pool,
+        params,
+        summary,
+    })
+}
+
+fn summarize_call(target: &str, pool: Option<&str>, params: &[ExplainedParam]) -> String {
+    let text = |name: &str| {
+        params
+            .iter()
+            .find(|param| param.name == name)
+            .map(ExplainedParam::text)
+            .unwrap_or_default()
+    };
+    let side = || match params.iter().find(|param| param.name == "is_bid") {
+        Some(param) if param.value == Value::Bool(true) => "Buy",
+        _ => "Sell",
+    };
+    let pool = pool.unwrap_or("unknown pool");
+
+    match target {
+        "pool::place_limit_order" => format!(
+            "{} {} at {} on {} ({}, manager {})",
+            side(),
+            text("quantity"),
+            text("price"),
+            pool,
+            text("order_type"),
+            text("balance_manager").trim_start_matches("manager ")
+        ),
+        "pool::place_market_order" => format!(
+            "{} {} at market on {} (manager {})",
+            side(),
+            text("quantity"),
+            pool,
+            text("balance_manager").trim_start_matches("manager ")
+        ),
+        "pool::cancel_order" => format!("Cancel order {} on {}", text("order_id"), pool),
+        "pool::modify_order" => format!(
+            "Reduce order {} to {} on {}",
+            text("order_id"),
+            text("new_quantity"),
+            pool
+        ),
+        "pool::cancel_all_orders" => format!("Cancel all orders on {}", pool),
+        "pool::swap_exact_base_for_quote" => format!(
+            "Swap {} for at least {} on {}",
+            text("base_in"),
+            text("min_quote_out"),
+            pool
+        ),
+        "pool::swap_exact_quote_for_base" => format!(
+            "Swap {} for at least {} on {}",
+            text("quote_in"),
+            text("min_base_out"),
+            pool
+        ),
+        "pool::stake" => format!("Stake {} in {}", text("amount"), pool),
+        "balance_manager::deposit" | "balance_manager::deposit_with_cap" => {
+            format!("Deposit {} into {}", text("coin"), text("balance_manager"))
+        }
+        "balance_manager::withdraw" | "balance_manager::withdraw_with_cap" => format!(
+            "Withdraw {} from {}",
+            text("amount"),
+            text("balance_manager")
+        ),
+        _ => format!(
+            "{} on {}: {}",
+            target,
+            pool,
+            params
+                .iter()
+                .filter(|param| {
+                    !matches!(
+                        param.name.as_str(),
+                        "pool" | "clock" | "trade_proof" | "registry" | "admin_cap"
+                    )
+                })
+                .map(|param| format!("{} {}", param.name, param.text()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn scaled(
+    config: &DeepBookConfig,
+    raw: Option<u64>,
+    coin_key: Option<String>,
+) -> Option<(Value, Option<String>)> {
+    let coin_key = coin_key?;
+    let scalar = config.get_coin(&coin_key).scalar as f64;
+    Some((Value::from(raw? as f64 / scalar), Some(coin_key)))
+}
+
+/// Decode a pure input as `T`
+fn pure<T: DeserializeOwned>(pt: &ProgrammableTransaction, argument: &Argument) -> Option<T> {
+    match argument {
+        Argument::Input(i) => match pt.inputs.get(*i as usize)? {
+            CallArg::Pure(bytes) => bcs::from_bytes(bytes).ok(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn object_id(pt: &ProgrammableTransaction, argument: &Argument) -> Option<ObjectID> {
+    match argument {
+        Argument::Input(i) => match pt.inputs.get(*i as usize)? {
+            CallArg::Object(ObjectArg::ImmOrOwnedObject((id, _, _)))
+            | CallArg::Object(ObjectArg::SharedObject { id, .. })
+            | CallArg::Object(ObjectArg::Receiving((id, _, _))) => Some(*id),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Amount of a coin produced by `SplitCoins` earlier in the transaction
+fn split_amount(pt: &ProgrammableTransaction, argument: &Argument) -> Option<u64> {
+    let (command, position) = match argument {
+        Argument::Result(i) => (*i, 0),
+        Argument::NestedResult(i, j) => (*i, *j),
+        _ => return None,
+    };
+    match pt.commands.get(command as usize)? {
+        Command::SplitCoins(_, amounts) => pure(pt, amounts.get(position as usize)?),
+        _ => None,
+    }
+}
+
+fn pool_key_for_coins(config: &DeepBookConfig, base: String, quote: String) -> Option<String> {
+    config.pool_keys().into_iter().find(|key| {
+        let pool = config.get_pool(key);
+        pool.base_coin == base && pool.quote_coin == quote
+    })
+}
+
+fn order_type_name(value: u8) -> Option<&'static str> {
+    [
+        "no_restriction",
+        "immediate_or_cancel",
+        "fill_or_kill",
+        "post_only",
+    ]
+    .get(value as usize)
+    .copied()
+}
+
+fn self_matching_name(value: u8) -> Option<&'static str> {
+    ["self_matching_allowed", "cancel_taker", "cancel_maker"]
+        .get(value as usize)
+        .copied()
+}
@@ -2,19 +2,24 @@ use anyhow::{Context, Error, Result, anyhow};
 use std::str::FromStr;
 use sui_sdk::{
     SuiClient,
-    rpc_types::{SuiObjectData, SuiObjectDataOptions, SuiObjectResponse},
+    rpc_types::{SuiObjectData, SuiObjectDataOptions, SuiObjectResponse, SuiParsedData},
     types::{
         TypeTag,
         base_types::{ObjectID, ObjectRef, SuiAddress},
+        execution_status::{ExecutionFailureStatus, ExecutionStatus},
         programmable_transaction_builder::ProgrammableTransactionBuilder,
-        transaction::{Argument, CallArg, Command, ObjectArg},
+        transaction::{Argument, CallArg, Command, ObjectArg, ProgrammableMoveCall},
         type_input::TypeInput,
     },
 };
 use sui_types::SUI_CLOCK_OBJECT_ID;
 
+use crate::types::MoveAbortLocation;
+
 pub mod config;
 pub mod constants;
+pub mod explain;
+pub mod offline;
 
 pub fn parse_type_input(type_str: &str) -> Result<TypeInput, anyhow::Error> {
     let type_tag = TypeTag::from_str(type_str)?;
@@ -65,6 +70,70 @@ pub async fn get_object_arg(client: &SuiClient, object_id: &str) -> Result<CallA
     }
 }
 
+/// Fetch an object's Move content as JSON
+pub async fn get_object_json(client: &SuiClient, object_id: ObjectID) -> Result<serde_json::Value> {
+    let object_response: SuiObjectResponse = client
+        .read_api()
+        .get_object_with_options(object_id, SuiObjectDataOptions::new().with_content())
+        .await?;
+
+    let content = object_response
+        .data
+        .and_then(|data| data.content)
+        .ok_or_else(|| anyhow!("Missing content in object response for '{}'", object_id))?;
+
+    match content {
+        SuiParsedData::MoveObject(object) => Ok(object.fields.to_json_value()),
+        SuiParsedData::Package(_) => Err(anyhow!("Object '{}' is a package", object_id)),
+    }
+}
+
+/// Walk a parsed Move object, stepping through the `fields` wrapper that
+/// nested structs carry in JSON
+pub fn json_field<'a>(
+    value: &'a serde_json::Value,
+    path: &[&str],
+) -> Result<&'a serde_json::Value> {
+    let mut current = value;
+    for key in path {
+        let node = current;
+        current = node
+            .get(*key)
+            .or_else(move || node.get("fields").and_then(|fields| fields.get(*key)))
+            .ok_or_else(|| anyhow!("Missing field '{}' in object content", key))?;
+    }
+    Ok(current)
+}
+
+/// Read a Move `u64`, which JSON-RPC renders as a string
+pub fn json_u64(value: &serde_json::Value) -> Result<u64> {
+    match value {
+        serde_json::Value::String(s) => s
+            .parse()
+            .with_context(|| format!("Invalid u64 in object content: {}", s)),
+        serde_json::Value::Number(n) => n
+            .as_u64()
+            .ok_or_else(|| anyhow!("Invalid u64 in object content: {}", n)),
+        _ => Err(anyhow!("Expected u64 in object content, got {}", value)),
+    }
+}
+
+/// u128 from Move JSON, which renders it as a decimal string
+pub fn json_u128(value: &serde_json::Value) -> Result<u128> {
+    value
+        .as_str()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| anyhow!("Expected u128 in object content, got {}", value))
+}
+
+/// Split a DeepBook order id into (is_bid, raw price, sequence number).
+/// Asks have bit 127 set; the price sits in bits 64..127.
+pub fn decode_order_id(order_id: u128) -> (bool, u64, u64) {
+    let is_bid = order_id >> 127 == 0;
+    let price = ((order_id >> 64) as u64) & ((1u64 << 63) - 1);
+    (is_bid, price, order_id as u64)
+}
+
 pub async fn get_clock_object_arg(client: &SuiClient) -> Result<CallArg, anyhow::Error> {
     let object_response: SuiObjectResponse = client
         .read_api()
@@ -97,6 +166,41 @@ pub async fn get_clock_object_arg(client: &SuiClient) -> Result<CallArg, anyhow:
     Ok(res)
 }
 
+/// Current chain time in ms, read from the shared `Clock` that
+/// [`get_clock_object_arg`] passes to calls
+pub async fn get_clock_timestamp_ms(client: &SuiClient) -> Result<u64> {
+    let clock = get_object_json(client, SUI_CLOCK_OBJECT_ID).await?;
+    json_u64(json_field(&clock, &["timestamp_ms"])?)
+}
+
+/// A SUI coin of `owner` holding at least `gas_budget`, paging through
+/// all of the owner's coins until one does
+pub async fn select_gas_coin(
+    client: &SuiClient,
+    owner: SuiAddress,
+    gas_budget: u64,
+) -> Result<ObjectRef> {
+    let mut cursor = None;
+    loop {
+        let page = client
+            .coin_read_api()
+            .get_coins(owner, Some("0x2::sui::SUI".to_string()), cursor, None)
+            .await
+            .with_context(|| format!("Failed to fetch SUI coins of {}", owner))?;
+        if let Some(coin) = page.data.iter().find(|coin| coin.balance >= gas_budget) {
+            return Ok(coin.object_ref());
+        }
+        if !page.has_next_page {
+            return Err(anyhow!(
+                "No SUI coin owned by {} covers a gas budget of {}",
+                owner,
+                gas_budget
+            ));
+        }
+        cursor = page.next_cursor;
+    }
+}
+
 pub async fn merge_and_split_coins(
     client: &SuiClient,
     ptb: &mut ProgrammableTransactionBuilder,
@@ -141,10 +245,7 @@ pub async fn merge_and_split_coins(
         let merge_target = coin_arguments.remove(0);
 
         if !coin_arguments.is_empty() {
-            println!("🔀 Merging {} coins into one...", coin_arguments.len() + 1);
             ptb.command(Command::MergeCoins(merge_target, coin_arguments));
-        } else {
-            println!("ℹ️ Only one coin available, skipping merge.");
         }
 
         // Convert amounts to pure arguments
@@ -165,7 +266,6 @@ pub async fn merge_and_split_coins(
         }
     } else {
         // Use GasCoin for SUI
-        println!("⚙️ Using GasCoin for SUI transfer.");
         let amount_args: Result<Vec<Argument>> = amounts.iter().map(|amt| ptb.pure(*amt)).collect();
         let split_result = ptb.command(Command::SplitCoins(Argument::GasCoin, amount_args?));
         match split_result {
@@ -179,3 +279,45 @@ pub async fn merge_and_split_coins(
         }
     }
 }
+
+/// Send PTB results (coins, caps) to `recipient`
+pub fn transfer_objects(
+    ptb: &mut ProgrammableTransactionBuilder,
+    objects: Vec<Argument>,
+    recipient: SuiAddress,
+) -> Result<()> {
+    let recipient_arg = ptb.pure(recipient)?;
+    ptb.command(Command::TransferObjects(objects, recipient_arg));
+    Ok(())
+}
+
+/// Create a zero-value `Coin<T>` inside the PTB via `0x2::coin::zero`
+pub fn zero_coin(ptb: &mut ProgrammableTransactionBuilder, coin_type: &str) -> Result<Argument> {
+    Ok(
+        ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+            package: ObjectID::from_hex_literal("0x2")?,
+            module: "coin".to_string(),
+            function: "zero".to_string(),
+            type_arguments: vec![parse_type_input(coin_type)?],
+            arguments: vec![],
+        }))),
+    )
+}
+
+/// Where a Move call aborted, if that is why `status` failed
+pub fn move_abort(status: &ExecutionStatus) -> Option<MoveAbortLocation> {
+    let ExecutionStatus::Failure {
+        error: ExecutionFailureStatus::MoveAbort(location, abort_code),
+        command,
+    } = status
+    else {
+        return None;
+    };
+
+    Some(MoveAbortLocation {
+        module: location.module.name().to_string(),
+        function: location.function_name.clone(),
+        abort_code: *abort_code,
+        command: command.map(|command| command as u64),
+    })
+}
@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result, anyhow};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use shared_crypto::intent::Intent;
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
+use sui_sdk::SuiClient;
+use sui_sdk::rpc_types::{
+    SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions,
+};
+use sui_sdk::types::crypto::{EncodeDecodeBase64, PublicKey, Signature, SuiSignature, ToFromBytes};
+use sui_sdk::types::multisig::{MultiSig, MultiSigPublicKey};
+use sui_sdk::types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_sdk::types::signature::GenericSignature;
+use sui_sdk::types::transaction::{
+    Transaction, TransactionData, TransactionDataAPI, TransactionKind,
+};
+
+use crate::types::TransactionDescription;
+use crate::utils::config::DeepBookConfig;
+use crate::utils::explain::describe_commands;
+
+/// Serialize unsigned transaction data as base64 BCS, the format
+/// `sui keytool sign --data` expects
+pub fn encode_transaction_data(tx_data: &TransactionData) -> Result<String> {
+    Ok(STANDARD.encode(bcs::to_bytes(tx_data)?))
+}
+
+pub fn decode_transaction_data(encoded: &str) -> Result<TransactionData> {
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .context("Transaction data is not valid base64")?;
+    bcs::from_bytes(&bytes).context("Transaction data is not valid BCS")
+}
+
+/// Describe the gas and DeepBook calls of a programmable transaction
+pub fn describe_transaction(
+    config: &DeepBookConfig,
+    tx_data: &TransactionData,
+) -> Result<TransactionDescription> {
+    let TransactionKind::ProgrammableTransaction(pt) = tx_data.kind() else {
+        return Err(anyhow!("Not a programmable transaction"));
+    };
+    let gas_data = tx_data.gas_data();
+
+    Ok(TransactionDescription {
+        sender: tx_data.sender(),
+        gas_owner: gas_data.owner,
+        gas_payment: gas_data.payment.iter().map(|(id, _, _)| *id).collect(),
+        gas_budget: gas_data.budget,
+        gas_price: gas_data.price,
+        commands: describe_commands(config, pt),
+    })
+}
+
+/// Describe base64 BCS transaction data, e.g. before signing it
+pub fn decode_transaction(
+    config: &DeepBookConfig,
+    encoded: &str,
+) -> Result<TransactionDescription> {
+    describe_transaction(config, &decode_transaction_data(encoded)?)
+}
+
+/// Multisig public key from base64 `flag || pk` keys, as printed by
+/// `sui keytool list`
+pub fn multisig_public_key(
+    public_keys: &[String],
+    weights: Vec<u8>,
+    threshold: u16,
+) -> Result<MultiSigPublicKey> {
+    let public_keys = public_keys
+        .iter()
+        .map(|key| {
+            PublicKey::decode_base64(key).map_err(|e| anyhow!("Invalid public key {}: {}", key, e))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    MultiSigPublicKey::new(public_keys, weights, threshold)
+        .map_err(|e| anyhow!("Invalid multisig configuration: {}", e))
+}
+
+/// Combine base64 `flag || sig || pk` partial signatures into one multisig.
+/// Together they must reach the key's threshold.
+pub fn combine_signatures(
+    multisig_pk: &MultiSigPublicKey,
+    signatures: &[String],
+) -> Result<GenericSignature> {
+    let signatures = signatures
+        .iter()
+        .map(|signature| {
+            let bytes = STANDARD
+                .decode(signature.trim())
+                .context("Signature is not valid base64")?;
+            Signature::from_bytes(&bytes).map_err(|e| anyhow!("Invalid signature: {}", e))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // The same signer may be pasted twice; count each key once
+    let mut signers = HashSet::new();
+    let signatures: Vec<Signature> = signatures
+        .into_iter()
+        .filter(|signature| signers.insert(signature.public_key_bytes().to_vec()))
+        .collect();
+
+    // `MultiSig::combine` accepts any subset; catch a short quorum before
+    // the validators do
+    let weight: u16 = signatures
+        .iter()
+        .map(|signature| {
+            multisig_pk
+                .pubkeys()
+                .iter()
+                .find(|(public_key, _)| public_key.as_ref() == signature.public_key_bytes())
+                .map(|(_, weight)| *weight as u16)
+                .ok_or_else(|| anyhow!("Signature from a key outside the multisig"))
+        })
+        .sum::<Result<u16>>()?;
+    if weight < *multisig_pk.threshold() {
+        return Err(anyhow!(
+            "Signatures carry weight {} but the multisig threshold is {}",
+            weight,
+            multisig_pk.threshold()
+        ));
+    }
+
+    let multisig = MultiSig::combine(signatures, multisig_pk.clone())
+        .map_err(|e| anyhow!("Failed to combine signatures: {}", e))?;
+
+    Ok(GenericSignature::MultiSig(multisig))
+}
+
+/// Execute transaction data with signatures gathered elsewhere
+pub async fn execute_signed(
+    client: &SuiClient,
+    tx_data: TransactionData,
+    signatures: Vec<GenericSignature>,
+) -> Result<SuiTransactionBlockResponse> {
+    client
+        .quorum_driver_api()
+        .execute_transaction_block(
+            Transaction::from_generic_sig_data(tx_data, signatures),
+            SuiTransactionBlockResponseOptions::full_content(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await
+        .context("Failed to execute signed transaction")
+}
+
+/// Signs transactions that library code submits on its own, e.g. a local
+/// keystore or a remote signer
+pub trait TransactionSigner {
+    fn sign(&self, tx_data: &TransactionData) -> Result<GenericSignature>;
+}
+
+impl TransactionSigner for FileBasedKeystore {
+    fn sign(&self, tx_data: &TransactionData) -> Result<GenericSignature> {
+        let signature = self.sign_secure(&tx_data.sender(), tx_data, Intent::sui_transaction())?;
+        Ok(GenericSignature::Signature(signature))
+    }
+}
+
+/// Sign with `signer` and execute, failing unless the transaction succeeds
+pub async fn sign_and_execute(
+    client: &SuiClient,
+    signer: &impl TransactionSigner,
+    tx_data: TransactionData,
+) -> Result<SuiTransactionBlockResponse> {
+    let signature = signer.sign(&tx_data)?;
+    let response = execute_signed(client, tx_data, vec![signature]).await?;
+
+    let effects = response
+        .effects
+        .as_ref()
+        .context("Transaction returned no effects")?;
+    if let SuiExecutionStatus::Failure { error } = effects.status() {
+        return Err(anyhow!("Transaction {} failed: {}", response.digest, error));
+    }
+    Ok(response)
+}
@@ -1,3 +1,27 @@
+mod test_helper;
+
+use anyhow::Result;
+use base64::Engine;
+use deepbookv3::errors::BookParamsError;
+use deepbookv3::transactions::deep_book_admin::{
+    summarize_admin_calls, validate_lot_size, validate_tick_size,
+};
+use deepbookv3::types::BookParams;
+use deepbookv3::utils::config::DeepBookConfig;
+use deepbookv3::utils::offline::{
+    combine_signatures, decode_transaction, decode_transaction_data, describe_transaction,
+    encode_transaction_data, multisig_public_key,
+};
+use serial_test::serial;
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_sdk::types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
+use sui_sdk::types::crypto::{
+    EncodeDecodeBase64, Signature, SuiKeyPair, ToFromBytes, get_key_pair,
+};
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::{CallArg, ObjectArg, TransactionData, TransactionDataAPI};
+use test_helper::setup_client;
+
 // mod test_helper;
 
 // use anyhow::Result;
@@ -178,3 +202,160 @@
 //     println!("✅ Set treasury address test passed!");
 //     Ok(())
 // }
+
+// Dev-inspect only; nothing is executed
+#[tokio::test]
+#[serial]
+async fn test_preview_book_params() -> Result<()> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+    let (tick_size, lot_size, min_size) = deep_book_client.get_pool_book_params("DEEP_SUI").await?;
+    let diff = deep_book_client
+        .preview_book_params(
+            "DEEP_SUI",
+            BookParams {
+                tick_size,
+                lot_size,
+                min_size: min_size * 10.0,
+            },
+        )
+        .await?;
+
+    assert_eq!(diff.old.min_size, min_size);
+    assert_eq!(diff.new.tick_size, tick_size);
+    assert_eq!(diff.new.lot_size, lot_size);
+    assert!((diff.new.min_size - min_size * 10.0).abs() < 1e-9);
+    Ok(())
+}
+
+#[test]
+fn test_validate_book_params() {
+    assert!(validate_tick_size(1_000).is_ok());
+    assert_eq!(
+        validate_tick_size(0),
+        Err(BookParamsError::InvalidTickSize(0))
+    );
+    assert_eq!(
+        validate_tick_size(1_500),
+        Err(BookParamsError::InvalidTickSize(1_500))
+    );
+
+    assert!(validate_lot_size(1_000, 10_000).is_ok());
+    assert_eq!(
+        validate_lot_size(2_000, 10_000),
+        Err(BookParamsError::InvalidLotSize(2_000))
+    );
+    assert_eq!(
+        validate_lot_size(10_000, 1_000),
+        Err(BookParamsError::InvalidMinSize {
+            min_size: 1_000,
+            lot_size: 10_000
+        })
+    );
+}
+
+fn offline_tx_data(sender: SuiAddress) -> (DeepBookConfig, TransactionData) {
+    let config = DeepBookConfig::new("testnet", sender, None, None, None, None);
+    let pool = config.get_pool("SUI_DBUSDC");
+
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let pool_input = ptb
+        .input(CallArg::Object(ObjectArg::SharedObject {
+            id: ObjectID::from_hex_literal(&pool.address).unwrap(),
+            initial_shared_version: SequenceNumber::from_u64(1),
+            mutable: true,
+        }))
+        .unwrap();
+    let tick_size = ptb.pure(1_000u64).unwrap();
+    ptb.programmable_move_call(
+        ObjectID::from_hex_literal(&config.deepbook_package_id).unwrap(),
+        "pool".parse().unwrap(),
+        "adjust_tick_size_admin".parse().unwrap(),
+        vec![],
+        vec![pool_input, tick_size],
+    );
+
+    let gas = (ObjectID::ZERO, SequenceNumber::new(), ObjectDigest::MIN);
+    let tx_data = TransactionData::new_programmable(sender, vec![gas], ptb.finish(), 1_000, 1);
+    (config, tx_data)
+}
+
+#[test]
+fn test_offline_transaction_roundtrip_and_summary() {
+    let (config, tx_data) = offline_tx_data(SuiAddress::ZERO);
+
+    let encoded = encode_transaction_data(&tx_data).unwrap();
+    let decoded = decode_transaction_data(&encoded).unwrap();
+    assert_eq!(decoded, tx_data);
+
+    let sui_sdk::types::transaction::TransactionKind::ProgrammableTransaction(pt) = decoded.kind()
+    else {
+        panic!("expected a programmable transaction");
+    };
+    assert_eq!(
+        summarize_admin_calls(&config, pt),
+        vec!["#0 pool::adjust_tick_size_admin(pool SUI_DBUSDC, 1000)".to_string()]
+    );
+}
+
+#[test]
+fn test_decode_transaction_description() {
+    let (config, tx_data) = offline_tx_data(SuiAddress::ZERO);
+    let encoded = encode_transaction_data(&tx_data).unwrap();
+
+    let description = decode_transaction(&config, &encoded).unwrap();
+    assert_eq!(
+        description,
+        describe_transaction(&config, &tx_data).unwrap()
+    );
+    assert_eq!(description.sender, SuiAddress::ZERO);
+    assert_eq!(description.gas_payment, vec![ObjectID::ZERO]);
+    assert_eq!(description.gas_budget, 1_000);
+
+    let command = &description.commands[0];
+    assert_eq!(command.kind, "MoveCall");
+    assert_eq!(
+        command.target.as_deref(),
+        Some("pool::adjust_tick_size_admin")
+    );
+    assert_eq!(command.arguments, vec!["pool SUI_DBUSDC", "1000"]);
+    assert!(
+        description
+            .to_string()
+            .ends_with("#0 pool::adjust_tick_size_admin(pool SUI_DBUSDC, 1000)")
+    );
+
+    assert!(decode_transaction(&config, "not base64!").is_err());
+}
+
+#[test]
+fn test_combine_multisig_signatures() {
+    let keys: Vec<SuiKeyPair> = (0..3)
+        .map(|_| SuiKeyPair::Ed25519(get_key_pair().1))
+        .collect();
+    let public_keys: Vec<String> = keys
+        .iter()
+        .map(|key| key.public().encode_base64())
+        .collect();
+    let multisig_pk = multisig_public_key(&public_keys, vec![1, 1, 1], 2).unwrap();
+
+    let (_, tx_data) = offline_tx_data(SuiAddress::from(&multisig_pk));
+    let intent_message = IntentMessage::new(Intent::sui_transaction(), tx_data);
+    let signatures: Vec<String> = keys[..2]
+        .iter()
+        .map(|key| {
+            let signature = Signature::new_secure(&intent_message, key);
+            base64_encode(signature.as_bytes())
+        })
+        .collect();
+
+    assert!(combine_signatures(&multisig_pk, &signatures).is_ok());
+    assert!(combine_signatures(&multisig_pk, &signatures[..1]).is_err());
+
+    // One signer twice does not reach a 2-of-3 threshold
+    let repeated = vec![signatures[0].clone(), signatures[0].clone()];
+    assert!(combine_signatures(&multisig_pk, &repeated).is_err());
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
@@ -1,8 +1,11 @@
 mod test_helper;
 
 use anyhow::Result;
+use deepbookv3::errors::{LadderError, TimeInForceError};
+use deepbookv3::transactions::deep_book::ladder_orders;
 use deepbookv3::types::{
-    OrderType, PlaceLimitOrderParams, PlaceMarketOrderParams, SelfMatchingOptions, SwapParams,
+    BookParams, ClientOrderId, LadderDistribution, LadderParams, OrderType, PlaceLimitOrderParams,
+    PlaceMarketOrderParams, SelfMatchingOptions, SwapParams, TimeInForce,
 };
 use serial_test::serial;
 use sui_sdk::types::{
@@ -24,7 +27,7 @@ async fn test_place_limit_order() -> Result<()> {
     let params = PlaceLimitOrderParams {
         pool_key: "DEEP_SUI".to_string(),
         balance_manager_key: "MANAGER_2".to_string(),
-        client_order_id: "123123".to_string(),
+        client_order_id: ClientOrderId(123123),
         price: 0.01,
         quantity: 1.0,
         is_bid: true,
@@ -72,7 +75,7 @@ async fn test_place_market_order() -> Result<()> {
     let params = PlaceMarketOrderParams {
         pool_key: "DEEP_SUI".to_string(),
         balance_manager_key: "MANAGER_2".to_string(),
-        client_order_id: "123123".to_string(),
+        client_order_id: ClientOrderId(123123),
         quantity: 1f64,
         is_bid: true,
         self_matching_option: Some(SelfMatchingOptions::SelfMatchingAllowed),
@@ -278,6 +281,36 @@ async fn test_get_pool_deep_price() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+#[serial]
+async fn test_add_deep_price_point() -> Result<()> {
+    let (client, sender, deep_book_client) = setup_client().await?;
+    let mut ptb = ProgrammableTransactionBuilder::new();
+
+    deep_book_client
+        .deep_book_admin
+        .add_deep_price_point(&mut ptb, "DBUSDT_SUI", "DEEP_SUI")
+        .await?;
+
+    let gas_coin = get_gas_coin(&client, sender).await?;
+    let gas_price = client.read_api().get_reference_gas_price().await?;
+    let tx_data = TransactionData::new_programmable(
+        sender,
+        vec![gas_coin],
+        ptb.finish(),
+        5_000_000,
+        gas_price,
+    );
+    sign_and_execute(&client, sender, tx_data).await?;
+
+    // The target pool now has a DEEP reference price for its quote asset
+    let data = deep_book_client.get_pool_deep_price("DBUSDT_SUI").await?;
+    assert!(!data.asset_is_base);
+    assert!(data.deep_per_quote.unwrap_or(0.0) > 0.0);
+
+    Ok(())
+}
+
 #[tokio::test]
 #[serial]
 async fn test_get_pool_book_params() -> Result<()> {
@@ -644,3 +677,205 @@ async fn test_swap_exact_quote_for_base() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn test_create_permissionless_pool_rejects_existing_pair() -> Result<()> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+    let mut ptb = ProgrammableTransactionBuilder::new();
+
+    // SUI/DBUSDC is already listed, in either order
+    assert!(deep_book_client.pool_exists("DBUSDC", "SUI").await?);
+    let result = deep_book_client
+        .create_permissionless_pool(&mut ptb, "SUI", "DBUSDC", 0.001, 0.1, 1.0)
+        .await;
+    assert!(result.is_err());
+    assert!(ptb.finish().commands.is_empty());
+
+    Ok(())
+}
+
+fn ladder(is_bid: bool, start_price: f64, end_price: f64, levels: usize) -> LadderParams {
+    LadderParams {
+        pool_key: "DEEP_SUI".to_string(),
+        balance_manager_key: "MANAGER_2".to_string(),
+        is_bid,
+        start_price,
+        end_price,
+        levels,
+        total_size: 10.3,
+        distribution: LadderDistribution::Arithmetic,
+        first_client_order_id: ClientOrderId(500),
+        expiration: None,
+        pay_with_deep: Some(true),
+    }
+}
+
+#[test]
+fn test_ladder_orders_align_to_book() {
+    let book = BookParams {
+        tick_size: 0.01,
+        lot_size: 0.1,
+        min_size: 1.0,
+    };
+    let approx = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+    // Bids round down; leftover lots go to the levels nearest the start
+    let orders = ladder_orders(&ladder(true, 1.0, 0.9, 5), &book).unwrap();
+    let prices: Vec<f64> = orders.iter().map(|order| order.price).collect();
+    let sizes: Vec<f64> = orders.iter().map(|order| order.quantity).collect();
+    for (actual, expected) in prices.iter().zip([1.0, 0.97, 0.95, 0.92, 0.9]) {
+        assert!(approx(*actual, expected), "price {}", actual);
+    }
+    for (actual, expected) in sizes.iter().zip([2.1, 2.1, 2.1, 2.0, 2.0]) {
+        assert!(approx(*actual, expected), "size {}", actual);
+    }
+    let ids: Vec<u64> = orders.iter().map(|order| order.client_order_id.0).collect();
+    assert_eq!(ids, [500, 501, 502, 503, 504]);
+    assert!(
+        orders
+            .iter()
+            .all(|order| order.is_bid && order.order_type == Some(OrderType::PostOnly))
+    );
+
+    // Asks round up, with a constant ratio between geometric levels
+    let mut params = ladder(false, 1.0, 2.0, 3);
+    params.distribution = LadderDistribution::Geometric;
+    let orders = ladder_orders(&params, &book).unwrap();
+    for (order, expected) in orders.iter().zip([1.0, 1.42, 2.0]) {
+        assert!(approx(order.price, expected), "price {}", order.price);
+    }
+}
+
+#[test]
+fn test_ladder_orders_reject_invalid_ladders() {
+    let book = BookParams {
+        tick_size: 0.01,
+        lot_size: 0.1,
+        min_size: 1.0,
+    };
+
+    assert_eq!(
+        ladder_orders(&ladder(true, 1.0, 0.9, 0), &book).unwrap_err(),
+        LadderError::NoLevels
+    );
+    assert!(matches!(
+        ladder_orders(&ladder(true, 1.0, 1.05, 10), &book),
+        Err(LadderError::LevelsOverlap { .. })
+    ));
+    assert!(matches!(
+        ladder_orders(&ladder(true, 1.0, 0.5, 20), &book),
+        Err(LadderError::SizeTooSmall { .. })
+    ));
+    let mut params = ladder(true, 1.0, 0.9, 3);
+    params.first_client_order_id = ClientOrderId(u64::MAX - 1);
+    assert!(matches!(
+        ladder_orders(&params, &book),
+        Err(LadderError::ClientOrderIdOverflow { levels: 3, .. })
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_place_ladder_shares_one_proof() -> Result<()> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+    let mut ptb = ProgrammableTransactionBuilder::new();
+
+    let mut params = ladder(true, 0.02, 0.01, 5);
+    params.total_size = 500.0;
+    let orders = deep_book_client.place_ladder(&mut ptb, &params).await?;
+    assert_eq!(orders.len(), 5);
+
+    let functions: Vec<String> = ptb
+        .finish()
+        .commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::MoveCall(call) => Some(call.function.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        functions
+            .iter()
+            .filter(|f| f.starts_with("generate_proof"))
+            .count(),
+        1
+    );
+    assert_eq!(
+        functions
+            .iter()
+            .filter(|f| *f == "place_limit_order")
+            .count(),
+        5
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_time_in_force_resolves_against_chain_time() {
+    let now_ms = 1_700_000_000_000;
+    let resolve = |time_in_force: TimeInForce| time_in_force.resolve(now_ms);
+
+    assert_eq!(
+        resolve(TimeInForce::Gtt(Duration::from_secs(60))),
+        Ok((OrderType::NoRestriction, now_ms + 60_000))
+    );
+    assert_eq!(
+        resolve(TimeInForce::Gtd(now_ms + 1)),
+        Ok((OrderType::NoRestriction, now_ms + 1))
+    );
+    assert_eq!(
+        resolve(TimeInForce::Ioc).unwrap().0,
+        OrderType::ImmediateOrCancel
+    );
+    assert_eq!(resolve(TimeInForce::Fok).unwrap().0, OrderType::FillOrKill);
+    assert_eq!(
+        resolve(TimeInForce::PostOnly).unwrap().0,
+        OrderType::PostOnly
+    );
+    assert_eq!(
+        resolve(TimeInForce::Gtc).unwrap(),
+        resolve(TimeInForce::Gtt(Duration::MAX)).unwrap()
+    );
+
+    assert_eq!(
+        resolve(TimeInForce::Gtd(now_ms)),
+        Err(TimeInForceError::Expired {
+            expire_timestamp: now_ms,
+            now_ms,
+        })
+    );
+    assert!(resolve(TimeInForce::Gtt(Duration::ZERO)).is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_place_limit_order_rejects_expired_order() -> Result<()> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+    let now_ms = deep_book_client.get_chain_time_ms().await?;
+    // Later than 2024-01-01
+    assert!(now_ms > 1_704_067_200_000);
+
+    let params = PlaceLimitOrderParams {
+        pool_key: "DEEP_SUI".to_string(),
+        balance_manager_key: "MANAGER_2".to_string(),
+        client_order_id: ClientOrderId(1),
+        price: 0.01,
+        quantity: 1.0,
+        is_bid: true,
+        expiration: None,
+        order_type: None,
+        self_matching_option: None,
+        pay_with_deep: Some(true),
+    };
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let result = deep_book_client
+        .place_limit_order_with_time_in_force(&mut ptb, &params, TimeInForce::Gtd(now_ms - 1))
+        .await;
+    assert!(result.is_err());
+    assert!(ptb.finish().commands.is_empty());
+
+    Ok(())
+}
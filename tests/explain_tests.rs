@@ -0,0 +1,120 @@
+use deepbookv3::types::BalanceManager;
+use deepbookv3::utils::config::DeepBookConfig;
+use deepbookv3::utils::explain::{explain_transaction, render_explanation};
+use deepbookv3::utils::parse_type_input;
+use serde_json::Value;
+use sui_sdk::types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::{Argument, CallArg, Command, ObjectArg, ProgrammableMoveCall};
+use sui_sdk::types::type_input::TypeInput;
+
+const MANAGER: &str = "0x08933685e0246a2ddae2f5e5628fdeba09de831cadf5ad949db308807f18bee5";
+
+fn config() -> DeepBookConfig {
+    let config = DeepBookConfig::new("testnet", SuiAddress::ZERO, None, None, None, None);
+    config.register_balance_manager("MAIN", BalanceManager::new(MANAGER));
+    config
+}
+
+fn shared(ptb: &mut ProgrammableTransactionBuilder, address: &str) -> Argument {
+    ptb.input(CallArg::Object(ObjectArg::SharedObject {
+        id: ObjectID::from_hex_literal(address).unwrap(),
+        initial_shared_version: SequenceNumber::from_u64(1),
+        mutable: true,
+    }))
+    .unwrap()
+}
+
+fn move_call(
+    ptb: &mut ProgrammableTransactionBuilder,
+    package: ObjectID,
+    target: (&str, &str),
+    type_arguments: Vec<TypeInput>,
+    arguments: Vec<Argument>,
+) -> Argument {
+    ptb.command(Command::MoveCall(Box::new(ProgrammableMoveCall {
+        package,
+        module: target.0.to_string(),
+        function: target.1.to_string(),
+        type_arguments,
+        arguments,
+    })))
+}
+
+#[test]
+fn test_explain_limit_order_and_deposit() {
+    let config = config();
+    let package = ObjectID::from_hex_literal(&config.deepbook_package_id).unwrap();
+    let pool = config.get_pool("SUI_DBUSDC");
+    let sui = parse_type_input(config.get_coin("SUI").coin_type).unwrap();
+    let dbusdc = parse_type_input(config.get_coin("DBUSDC").coin_type).unwrap();
+
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let manager = shared(&mut ptb, MANAGER);
+
+    // Deposit 1.5 SUI split from gas
+    let amount = ptb.pure(1_500_000_000u64).unwrap();
+    let coin = ptb.command(Command::SplitCoins(Argument::GasCoin, vec![amount]));
+    move_call(
+        &mut ptb,
+        package,
+        ("balance_manager", "deposit"),
+        vec![sui.clone()],
+        vec![manager, coin],
+    );
+
+    // Buy 10 SUI at 2.5 DBUSDC, post only
+    let proof = move_call(
+        &mut ptb,
+        package,
+        ("balance_manager", "generate_proof_as_owner"),
+        vec![],
+        vec![manager],
+    );
+    let pool_arg = shared(&mut ptb, &pool.address);
+    let arguments = vec![
+        pool_arg,
+        manager,
+        proof,
+        ptb.pure(7u64).unwrap(),
+        ptb.pure(3u8).unwrap(),
+        ptb.pure(0u8).unwrap(),
+        ptb.pure(2_500_000u64).unwrap(),
+        ptb.pure(10_000_000_000u64).unwrap(),
+        ptb.pure(true).unwrap(),
+        ptb.pure(true).unwrap(),
+        ptb.pure(u64::MAX).unwrap(),
+        ptb.input(CallArg::CLOCK_IMM).unwrap(),
+    ];
+    move_call(
+        &mut ptb,
+        package,
+        ("pool", "place_limit_order"),
+        vec![sui, dbusdc],
+        arguments,
+    );
+
+    let explained = explain_transaction(&config, &ptb.finish());
+    assert_eq!(explained.len(), 4);
+    assert_eq!(explained[1].summary, "Deposit 1.5 SUI into manager MAIN");
+
+    let order = &explained[3];
+    assert_eq!(order.call.as_deref(), Some("pool::place_limit_order"));
+    assert_eq!(order.pool.as_deref(), Some("SUI_DBUSDC"));
+    let param = |name: &str| order.params.iter().find(|p| p.name == name).unwrap();
+    assert_eq!(param("price").value, Value::from(2.5));
+    assert_eq!(param("price").unit.as_deref(), Some("DBUSDC"));
+    assert_eq!(param("quantity").text(), "10 SUI");
+    assert_eq!(param("order_type").text(), "post_only");
+    assert_eq!(
+        order.summary,
+        "Buy 10 SUI at 2.5 DBUSDC on SUI_DBUSDC (post_only, manager MAIN)"
+    );
+
+    // Calls without a layout keep the plain description
+    assert_eq!(explained[2].params, vec![]);
+    assert!(
+        render_explanation(&explained)
+            .starts_with("#0 split gas into [1500000000]\n#1 Deposit 1.5 SUI into manager MAIN")
+    );
+}
@@ -1,8 +1,16 @@
 mod test_helper;
 
 use anyhow::Result;
+use deepbookv3::transactions::flash_loans::FlashLoanPlan;
+use deepbookv3::types::{SwapDirection, SwapLeg};
+use deepbookv3::utils::config::DeepBookConfig;
+use deepbookv3::utils::move_abort;
 use serial_test::serial;
+use std::str::FromStr;
 use sui_sdk::types::{
+    base_types::SuiAddress,
+    execution_status::{ExecutionFailureStatus, ExecutionStatus, MoveLocation},
+    parse_sui_struct_tag,
     programmable_transaction_builder::ProgrammableTransactionBuilder,
     transaction::{Command, TransactionData},
 };
@@ -116,3 +124,162 @@ async fn test_flash_loans_quote_workflow() -> Result<(), anyhow::Error> {
     );
     Ok(())
 }
+
+#[test]
+fn test_flash_loan_plan_rejects_unclosed_route() -> Result<(), anyhow::Error> {
+    let sender =
+        SuiAddress::from_str("0x38a27d258039c629219b3dbaaeb502381d26f9b93f985e2fec7d248db00d3cf1")?;
+    let config = DeepBookConfig::new("testnet", sender, None, None, None, None);
+
+    // DEEP -> DBUSDC never comes back to DEEP
+    let plan = FlashLoanPlan::borrow_base("DEEP_SUI", 10.0).swap(SwapLeg {
+        pool_key: "DEEP_DBUSDC".to_string(),
+        direction: SwapDirection::BaseForQuote,
+        deep_amount: 0.0,
+        min_out: 0.0,
+    });
+    assert!(plan.validate(&config).is_err());
+
+    // Input of the first leg must be the borrowed asset
+    let plan = FlashLoanPlan::borrow_base("DEEP_SUI", 10.0).swap(SwapLeg {
+        pool_key: "SUI_DBUSDC".to_string(),
+        direction: SwapDirection::BaseForQuote,
+        deep_amount: 0.0,
+        min_out: 0.0,
+    });
+    assert!(plan.validate(&config).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_flash_loan_plan_splits_deep_once() -> Result<(), anyhow::Error> {
+    let (_client, sender, deep_book_client) = setup_client().await?;
+
+    // SUI -> DBUSDC -> SUI, paying DEEP fees on both legs
+    let leg = |direction| SwapLeg {
+        pool_key: "SUI_DBUSDC".to_string(),
+        direction,
+        deep_amount: 0.1,
+        min_out: 0.0,
+    };
+    let plan = FlashLoanPlan::borrow_base("SUI_DBUSDC", 1.0)
+        .swap(leg(SwapDirection::BaseForQuote))
+        .swap(leg(SwapDirection::QuoteForBase));
+
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let commands = plan
+        .build(
+            &mut ptb,
+            &deep_book_client.flash_loans,
+            &deep_book_client.deep_book,
+            sender,
+        )
+        .await?;
+    assert_eq!(commands.swap_commands.len(), 2);
+
+    let pt = ptb.finish();
+    let merges = pt
+        .commands
+        .iter()
+        .filter(|command| matches!(command, Command::MergeCoins(..)))
+        .count();
+    assert!(merges <= 1, "DEEP coins merged {} times", merges);
+    assert!(
+        pt.commands.iter().any(
+            |command| matches!(command, Command::SplitCoins(_, amounts) if amounts.len() == 2)
+        )
+    );
+
+    Ok(())
+}
+
+fn round_trip(pool_key: &str, min_out: f64) -> Vec<SwapLeg> {
+    [SwapDirection::BaseForQuote, SwapDirection::QuoteForBase]
+        .into_iter()
+        .map(|direction| SwapLeg {
+            pool_key: pool_key.to_string(),
+            direction,
+            deep_amount: 0.0,
+            min_out,
+        })
+        .collect()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_simulate_flash_loan_round_trip_cannot_repay() -> Result<(), anyhow::Error> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+
+    // Selling and buying back on one book loses the spread, so the loan
+    // can never be repaid in full
+    let mut plan = FlashLoanPlan::borrow_base("DEEP_SUI", 10.0);
+    plan.legs = round_trip("DEEP_DBUSDC", 0.0);
+
+    let simulation = deep_book_client.simulate_flash_loan(&plan).await?;
+    assert!(
+        !simulation.repaid,
+        "round trip repaid the loan: {:?}",
+        simulation
+    );
+    assert!(simulation.error.is_some());
+    assert!(simulation.legs.is_empty());
+    assert!(simulation.profit.is_empty());
+    assert!(!simulation.is_profitable());
+
+    Ok(())
+}
+
+#[test]
+fn test_move_abort_from_status() -> Result<(), anyhow::Error> {
+    let module = parse_sui_struct_tag(
+        "0x2c8d603bc51326b8c13cef9dd07031a408a48dddb541963357661df5d3204809::vault::Vault",
+    )?
+    .module_id();
+    let status = ExecutionStatus::Failure {
+        error: ExecutionFailureStatus::MoveAbort(
+            MoveLocation {
+                module,
+                function: 14,
+                instruction: 38,
+                function_name: Some("return_flashloan_base".to_string()),
+            },
+            5,
+        ),
+        command: Some(9),
+    };
+    let abort = move_abort(&status).expect("status is a Move abort");
+
+    assert_eq!(abort.module, "vault");
+    assert_eq!(abort.function.as_deref(), Some("return_flashloan_base"));
+    assert_eq!(abort.abort_code, 5);
+    assert_eq!(abort.command, Some(9));
+
+    let out_of_gas = ExecutionStatus::Failure {
+        error: ExecutionFailureStatus::InsufficientGas,
+        command: None,
+    };
+    assert!(move_abort(&out_of_gas).is_none());
+    assert!(move_abort(&ExecutionStatus::Success).is_none());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_simulate_flash_loan_reports_abort() -> Result<(), anyhow::Error> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+
+    // No swap returns a million DEEP worth of DBUSDC for 10 DEEP
+    let mut plan = FlashLoanPlan::borrow_base("DEEP_SUI", 10.0);
+    plan.legs = round_trip("DEEP_DBUSDC", 1_000_000.0);
+
+    let simulation = deep_book_client.simulate_flash_loan(&plan).await?;
+    assert!(!simulation.repaid);
+    assert!(simulation.legs.is_empty());
+    let abort = simulation.abort.expect("min_out should abort the swap");
+    assert_eq!(abort.module, "pool");
+    assert!(abort.command.is_some());
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 mod test_helper;
 
 use anyhow::Result;
+use deepbookv3::errors::ProposalError;
+use deepbookv3::transactions::governance::validate_proposal;
+use deepbookv3::types::{GovernanceState, TradeParams};
 use serial_test::serial;
 use sui_sdk::types::{
     programmable_transaction_builder::ProgrammableTransactionBuilder, transaction::TransactionData,
@@ -89,7 +92,7 @@ async fn test_submit_proposal() -> Result<()> {
     // Step 1: Set up proposal submission transaction
     deep_book_client
         .governance
-        .submit_proposal(&mut ptb, "SUI_DBUSDC", "MANAGER_2", 0.001, 0.002, 50.0)
+        .submit_proposal(&mut ptb, "SUI_DBUSDC", "MANAGER_2", 0.001, 0.0005, 50.0)
         .await?;
 
     // Step 2: Fetch gas coin
@@ -121,11 +124,15 @@ async fn test_vote() -> Result<()> {
     let (client, sender, deep_book_client) = setup_client().await?;
     let mut ptb = ProgrammableTransactionBuilder::new();
 
-    // Step 1: Set up voting transaction
-    let proposal_id = "0x123456789abcdef"; // Replace with actual proposal ID
+    // Step 1: Set up voting transaction on the first open proposal
+    let proposals = deep_book_client.get_proposals("SUI_DBUSDC").await?;
+    let Some(proposal) = proposals.first() else {
+        println!("No open proposals, skipping vote.");
+        return Ok(());
+    };
     deep_book_client
         .governance
-        .vote(&mut ptb, "SUI_DBUSDC", "MANAGER_2", proposal_id)
+        .vote(&mut ptb, "SUI_DBUSDC", "MANAGER_2", proposal.id)
         .await?;
 
     // Step 2: Fetch gas coin
@@ -148,3 +155,104 @@ async fn test_vote() -> Result<()> {
     println!("Voting transaction successful.");
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn test_get_governance() -> Result<()> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+
+    let governance = deep_book_client.get_governance("SUI_DBUSDC").await?;
+    println!("Governance: {:#?}", governance);
+
+    assert!(governance.epoch > 0, "Epoch should be greater than 0");
+    assert!(governance.quorum >= 0.0, "Quorum should be non-negative");
+    for proposal in &governance.proposals {
+        assert!(
+            proposal.maker_fee <= proposal.taker_fee,
+            "Maker fee should not exceed taker fee"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_vote_for_best_proposal() -> Result<()> {
+    let (client, sender, deep_book_client) = setup_client().await?;
+    let mut ptb = ProgrammableTransactionBuilder::new();
+
+    if deep_book_client
+        .get_proposals("SUI_DBUSDC")
+        .await?
+        .is_empty()
+    {
+        println!("No open proposals, skipping vote.");
+        return Ok(());
+    }
+
+    // Back the proposal with the lowest taker fee
+    let proposal_id = deep_book_client
+        .governance
+        .vote_for_best_proposal(&mut ptb, "SUI_DBUSDC", "MANAGER_2", |p| -p.taker_fee)
+        .await?;
+    println!("Voting for proposal {}", proposal_id);
+
+    let gas_coin = get_gas_coin(&client, sender).await?;
+    let gas_budget = 5_000_000;
+    let gas_price = client.read_api().get_reference_gas_price().await?;
+    let tx_data = TransactionData::new_programmable(
+        sender,
+        vec![gas_coin],
+        ptb.finish(),
+        gas_budget,
+        gas_price,
+    );
+
+    sign_and_execute(&client, sender, tx_data).await?;
+
+    println!("Voting transaction successful.");
+    Ok(())
+}
+
+#[test]
+fn test_validate_proposal_bounds() {
+    let params = TradeParams {
+        taker_fee: 0.001,
+        maker_fee: 0.0005,
+        stake_required: 100.0,
+    };
+    let mut governance = GovernanceState {
+        epoch: 1,
+        whitelisted: false,
+        stable: false,
+        quorum: 0.0,
+        voting_power: 0.0,
+        proposals: vec![],
+        trade_params: params,
+        next_trade_params: params,
+    };
+
+    assert!(validate_proposal(&governance, "SUI_DBUSDC", 0.001, 0.0005, 50.0).is_ok());
+    assert!(matches!(
+        validate_proposal(&governance, "SUI_DBUSDC", 0.01, 0.0005, 50.0),
+        Err(ProposalError::TakerFeeOutOfRange { .. })
+    ));
+    assert!(matches!(
+        validate_proposal(&governance, "SUI_DBUSDC", 0.0002, 0.0003, 50.0),
+        Err(ProposalError::MakerAboveTaker { .. })
+    ));
+
+    // Volatile fees are too high for a stable pool
+    governance.stable = true;
+    assert!(matches!(
+        validate_proposal(&governance, "SUI_DBUSDC", 0.001, 0.0005, 50.0),
+        Err(ProposalError::TakerFeeOutOfRange { kind: "stable", .. })
+    ));
+
+    governance.whitelisted = true;
+    assert_eq!(
+        validate_proposal(&governance, "SUI_DBUSDC", 0.00005, 0.0, 50.0),
+        Err(ProposalError::WhitelistedPool("SUI_DBUSDC".to_string()))
+    );
+}
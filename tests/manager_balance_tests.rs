@@ -1,6 +1,9 @@
 mod test_helper;
 
 use anyhow::Result;
+use deepbookv3::errors::ManagerAccessError;
+use deepbookv3::types::{BalanceManager, CapKind};
+use deepbookv3::utils::transfer_objects;
 use serial_test::serial;
 use sui_sdk::types::{
     programmable_transaction_builder::ProgrammableTransactionBuilder, transaction::TransactionData,
@@ -41,6 +44,41 @@ async fn test_create_and_share_balance_manager() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+#[serial]
+async fn test_create_and_register_balance_manager() -> Result<()> {
+    let (client, sender, deep_book_client) = setup_client().await?;
+    let mut ptb: ProgrammableTransactionBuilder = ProgrammableTransactionBuilder::new();
+
+    deep_book_client
+        .balance_manager
+        .create_and_share_balance_manager(&mut ptb)
+        .await?;
+
+    let gas_coin = get_gas_coin(&client, sender).await?;
+    let gas_budget = 5_000_000;
+    let gas_price = client.read_api().get_reference_gas_price().await?;
+    let tx_data = TransactionData::new_programmable(
+        sender,
+        vec![gas_coin],
+        ptb.finish(),
+        gas_budget,
+        gas_price,
+    );
+
+    let response = sign_and_execute(&client, sender, tx_data).await?;
+
+    // The new manager is usable right away under its key
+    let manager_id = deep_book_client.register_created_balance_manager("NEW_MANAGER", &response)?;
+    let owner = deep_book_client.get_manager_owner("NEW_MANAGER").await?;
+    assert_eq!(owner, sender);
+
+    let discovered = deep_book_client.discover_balance_managers(sender).await?;
+    assert!(discovered.contains(&manager_id));
+
+    Ok(())
+}
+
 #[tokio::test]
 #[serial]
 async fn test_check_manager_balance() -> Result<()> {
@@ -115,10 +153,11 @@ async fn test_withdraw_from_manager() -> Result<()> {
     // Step 1: Set up transaction for withdrawal
     let withdraw_amount = 0.1;
     let recipient = sender; // Self-withdrawal test
-    let pt = deep_book_client
+    let coin = deep_book_client
         .balance_manager
-        .withdraw_from_manager(&mut ptb, "MANAGER_2", "SUI", withdraw_amount, recipient)
+        .withdraw_from_manager(&mut ptb, "MANAGER_2", "SUI", withdraw_amount)
         .await?;
+    transfer_objects(&mut ptb, vec![coin], recipient)?;
 
     // Step 2: Fetch a suitable gas coin
     let gas_coin = get_gas_coin(&client, sender).await?;
@@ -152,7 +191,7 @@ async fn test_withdraw_all_from_manager() -> Result<()> {
     let recipient = sender; // Self-withdrawal test
     deep_book_client
         .balance_manager
-        .withdraw_all_from_manager(&mut ptb, "MANAGER_2", "USDC", recipient)
+        .withdraw_all_from_manager_to(&mut ptb, "MANAGER_2", "USDC", recipient)
         .await?;
 
     // Step 2: Fetch a suitable gas coin
@@ -245,3 +284,168 @@ async fn test_generate_trade_proof() -> Result<()> {
     println!("Trade proof generated successfully.");
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn test_rotate_trade_cap() -> Result<()> {
+    let (client, sender, deep_book_client) = setup_client().await?;
+
+    // Mint a cap for ourselves so there is something to rotate
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    deep_book_client
+        .balance_manager
+        .mint_cap_for(&mut ptb, "MANAGER_2", CapKind::Trade, sender)
+        .await?;
+    let gas_coin = get_gas_coin(&client, sender).await?;
+    let gas_price = client.read_api().get_reference_gas_price().await?;
+    let tx_data = TransactionData::new_programmable(
+        sender,
+        vec![gas_coin],
+        ptb.finish(),
+        5_000_000,
+        gas_price,
+    );
+    sign_and_execute(&client, sender, tx_data).await?;
+
+    let before = deep_book_client.get_allow_listed_caps("MANAGER_2").await?;
+    let caps = deep_book_client.list_caps("MANAGER_2").await?;
+    let old_cap = caps
+        .iter()
+        .find(|cap| cap.kind == Some(CapKind::Trade) && cap.owner == Some(sender))
+        .expect("minted trade cap should be listed")
+        .id;
+
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    deep_book_client
+        .balance_manager
+        .rotate_cap(&mut ptb, "MANAGER_2", CapKind::Trade, old_cap, sender)
+        .await?;
+    let gas_coin = get_gas_coin(&client, sender).await?;
+    let tx_data = TransactionData::new_programmable(
+        sender,
+        vec![gas_coin],
+        ptb.finish(),
+        5_000_000,
+        gas_price,
+    );
+    sign_and_execute(&client, sender, tx_data).await?;
+
+    let after = deep_book_client.get_allow_listed_caps("MANAGER_2").await?;
+    assert!(!after.contains(&old_cap));
+    assert_eq!(after.len(), before.len());
+
+    // Revoke the rotated cap so the shared manager's allow list doesn't grow
+    let new_cap = *after
+        .iter()
+        .find(|cap| !before.contains(cap))
+        .expect("rotated cap should be allow-listed");
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    deep_book_client
+        .balance_manager
+        .revoke_trade_cap(&mut ptb, "MANAGER_2", new_cap)
+        .await?;
+    let gas_coin = get_gas_coin(&client, sender).await?;
+    let tx_data = TransactionData::new_programmable(
+        sender,
+        vec![gas_coin],
+        ptb.finish(),
+        5_000_000,
+        gas_price,
+    );
+    sign_and_execute(&client, sender, tx_data).await?;
+
+    let revoked = deep_book_client.get_allow_listed_caps("MANAGER_2").await?;
+    assert!(!revoked.contains(&new_cap));
+    assert_eq!(revoked.len(), before.len() - 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_delegated_mode_refuses_owner_operations() -> Result<()> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+    let manager = deep_book_client.get_manager_id("MANAGER_2").await?.bytes;
+
+    // The manager itself is not a TradeCap
+    let err = deep_book_client
+        .use_trade_cap("MANAGER_2", manager)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ManagerAccessError>(),
+        Some(ManagerAccessError::NotATradeCap(_))
+    ));
+
+    deep_book_client.register_balance_manager(
+        "DELEGATED",
+        BalanceManager::delegated(&manager.to_string(), &manager.to_string()),
+    );
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let err = deep_book_client
+        .balance_manager
+        .withdraw_all_from_manager(&mut ptb, "DELEGATED", "SUI")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<ManagerAccessError>(),
+        Some(&ManagerAccessError::OwnerOnly {
+            manager: "DELEGATED".to_string(),
+            operation: "withdraw_all",
+        })
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_withdraw_and_redeposit_in_one_ptb() -> Result<()> {
+    let (client, sender, deep_book_client) = setup_client().await?;
+    let mut ptb = ProgrammableTransactionBuilder::new();
+
+    // The withdrawn coin is routed straight back instead of to the sender
+    let coin = deep_book_client
+        .balance_manager
+        .withdraw_from_manager(&mut ptb, "MANAGER_2", "SUI", 0.1)
+        .await?;
+    deep_book_client
+        .balance_manager
+        .deposit_coin_into_manager(&mut ptb, "MANAGER_2", "SUI", coin)
+        .await?;
+
+    let gas_coin = get_gas_coin(&client, sender).await?;
+    let gas_price = client.read_api().get_reference_gas_price().await?;
+    let tx_data = TransactionData::new_programmable(
+        sender,
+        vec![gas_coin],
+        ptb.finish(),
+        5_000_000,
+        gas_price,
+    );
+    sign_and_execute(&client, sender, tx_data).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_get_manager_balance_report() -> Result<()> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+
+    let balances = deep_book_client.get_manager_balances("MANAGER_2").await?;
+    let (_, sui_balance) = deep_book_client
+        .check_manager_balance("MANAGER_2", "SUI")
+        .await?;
+    assert_eq!(balances.get("SUI"), Some(&sui_balance));
+
+    let report = deep_book_client
+        .get_manager_balance_report("MANAGER_2")
+        .await?;
+    for (pool_key, pool) in &report.pools {
+        println!("{}: {:?}", pool_key, pool);
+        assert!(pool.locked.base >= 0.0 && pool.locked.quote >= 0.0);
+    }
+
+    Ok(())
+}
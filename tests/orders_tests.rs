@@ -0,0 +1,128 @@
+mod test_helper;
+
+use anyhow::Result;
+use deepbookv3::orders::{ClientOrderIdGenerator, OrderRegistry, Reconciliation};
+use deepbookv3::types::{ClientOrderId, OpenOrder, OrderPlacement};
+use serial_test::serial;
+use test_helper::setup_client;
+
+/// Id of the `sequence`th bid at price 1.0. Bid sequence numbers count down
+/// from `u64::MAX`.
+fn bid_id(sequence: u64) -> u128 {
+    (1_000_000u128 << 64) | (u64::MAX - sequence) as u128
+}
+
+/// Id of the `sequence`th ask at price 1.0
+fn ask_id(sequence: u64) -> u128 {
+    (1u128 << 127) | (1_000_000u128 << 64) | sequence as u128
+}
+
+fn placement(order_id: u128, client_order_id: u64) -> OrderPlacement {
+    OrderPlacement {
+        pool_key: "SUI_DBUSDC".to_string(),
+        order_id,
+        client_order_id: ClientOrderId(client_order_id),
+        is_bid: true,
+        price: 1.0,
+        quantity: 5.0,
+        expire_timestamp: u64::MAX,
+    }
+}
+
+fn open_order(order_id: u128, client_order_id: u64, quantity: f64) -> OpenOrder {
+    OpenOrder {
+        order_id,
+        client_order_id: ClientOrderId(client_order_id),
+        is_bid: order_id >> 127 == 0,
+        price: 1.0,
+        quantity,
+        filled_quantity: 5.0 - quantity,
+        expire_timestamp: u64::MAX,
+    }
+}
+
+#[test]
+fn test_client_order_ids_parse_strictly() {
+    assert_eq!("42".parse::<ClientOrderId>().unwrap(), ClientOrderId(42));
+    assert!("order-1".parse::<ClientOrderId>().is_err());
+    assert!("".parse::<ClientOrderId>().is_err());
+    assert_eq!(ClientOrderId(7).to_string(), "7");
+}
+
+#[test]
+fn test_generator_is_monotonic() {
+    let mut ids = ClientOrderIdGenerator::new();
+    let first = ids.next_id();
+    let second = ids.next_id();
+    assert!(second > first);
+
+    // Generators made at once, e.g. an engine's and an executor's, share a
+    // sequence
+    let mut engine_ids = ClientOrderIdGenerator::new();
+    let mut executor_ids = ClientOrderIdGenerator::new();
+    assert_ne!(engine_ids.next_id(), executor_ids.next_id());
+
+    // Resumes past a higher id than the clock gives
+    let mut ids = ClientOrderIdGenerator::starting_after(ClientOrderId(u64::MAX - 10));
+    assert_eq!(ids.next_id(), ClientOrderId(u64::MAX - 9));
+}
+
+#[test]
+fn test_registry_reconciles_with_open_orders() {
+    let mut registry = OrderRegistry::new();
+    registry.record_placements(&[placement(bid_id(10), 1), placement(bid_id(11), 2)]);
+    assert_eq!(registry.order_id(ClientOrderId(1)), Some(bid_id(10)));
+    assert_eq!(registry.client_order_id(bid_id(11)), Some(ClientOrderId(2)));
+
+    // Bid 10 partially filled, 11 gone, 12 placed before a restart and 13
+    // reusing client id 3; asks 14 and 15 share client id 4
+    let open = [
+        open_order(bid_id(10), 1, 3.0),
+        open_order(bid_id(13), 3, 5.0),
+        open_order(bid_id(12), 3, 5.0),
+        open_order(ask_id(15), 4, 5.0),
+        open_order(ask_id(14), 4, 5.0),
+    ];
+    let report = registry.reconcile("SUI_DBUSDC", &open);
+    assert_eq!(
+        report,
+        Reconciliation {
+            recovered: vec![ClientOrderId(3), ClientOrderId(4)],
+            closed: vec![ClientOrderId(2)],
+            duplicates: vec![ClientOrderId(3), ClientOrderId(4)],
+        }
+    );
+    assert_eq!(registry.get(ClientOrderId(1)).unwrap().quantity, 3.0);
+    assert_eq!(registry.order_id(ClientOrderId(3)), Some(bid_id(12)));
+    assert_eq!(registry.order_id(ClientOrderId(4)), Some(ask_id(14)));
+    assert_eq!(registry.client_order_id(bid_id(11)), None);
+    assert_eq!(registry.last_client_order_id(), Some(ClientOrderId(4)));
+
+    // Other pools are left alone
+    let report = registry.reconcile("DEEP_SUI", &[]);
+    assert_eq!(report, Reconciliation::default());
+    assert_eq!(registry.orders().count(), 3);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_registry_sync() -> Result<()> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+    let open = deep_book_client
+        .get_open_orders("DEEP_SUI", "MANAGER_2")
+        .await?;
+
+    let mut registry = OrderRegistry::new();
+    let report = registry
+        .sync(&deep_book_client, "DEEP_SUI", "MANAGER_2")
+        .await?;
+    // A fresh registry recovers every open order and closes none
+    assert!(report.closed.is_empty());
+    assert_eq!(report.recovered.len(), registry.orders().count());
+    assert!(open.len() >= report.recovered.len());
+    for order in registry.orders() {
+        assert!(open.iter().any(|o| o.order_id == order.order_id));
+    }
+
+    Ok(())
+}
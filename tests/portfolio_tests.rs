@@ -0,0 +1,71 @@
+mod test_helper;
+
+use anyhow::Result;
+use deepbookv3::portfolio::{PortfolioTracker, Position, value_manager};
+use deepbookv3::types::{ClientOrderId, OrderFill};
+use serial_test::serial;
+use test_helper::setup_client;
+
+fn fill(is_buy: bool, base_quantity: f64, quote_quantity: f64) -> OrderFill {
+    OrderFill {
+        pool_key: "SUI_DBUSDC".to_string(),
+        base_coin: "SUI".to_string(),
+        quote_coin: "DBUSDC".to_string(),
+        order_id: 1,
+        client_order_id: ClientOrderId(1),
+        is_buy,
+        base_quantity,
+        quote_quantity,
+        deep_fee: 0.01,
+        timestamp_ms: 0,
+    }
+}
+
+#[test]
+fn test_position_average_cost_pnl() {
+    let mut position = Position::new("SUI", "DBUSDC");
+
+    position.apply_fill(&fill(true, 10.0, 10.0));
+    position.apply_fill(&fill(true, 10.0, 20.0));
+    assert_eq!(position.average_cost(), Some(1.5));
+
+    position.apply_fill(&fill(false, 5.0, 10.0));
+    assert_eq!(position.base, 15.0);
+    assert_eq!(position.realized, 2.5);
+    assert_eq!(position.unrealized(2.0), 7.5);
+
+    // Selling through zero flips the position short at the fill price
+    position.apply_fill(&fill(false, 20.0, 60.0));
+    assert_eq!(position.base, -5.0);
+    assert_eq!(position.realized, 25.0);
+    assert_eq!(position.average_cost(), Some(3.0));
+    assert!((position.deep_fees - 0.04).abs() < 1e-9);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_value_manager() -> Result<()> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+
+    let valuation = value_manager(&deep_book_client, "MANAGER_2", "DBUSDC").await?;
+    println!("Valuation: {:?}", valuation);
+    assert_eq!(valuation.total, valuation.values.values().sum::<f64>());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_portfolio_snapshot_persists() -> Result<()> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+    let path = std::env::temp_dir().join("deepbook_portfolio_test.json");
+    let _ = std::fs::remove_file(&path);
+
+    let mut tracker = PortfolioTracker::open(&path, "DBUSDC")?;
+    tracker.snapshot(&deep_book_client, "MANAGER_2").await?;
+
+    let reloaded = PortfolioTracker::open(&path, "DBUSDC")?;
+    assert_eq!(reloaded.history("MANAGER_2").unwrap().snapshots.len(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+mod test_helper;
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use deepbookv3::errors::RiskError;
+use deepbookv3::risk::{OrderIntent, RiskLimits, RiskManager, RiskSnapshot};
+use deepbookv3::types::{ClientOrderId, PlaceLimitOrderParams};
+use serial_test::serial;
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::Command;
+use test_helper::setup_client;
+
+fn snapshot(mid: Option<f64>, base: f64, quote: f64, open_orders: usize) -> RiskSnapshot {
+    RiskSnapshot {
+        pool_key: "SUI_DBUSDC".to_string(),
+        base_coin: "SUI".to_string(),
+        quote_coin: "DBUSDC".to_string(),
+        mid,
+        balances: HashMap::from([("SUI".to_string(), base), ("DBUSDC".to_string(), quote)]),
+        open_orders,
+        open_exposure: HashMap::new(),
+    }
+}
+
+fn limit(is_bid: bool, price: f64, quantity: f64) -> OrderIntent {
+    OrderIntent {
+        is_bid,
+        quantity,
+        price: Some(price),
+    }
+}
+
+fn market(is_bid: bool, quantity: f64) -> OrderIntent {
+    OrderIntent {
+        is_bid,
+        quantity,
+        price: None,
+    }
+}
+
+#[test]
+fn test_notional_and_collar_limits() {
+    let limits = RiskLimits {
+        max_order_notional: Some(100.0),
+        price_collar: Some(0.05),
+        ..Default::default()
+    };
+    let state = snapshot(Some(2.0), 0.0, 0.0, 0);
+
+    assert_eq!(limits.check(&[limit(true, 2.0, 50.0)], &state), Ok(()));
+    assert_eq!(
+        limits.check(&[limit(true, 2.0, 51.0)], &state),
+        Err(RiskError::NotionalTooLarge {
+            notional: 102.0,
+            max: 100.0
+        })
+    );
+    // Market orders are valued at mid and have no price to collar
+    assert!(limits.check(&[market(false, 51.0)], &state).is_err());
+    assert_eq!(limits.check(&[market(false, 50.0)], &state), Ok(()));
+
+    assert_eq!(limits.check(&[limit(false, 2.1, 1.0)], &state), Ok(()));
+    assert_eq!(
+        limits.check(&[limit(false, 2.2, 1.0)], &state),
+        Err(RiskError::OutsideCollar {
+            price: 2.2,
+            mid: 2.0,
+            max_deviation: 0.05
+        })
+    );
+
+    // Without a mid only orders that need one are refused
+    let empty = snapshot(None, 0.0, 0.0, 0);
+    assert_eq!(
+        limits.check(&[limit(true, 2.0, 1.0)], &empty),
+        Err(RiskError::NoMidPrice {
+            pool_key: "SUI_DBUSDC".to_string()
+        })
+    );
+    let uncollared = RiskLimits {
+        price_collar: None,
+        ..limits
+    };
+    assert_eq!(uncollared.check(&[limit(true, 2.0, 1.0)], &empty), Ok(()));
+}
+
+#[test]
+fn test_position_balance_and_open_order_limits() {
+    let limits = RiskLimits {
+        max_position: HashMap::from([("SUI".to_string(), 100.0)]),
+        max_open_orders: Some(3),
+        require_balance: true,
+        ..Default::default()
+    };
+    let state = snapshot(Some(1.0), 90.0, 20.0, 1);
+
+    assert_eq!(limits.check(&[limit(true, 1.0, 10.0)], &state), Ok(()));
+    assert!(matches!(
+        limits.check(&[limit(true, 1.0, 5.0), limit(true, 0.9, 6.0)], &state),
+        Err(RiskError::PositionTooLarge { max: 100.0, .. })
+    ));
+    // Resting bids count as if filled, so splitting an order doesn't help
+    let mut resting = state.clone();
+    resting.open_exposure.insert("SUI".to_string(), 6.0);
+    assert!(matches!(
+        limits.check(&[limit(true, 1.0, 5.0)], &resting),
+        Err(RiskError::PositionTooLarge { max: 100.0, .. })
+    ));
+
+    // Selling reduces the position, but needs the base to sell
+    assert_eq!(limits.check(&[limit(false, 1.0, 90.0)], &state), Ok(()));
+    assert!(matches!(
+        limits.check(&[limit(false, 1.0, 91.0)], &state),
+        Err(RiskError::InsufficientBalance { ref coin, .. }) if coin == "SUI"
+    ));
+    let rich = snapshot(Some(1.0), 0.0, 20.0, 1);
+    assert!(matches!(
+        limits.check(&[limit(true, 2.0, 10.5)], &rich),
+        Err(RiskError::InsufficientBalance { ref coin, .. }) if coin == "DBUSDC"
+    ));
+
+    // A ladder counts every level; market orders never rest
+    let ladder = [
+        limit(true, 0.5, 1.0),
+        limit(true, 0.4, 1.0),
+        limit(true, 0.3, 1.0),
+    ];
+    assert_eq!(
+        limits.check(&ladder, &state),
+        Err(RiskError::TooManyOpenOrders {
+            pool_key: "SUI_DBUSDC".to_string(),
+            open: 4,
+            max: 3
+        })
+    );
+    let full = snapshot(Some(1.0), 0.0, 20.0, 3);
+    assert_eq!(limits.check(&[market(true, 1.0)], &full), Ok(()));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_kill_switch_cancels_everywhere_and_refuses_orders() -> Result<()> {
+    let (_client, _sender, deep_book_client) = setup_client().await?;
+    let mut risk = RiskManager::new(deep_book_client, RiskLimits::default());
+
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let report = risk.kill_switch(&mut ptb, "MANAGER_2").await?;
+    assert!(risk.is_killed());
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+    let cancels = ptb
+        .finish()
+        .commands
+        .iter()
+        .filter(|command| match command {
+            Command::MoveCall(call) => call.function.as_str() == "cancel_all_orders",
+            _ => false,
+        })
+        .count();
+    assert_eq!(cancels, report.cancelled.len());
+
+    let params = PlaceLimitOrderParams {
+        pool_key: "DEEP_SUI".to_string(),
+        balance_manager_key: "MANAGER_2".to_string(),
+        client_order_id: ClientOrderId(1),
+        price: 0.1,
+        quantity: 10.0,
+        is_bid: true,
+        expiration: None,
+        order_type: None,
+        self_matching_option: None,
+        pay_with_deep: Some(true),
+    };
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let error = risk.place_limit_order(&mut ptb, &params).await.unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<RiskError>(),
+        Some(&RiskError::KillSwitchEngaged)
+    );
+    assert!(ptb.finish().commands.is_empty());
+
+    risk.reset_kill_switch();
+    assert!(!risk.is_killed());
+    Ok(())
+}
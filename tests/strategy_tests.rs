@@ -0,0 +1,533 @@
+use deepbookv3::orders::ClientOrderIdGenerator;
+use std::time::Duration;
+
+use deepbookv3::strategy::{
+    Action, Algorithm, BookSnapshot, Engine, Exchange, Executor, Inventory, ParentOrder, Quote,
+    QuotingParams, SimulatedExchange, SymmetricQuoter, plan_actions,
+};
+use deepbookv3::types::{ClientOrderId, OpenOrder};
+
+fn book(bid: f64, ask: f64) -> BookSnapshot {
+    BookSnapshot {
+        bids: vec![(bid, 100.0)],
+        asks: vec![(ask, 100.0)],
+        timestamp_ms: 0,
+    }
+}
+
+fn order(order_id: u128, is_bid: bool, price: f64, quantity: f64) -> OpenOrder {
+    OpenOrder {
+        order_id,
+        client_order_id: ClientOrderId(order_id as u64),
+        is_bid,
+        price,
+        quantity,
+        filled_quantity: 0.0,
+        expire_timestamp: u64::MAX,
+    }
+}
+
+fn funded() -> Inventory {
+    Inventory {
+        base: 100.0,
+        quote: 100.0,
+        ..Default::default()
+    }
+}
+
+fn assert_place(action: &Action, is_bid: bool, price: f64, quantity: f64) {
+    let Action::Place { quote, .. } = action else {
+        panic!("Expected a place, got {:?}", action);
+    };
+    assert_eq!(quote.is_bid, is_bid);
+    assert!((quote.price - price).abs() < 1e-9, "price {}", quote.price);
+    assert!(
+        (quote.quantity - quantity).abs() < 1e-9,
+        "quantity {}",
+        quote.quantity
+    );
+}
+
+#[test]
+fn test_plan_rounds_and_drops_crossing_quotes() {
+    let params = QuotingParams::new(0.01, 0.1, 0.5);
+    let desired = [
+        Quote::bid(1.037, 1.26),
+        // Crosses the best ask
+        Quote::bid(1.06, 1.0),
+        // Below the minimum size
+        Quote::bid(0.98, 0.3),
+        Quote::ask(1.051, 2.0),
+    ];
+
+    let mut ids = ClientOrderIdGenerator::starting_after(ClientOrderId(u64::MAX / 2));
+    let actions = plan_actions(
+        &desired,
+        &[],
+        &book(1.00, 1.05),
+        &funded(),
+        &params,
+        &mut ids,
+    );
+
+    assert_eq!(actions.len(), 2);
+    assert_place(&actions[0], false, 1.06, 2.0);
+    assert_place(&actions[1], true, 1.03, 1.2);
+    let placed: Vec<ClientOrderId> = actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::Place {
+                client_order_id, ..
+            } => Some(*client_order_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        placed,
+        [
+            ClientOrderId(u64::MAX / 2 + 1),
+            ClientOrderId(u64::MAX / 2 + 2)
+        ]
+    );
+}
+
+#[test]
+fn test_plan_modifies_before_cancelling() {
+    let params = QuotingParams::new(0.01, 0.1, 0.5);
+    let live = [
+        order(1, true, 1.00, 1.0),
+        order(2, true, 1.00, 1.0),
+        order(3, false, 1.10, 1.0),
+    ];
+    let desired = [Quote::bid(1.00, 1.6), Quote::ask(1.20, 1.0)];
+
+    let mut ids = ClientOrderIdGenerator::new();
+    let actions = plan_actions(
+        &desired,
+        &live,
+        &book(0.95, 1.25),
+        &Inventory::default(),
+        &params,
+        &mut ids,
+    );
+
+    assert_eq!(actions.len(), 3);
+    assert_eq!(actions[0], Action::Cancel { order_id: 3 });
+    let Action::Modify { order_id, quantity } = actions[1] else {
+        panic!("Expected a modify, got {:?}", actions[1]);
+    };
+    assert_eq!(order_id, 2);
+    assert!((quantity - 0.6).abs() < 1e-9);
+    assert_place(&actions[2], false, 1.20, 1.0);
+
+    // Shrinking the newest order below the minimum cancels it instead
+    let actions = plan_actions(
+        &[Quote::bid(1.00, 1.3)],
+        &live[..2],
+        &book(0.95, 1.25),
+        &Inventory::default(),
+        &params,
+        &mut ids,
+    );
+    assert_eq!(actions, vec![Action::Cancel { order_id: 2 }]);
+}
+
+#[test]
+fn test_plan_respects_inventory_limits() {
+    let mut params = QuotingParams::new(0.01, 0.1, 0.5);
+    params.max_base = 11.0;
+    params.min_base = 9.4;
+    let inventory = Inventory {
+        base: 10.0,
+        quote: 100.0,
+        ..Default::default()
+    };
+    let desired = [
+        Quote::bid(0.99, 0.8),
+        Quote::bid(1.00, 0.8),
+        Quote::ask(1.10, 1.0),
+    ];
+
+    let mut ids = ClientOrderIdGenerator::new();
+    let actions = plan_actions(
+        &desired,
+        &[],
+        &book(0.95, 1.25),
+        &inventory,
+        &params,
+        &mut ids,
+    );
+
+    // The best bid keeps its size, leaving too little room for the other
+    assert_eq!(actions.len(), 2);
+    assert_place(&actions[0], false, 1.10, 0.6);
+    assert_place(&actions[1], true, 1.00, 0.8);
+}
+
+#[test]
+fn test_plan_counts_locked_base_and_free_quote() {
+    let mut params = QuotingParams::new(0.01, 0.1, 0.5);
+    params.min_base = 9.0;
+    // 1.0 base is locked in the resting ask
+    let inventory = Inventory {
+        base: 9.0,
+        quote: 0.5,
+        locked_base: 1.0,
+        locked_quote: 0.0,
+    };
+    let live = [order(1, false, 1.10, 1.0)];
+    let desired = [Quote::ask(1.10, 1.0), Quote::bid(1.00, 1.0)];
+
+    let mut ids = ClientOrderIdGenerator::new();
+    let actions = plan_actions(
+        &desired,
+        &live,
+        &book(0.95, 1.25),
+        &inventory,
+        &params,
+        &mut ids,
+    );
+
+    // The resting ask stays and the bid is cut to what 0.5 quote buys
+    assert_eq!(actions.len(), 1);
+    assert_place(&actions[0], true, 1.00, 0.5);
+}
+
+#[tokio::test]
+async fn test_engine_requotes_only_on_change() {
+    let exchange =
+        SimulatedExchange::new("SUI_DBUSDC", "SUI", "DBUSDC", book(0.99, 1.01), funded());
+    let quoter = SymmetricQuoter {
+        half_spread: 0.005,
+        step: 0.002,
+        levels: 2,
+        size: 1.0,
+    };
+    let mut engine = Engine::new(quoter, exchange, QuotingParams::new(0.001, 0.1, 0.1));
+
+    let actions = engine.tick(0).await.unwrap();
+    assert_eq!(actions.len(), 4);
+    assert_eq!(engine.exchange.orders().len(), 4);
+
+    // Our own quotes don't move mid
+    assert_eq!(engine.tick(1).await.unwrap(), vec![]);
+
+    // A partial fill is topped up at the same level
+    assert!((engine.exchange.take(true, 0.4, 1.01) - 0.4).abs() < 1e-9);
+    let actions = engine.tick(2).await.unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_place(&actions[0], false, 1.005, 0.4);
+    assert!((engine.exchange.inventory.base - 99.6).abs() < 1e-9);
+
+    // A new mid replaces every quote in one transaction
+    engine.exchange.external = book(1.09, 1.11);
+    let actions = engine.tick(3).await.unwrap();
+    let cancels = actions
+        .iter()
+        .filter(|action| matches!(action, Action::Cancel { .. }))
+        .count();
+    assert_eq!(cancels, 5);
+    assert_eq!(actions.len(), 9);
+    assert_eq!(engine.exchange.transactions.len(), 3);
+}
+
+#[tokio::test]
+async fn test_engine_leaves_other_orders_alone() {
+    let mut exchange =
+        SimulatedExchange::new("SUI_DBUSDC", "SUI", "DBUSDC", book(0.99, 1.01), funded());
+    let params = QuotingParams::new(0.001, 0.1, 0.1);
+    // A manual order resting where the quoter wants nothing
+    let manual = Action::Place {
+        quote: Quote::bid(0.9, 1.0),
+        client_order_id: ClientOrderId(1),
+    };
+    exchange.execute(&[manual], &params).await.unwrap();
+    let quoter = SymmetricQuoter {
+        half_spread: 0.005,
+        step: 0.002,
+        levels: 1,
+        size: 1.0,
+    };
+    let mut engine = Engine::new(quoter, exchange, params);
+
+    let actions = engine.tick(0).await.unwrap();
+    assert_eq!(actions.len(), 2);
+    assert!(
+        actions
+            .iter()
+            .all(|action| matches!(action, Action::Place { .. }))
+    );
+    assert_eq!(engine.exchange.orders().len(), 3);
+    assert_eq!(engine.tick(1).await.unwrap(), vec![]);
+}
+
+#[tokio::test]
+async fn test_simulated_exchange_is_atomic() {
+    let mut exchange = SimulatedExchange::new(
+        "SUI_DBUSDC",
+        "SUI",
+        "DBUSDC",
+        book(0.99, 1.01),
+        Inventory::default(),
+    );
+    let params = QuotingParams::new(0.01, 0.1, 0.1);
+    let actions = [
+        Action::Place {
+            quote: Quote::bid(0.98, 1.0),
+            client_order_id: ClientOrderId(1),
+        },
+        Action::Place {
+            quote: Quote::bid(1.02, 1.0),
+            client_order_id: ClientOrderId(2),
+        },
+    ];
+
+    assert!(exchange.execute(&actions, &params).await.is_err());
+    assert!(exchange.orders().is_empty());
+    assert!(exchange.transactions.is_empty());
+
+    exchange.execute(&actions[..1], &params).await.unwrap();
+    assert_eq!(exchange.orders().len(), 1);
+}
+
+fn simulated(bids: Vec<(f64, f64)>, asks: Vec<(f64, f64)>) -> SimulatedExchange {
+    SimulatedExchange::new(
+        "SUI_DBUSDC",
+        "SUI",
+        "DBUSDC",
+        BookSnapshot {
+            bids,
+            asks,
+            timestamp_ms: 0,
+        },
+        funded(),
+    )
+}
+
+#[tokio::test]
+async fn test_twap_catches_up_with_its_schedule() {
+    let exchange = simulated(vec![(0.99, 100.0)], vec![(1.01, 5.0), (1.02, 100.0)]);
+    let parent = ParentOrder {
+        is_bid: true,
+        quantity: 10.0,
+        limit_price: None,
+    };
+    let twap = Algorithm::Twap {
+        duration: Duration::from_secs(4),
+        slices: 4,
+        passive: false,
+    };
+    let params = QuotingParams::new(0.01, 0.1, 0.5);
+    let mut executor = Executor::new(exchange, params, parent, twap).unwrap();
+
+    let actions = executor.tick(0).await.unwrap();
+    assert_eq!(actions.len(), 1);
+    let Action::Take { quantity, .. } = actions[0] else {
+        panic!("Expected a take, got {:?}", actions[0]);
+    };
+    assert!((quantity - 2.5).abs() < 1e-9);
+    assert_eq!(executor.tick(500).await.unwrap(), vec![]);
+
+    // A late tick sends the two slices due at once
+    let actions = executor.tick(2_500).await.unwrap();
+    let Action::Take { quantity, .. } = actions[0] else {
+        panic!("Expected a take, got {:?}", actions[0]);
+    };
+    assert!((quantity - 5.0).abs() < 1e-9);
+    assert_eq!(executor.tick(3_000).await.unwrap().len(), 1);
+    assert_eq!(executor.tick(4_000).await.unwrap(), vec![]);
+    assert!(executor.is_done());
+
+    let report = executor.report();
+    assert!((report.filled - 10.0).abs() < 1e-9);
+    assert_eq!(report.children, 3);
+    assert_eq!(report.arrival_mid, Some(1.0));
+    assert!((report.average_price().unwrap() - 1.015).abs() < 1e-9);
+    assert!((report.slippage_bps().unwrap() - 150.0).abs() < 1e-6);
+    assert_eq!(executor.exchange.transactions.len(), 3);
+}
+
+#[tokio::test]
+async fn test_passive_twap_requotes_at_the_touch() {
+    let exchange = simulated(vec![(0.99, 100.0)], vec![(1.01, 100.0)]);
+    let parent = ParentOrder {
+        is_bid: false,
+        quantity: 4.0,
+        limit_price: Some(1.0),
+    };
+    let twap = Algorithm::Twap {
+        duration: Duration::from_secs(2),
+        slices: 2,
+        passive: true,
+    };
+    let params = QuotingParams::new(0.01, 0.1, 0.5);
+    let mut executor = Executor::new(exchange, params, parent, twap).unwrap();
+
+    let actions = executor.tick(0).await.unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_place(&actions[0], false, 1.01, 2.0);
+    assert!((executor.exchange.take(true, 0.5, 1.01) - 0.5).abs() < 1e-9);
+
+    // The rest of the first slice is replaced together with the second
+    let actions = executor.tick(1_000).await.unwrap();
+    assert_eq!(actions.len(), 2);
+    assert!(matches!(actions[0], Action::Cancel { .. }));
+    assert_place(&actions[1], false, 1.01, 3.5);
+
+    // Out of time: the resting child is cancelled and the rest left undone
+    let actions = executor.tick(2_000).await.unwrap();
+    assert!(matches!(actions[..], [Action::Cancel { .. }]));
+    assert!(executor.is_done());
+    assert!(executor.exchange.orders().is_empty());
+    assert!((executor.remaining() - 3.5).abs() < 1e-9);
+    assert!((executor.report().slippage_bps().unwrap() + 100.0).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn test_passive_twap_does_not_resend_unreported_fills() {
+    let exchange = simulated(vec![(0.99, 100.0)], vec![(1.01, 100.0)]);
+    let parent = ParentOrder {
+        is_bid: false,
+        quantity: 4.0,
+        limit_price: Some(1.0),
+    };
+    let twap = Algorithm::Twap {
+        duration: Duration::from_secs(2),
+        slices: 2,
+        passive: true,
+    };
+    let params = QuotingParams::new(0.01, 0.1, 0.5);
+    let mut executor = Executor::new(exchange, params, parent, twap).unwrap();
+
+    executor.tick(0).await.unwrap();
+    assert!((executor.exchange.take(true, 0.5, 1.01) - 0.5).abs() < 1e-9);
+    // The child shrank on chain but its fill was never reported
+    assert_eq!(executor.exchange.fills().await.unwrap().len(), 1);
+
+    let actions = executor.tick(1_000).await.unwrap();
+    assert_eq!(actions.len(), 2);
+    assert_place(&actions[1], false, 1.01, 3.5);
+    assert_eq!(executor.report().filled, 0.0);
+}
+
+#[tokio::test]
+async fn test_iceberg_shows_one_clip_at_a_time() {
+    let exchange = simulated(vec![(0.99, 100.0)], vec![(1.10, 100.0)]);
+    let parent = ParentOrder {
+        is_bid: false,
+        quantity: 2.5,
+        limit_price: Some(1.05),
+    };
+    let params = QuotingParams::new(0.01, 0.1, 0.5);
+    let iceberg = Algorithm::Iceberg { clip: 1.0 };
+    let mut executor = Executor::new(exchange, params, parent, iceberg).unwrap();
+
+    let actions = executor.tick(0).await.unwrap();
+    assert_place(&actions[0], false, 1.05, 1.0);
+    assert_eq!(executor.tick(1).await.unwrap(), vec![]);
+
+    // A partially filled clip is left alone
+    executor.exchange.take(true, 0.6, 1.05);
+    assert_eq!(executor.tick(2).await.unwrap(), vec![]);
+    executor.exchange.take(true, 0.4, 1.05);
+    let actions = executor.tick(3).await.unwrap();
+    assert_place(&actions[0], false, 1.05, 1.0);
+
+    // The last clip is what remains
+    assert!((executor.exchange.take(true, 5.0, 1.05) - 1.0).abs() < 1e-9);
+    let actions = executor.tick(4).await.unwrap();
+    assert_place(&actions[0], false, 1.05, 0.5);
+    executor.exchange.take(true, 5.0, 1.05);
+    assert_eq!(executor.tick(5).await.unwrap(), vec![]);
+
+    assert!(executor.is_done());
+    assert_eq!(executor.report().children, 3);
+    assert!((executor.report().filled - 2.5).abs() < 1e-9);
+    assert!((executor.report().average_price().unwrap() - 1.05).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_iceberg_replaces_a_clip_cancelled_elsewhere() {
+    let exchange = simulated(vec![(0.99, 100.0)], vec![(1.10, 100.0)]);
+    let parent = ParentOrder {
+        is_bid: false,
+        quantity: 2.0,
+        limit_price: Some(1.05),
+    };
+    let params = QuotingParams::new(0.01, 0.1, 0.5);
+    let iceberg = Algorithm::Iceberg { clip: 1.0 };
+    let mut executor = Executor::new(exchange, params, parent, iceberg).unwrap();
+
+    executor.tick(0).await.unwrap();
+    executor.exchange.take(true, 0.4, 1.05);
+    // e.g. the kill switch pulls the clip
+    let order_id = executor.exchange.orders()[0].order_id;
+    executor
+        .exchange
+        .execute(&[Action::Cancel { order_id }], &params)
+        .await
+        .unwrap();
+
+    // The rest is written off once the clip is gone and no more fills come
+    assert_eq!(executor.tick(1).await.unwrap(), vec![]);
+    let actions = executor.tick(2).await.unwrap();
+    assert_place(&actions[0], false, 1.05, 1.0);
+    assert!((executor.remaining() - 1.6).abs() < 1e-9);
+
+    // Past the deadline the resting clip is cancelled
+    let report = executor
+        .run(Duration::from_millis(1), Duration::ZERO)
+        .await
+        .unwrap();
+    assert!(executor.is_done());
+    assert!(executor.exchange.orders().is_empty());
+    assert_eq!(report.children, 2);
+}
+
+#[tokio::test]
+async fn test_post_only_iceberg_waits_instead_of_crossing() {
+    let exchange = simulated(vec![(1.06, 100.0)], vec![(1.10, 100.0)]);
+    let parent = ParentOrder {
+        is_bid: false,
+        quantity: 2.0,
+        limit_price: Some(1.05),
+    };
+    let params = QuotingParams::new(0.01, 0.1, 0.5);
+    let iceberg = Algorithm::Iceberg { clip: 1.0 };
+    let mut executor = Executor::new(exchange, params, parent, iceberg).unwrap();
+
+    assert_eq!(executor.tick(0).await.unwrap(), vec![]);
+    assert!(!executor.is_done());
+    assert!(executor.exchange.transactions.is_empty());
+
+    executor.exchange.external = book(0.99, 1.10);
+    let actions = executor.tick(1).await.unwrap();
+    assert_place(&actions[0], false, 1.05, 1.0);
+}
+
+#[test]
+fn test_executor_rejects_unworkable_orders() {
+    let params = QuotingParams::new(0.01, 0.1, 0.5);
+    let exchange = || simulated(vec![(0.99, 100.0)], vec![(1.01, 100.0)]);
+    let parent = ParentOrder {
+        is_bid: true,
+        quantity: 10.0,
+        limit_price: None,
+    };
+
+    let iceberg = Algorithm::Iceberg { clip: 1.0 };
+    assert!(Executor::new(exchange(), params, parent, iceberg).is_err());
+    let priced = ParentOrder {
+        limit_price: Some(1.0),
+        ..parent
+    };
+    let small_clip = Algorithm::Iceberg { clip: 0.2 };
+    assert!(Executor::new(exchange(), params, priced, small_clip).is_err());
+    let no_slices = Algorithm::Twap {
+        duration: Duration::from_secs(1),
+        slices: 0,
+        passive: false,
+    };
+    assert!(Executor::new(exchange(), params, parent, no_slices).is_err());
+    assert!(Executor::new(exchange(), params, priced, iceberg).is_ok());
+}
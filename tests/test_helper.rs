@@ -6,7 +6,7 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use sui_config::{SUI_KEYSTORE_FILENAME, sui_config_dir};
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
-use sui_sdk::rpc_types::SuiTransactionBlockResponseOptions;
+use sui_sdk::rpc_types::{SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions};
 use sui_sdk::types::base_types::{ObjectRef, SuiAddress};
 use sui_sdk::types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_sdk::types::transaction::{Transaction, TransactionData};
@@ -22,7 +22,8 @@ pub async fn setup_client() -> Result<(SuiClient, SuiAddress, DeepBookClient)> {
     let balance_managers = HashMap::from([(
         "MANAGER_2".to_string(),
         BalanceManager {
-            address: "0x08933685e0246a2ddae2f5e5628fdeba09de831cadf5ad949db308807f18bee5", // balance_manager for testnet
+            address: "0x08933685e0246a2ddae2f5e5628fdeba09de831cadf5ad949db308807f18bee5"
+                .to_string(), // balance_manager for testnet
             // address: "0x73e7bc2f1007a4f1ffcc42af9305e4e7ce16274297e2e513b2503b9c85c287d4", // balance_manager for devnet
             trade_cap: None,
             deposit_cap: None,
@@ -66,7 +67,7 @@ pub async fn sign_and_execute(
     client: &SuiClient,
     sender: SuiAddress,
     tx_data: TransactionData,
-) -> Result<()> {
+) -> Result<SuiTransactionBlockResponse> {
     let keystore = FileBasedKeystore::new(&sui_config_dir()?.join(SUI_KEYSTORE_FILENAME))?;
     let signature = keystore.sign_secure(&sender, &tx_data, Intent::sui_transaction())?;
 
@@ -91,7 +92,7 @@ pub async fn sign_and_execute(
                 );
                 println!("Transaction Successful: {:?}", response);
 
-                return Ok(());
+                return Ok(response);
             }
             Err(e) => {
                 if e.to_string().contains("reserved for another transaction") {
@@ -0,0 +1,211 @@
+use deepbookv3::triggers::{
+    MarketPrices, PriceSource, TriggerCondition, TriggerExecution, TriggerService, TriggerSpec,
+    ioc_limit_price,
+};
+use deepbookv3::types::BookParams;
+
+const BOOK: BookParams = BookParams {
+    tick_size: 0.01,
+    lot_size: 0.1,
+    min_size: 1.0,
+};
+
+fn spec(is_bid: bool, condition: TriggerCondition) -> TriggerSpec {
+    TriggerSpec {
+        pool_key: "SUI_DBUSDC".to_string(),
+        manager_key: "MANAGER_1".to_string(),
+        is_bid,
+        quantity: 1.0,
+        condition,
+        source: PriceSource::Mid,
+        execution: TriggerExecution::Market,
+        pay_with_deep: true,
+    }
+}
+
+fn mid(price: f64) -> MarketPrices {
+    MarketPrices {
+        mid: price,
+        best_bid: Some(price - 0.01),
+        best_ask: Some(price + 0.01),
+    }
+}
+
+fn open_service(name: &str) -> (TriggerService, std::path::PathBuf) {
+    let path = std::env::temp_dir().join(format!("deepbook_{}_{}.json", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    (TriggerService::open(&path).unwrap(), path)
+}
+
+#[test]
+fn test_stop_and_take_profit_fire_on_their_side() {
+    let (mut service, path) = open_service("triggers_stop");
+    let sell_stop = service
+        .add(
+            spec(false, TriggerCondition::StopLoss { price: 1.0 }),
+            &BOOK,
+        )
+        .unwrap();
+    let sell_target = service
+        .add(
+            spec(false, TriggerCondition::TakeProfit { price: 1.5 }),
+            &BOOK,
+        )
+        .unwrap();
+    let buy_stop = service
+        .add(spec(true, TriggerCondition::StopLoss { price: 1.5 }), &BOOK)
+        .unwrap();
+    let buy_target = service
+        .add(
+            spec(true, TriggerCondition::TakeProfit { price: 1.0 }),
+            &BOOK,
+        )
+        .unwrap();
+
+    assert!(service.evaluate("SUI_DBUSDC", &mid(1.2)).is_empty());
+    assert!(service.evaluate("OTHER_POOL", &mid(0.5)).is_empty());
+    assert_eq!(
+        service.evaluate("SUI_DBUSDC", &mid(1.0)),
+        [sell_stop, buy_target]
+    );
+    assert_eq!(
+        service.evaluate("SUI_DBUSDC", &mid(1.6)),
+        [sell_target, buy_stop]
+    );
+
+    // Client order ids are distinct and increasing
+    let ids: Vec<_> = service
+        .triggers()
+        .iter()
+        .map(|t| t.client_order_id)
+        .collect();
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_trailing_stop_follows_the_best_price() {
+    let (mut service, path) = open_service("triggers_trailing");
+    let sell = service
+        .add(
+            spec(
+                false,
+                TriggerCondition::TrailingStop {
+                    distance: 0.1,
+                    extreme: None,
+                },
+            ),
+            &BOOK,
+        )
+        .unwrap();
+    let buy = service
+        .add(
+            spec(
+                true,
+                TriggerCondition::TrailingStop {
+                    distance: 0.1,
+                    extreme: None,
+                },
+            ),
+            &BOOK,
+        )
+        .unwrap();
+
+    assert!(service.evaluate("SUI_DBUSDC", &mid(1.0)).is_empty());
+    // The sell stop trails up to 1.2 - 0.1 while the buy stop stays at 1.0 + 0.1
+    assert_eq!(service.evaluate("SUI_DBUSDC", &mid(1.2)), [buy]);
+    assert_eq!(service.evaluate("SUI_DBUSDC", &mid(1.15)), [buy]);
+    assert_eq!(service.evaluate("SUI_DBUSDC", &mid(1.09)), [sell]);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_touch_source_uses_the_side_the_order_hits() {
+    let (mut service, path) = open_service("triggers_touch");
+    let mut sell = spec(false, TriggerCondition::StopLoss { price: 1.0 });
+    sell.source = PriceSource::Touch;
+    let id = service.add(sell, &BOOK).unwrap();
+
+    // Mid is above the stop but the best bid is not
+    assert_eq!(service.evaluate("SUI_DBUSDC", &mid(1.005)), [id]);
+
+    // No bids at all never fires
+    let empty = MarketPrices {
+        mid: 0.5,
+        best_bid: None,
+        best_ask: Some(0.6),
+    };
+    assert!(service.evaluate("SUI_DBUSDC", &empty).is_empty());
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_triggers_survive_restarts() {
+    let (mut service, path) = open_service("triggers_restart");
+    let kept = service
+        .add(
+            spec(
+                false,
+                TriggerCondition::TrailingStop {
+                    distance: 0.1,
+                    extreme: None,
+                },
+            ),
+            &BOOK,
+        )
+        .unwrap();
+    let cancelled = service
+        .add(spec(true, TriggerCondition::StopLoss { price: 2.0 }), &BOOK)
+        .unwrap();
+    service.evaluate("SUI_DBUSDC", &mid(1.3));
+    service.save().unwrap();
+    assert!(service.cancel(cancelled).unwrap().is_some());
+    assert!(service.cancel(cancelled).unwrap().is_none());
+
+    let mut reopened = TriggerService::open(&path).unwrap();
+    assert_eq!(reopened.triggers(), service.triggers());
+    assert_eq!(
+        reopened.triggers()[0].spec.condition,
+        TriggerCondition::TrailingStop {
+            distance: 0.1,
+            extreme: Some(1.3),
+        }
+    );
+
+    // Ids keep increasing after the restart
+    let added = reopened
+        .add(spec(true, TriggerCondition::StopLoss { price: 2.0 }), &BOOK)
+        .unwrap();
+    assert!(added > cancelled && added > kept);
+    let triggers = reopened.triggers();
+    assert!(triggers[1].client_order_id > triggers[0].client_order_id);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_add_rejects_quantities_the_pool_would_abort() {
+    let (mut service, path) = open_service("triggers_sizes");
+    let mut off_lot = spec(false, TriggerCondition::StopLoss { price: 1.0 });
+    off_lot.quantity = 1.05;
+    assert!(service.add(off_lot, &BOOK).is_err());
+
+    let mut below_min = spec(false, TriggerCondition::StopLoss { price: 1.0 });
+    below_min.quantity = 0.9;
+    assert!(service.add(below_min, &BOOK).is_err());
+
+    let mut on_lot = spec(false, TriggerCondition::StopLoss { price: 1.0 });
+    on_lot.quantity = 1.3;
+    assert!(service.add(on_lot, &BOOK).is_ok());
+    assert_eq!(service.triggers().len(), 1);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn test_ioc_limit_price_allows_slippage_on_ticks() {
+    assert!((ioc_limit_price(true, 1.0, 0.02, 0.01) - 1.02).abs() < 1e-9);
+    assert!((ioc_limit_price(false, 1.0, 0.02, 0.01) - 0.98).abs() < 1e-9);
+    assert!((ioc_limit_price(true, 1.003, 0.0, 0.01) - 1.01).abs() < 1e-9);
+    assert!((ioc_limit_price(false, 1.007, 0.0, 0.01) - 1.0).abs() < 1e-9);
+    // Never below one tick
+    assert!((ioc_limit_price(false, 0.01, 0.5, 0.01) - 0.01).abs() < 1e-9);
+}